@@ -1,7 +1,13 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
+use skat_aug23::skat::context::GameContext;
 use skat_aug23::skat::defs::{Game, Player};
+use skat_aug23::skat::engine::SkatEngine;
+use skat_aug23::skat::scoring::Contract;
+use skat_aug23::skat::solved::SolvedStore;
+use skat_aug23::skat::tt::{ReplacementPolicy, TranspositionTable};
 
 #[derive(Parser)]
 #[command(
@@ -32,6 +38,26 @@ EXAMPLES:
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    /// Seed of all random deals, samples and playouts; a run is repeated
+    /// exactly with the same seed. Random (and printed) if not given
+    #[arg(long, global = true)]
+    pub seed: Option<u64>,
+    /// Adaptive sampling of the PIMC card choices: after --samples, more
+    /// samples are drawn until the best card's lead is significant or this
+    /// many samples are used
+    #[arg(long, global = true)]
+    pub max_samples: Option<u32>,
+    /// Chooses the cards of the PIMC commands and playouts by an alpha-mu
+    /// search of this many own moves over the samples (at most 64) instead
+    /// of solving every sample on its own
+    #[arg(long, global = true)]
+    pub alpha_mu: Option<u8>,
+    /// Agents of the playouts (playout, points-playout, smart-points-playout,
+    /// null-playout, ramsch-playout): one kind for all seats or three for declarer, left and
+    /// right, e.g. "perfect,pimc,pimc". The tournament takes its two or more
+    /// competing kinds. Kinds: perfect, pimc, pimc-points, ismcts, random, rules
+    #[arg(long, global = true)]
+    pub agents: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -42,9 +68,12 @@ pub enum Commands {
         /// Path to the JSON context file
         #[arg(short, long)]
         context: String,
-        /// Optimum mode: "best_value" or "all_winning"
+        /// Optimum mode: "best_value" or "all_winning"; with suffix "_mtdf" the
+        /// exact values are found with zero-window probes
         #[arg(long)]
         optimum_mode: Option<String>,
+        #[command(flatten)]
+        engine: EngineArgs,
     },
     /// Simulates the game using Perfect Information Monte Carlo (PIMC).
     /// It samples possible hidden card distributions to handle incomplete information and plays out the game move-by-move. This is closest to a "real" AI player.
@@ -62,7 +91,7 @@ pub enum Commands {
         #[arg(short, long, default_value_t = 20)]
         samples: u32,
         /// Sampling mode for PIMC: "random" (default), "smart-grand" or "smart-suit"
-        /// (Filters out unplayable hands for the declarer).
+        /// (samples with an unplayable hand for the declarer weigh 1%).
         #[arg(short, long, default_value = "random")]
         distribution: String,
     },
@@ -83,7 +112,7 @@ pub enum Commands {
         #[arg(short, long, default_value_t = 20)]
         samples: u32,
         /// Sampling mode for PIMC: "random" (default), "smart-grand" or "smart-suit"
-        /// (Filters out unplayable hands for the declarer).
+        /// (samples with an unplayable hand for the declarer weigh 1%).
         #[arg(short, long, default_value = "random")]
         distribution: String,
         /// Selection strategy: "average" (default), "minimum", or "hybrid".
@@ -102,6 +131,12 @@ pub enum Commands {
         /// Point-threshold for the trump move heuristic (default 2.0).
         #[arg(long, default_value_t = 2.0)]
         trump_heuristic_threshold: f32,
+        /// Estimates the card metrics with this many ISMCTS iterations instead of PIMC samples.
+        #[arg(long)]
+        ismcts: Option<u32>,
+        /// Rollout policy of ISMCTS: "random" (default), "heuristic" or "double-dummy".
+        #[arg(long, default_value = "random")]
+        rollout: String,
     },
     /// Like PointsPlayout, but ONLY simulates "interesting" games:
     /// auto-generates a random deal, runs perfect-information best-game
@@ -121,6 +156,12 @@ pub enum Commands {
         /// Fallback strategy for hybrid mode: "average" or "minimum".
         #[arg(long, default_value = "average")]
         hybrid_fallback: String,
+        /// Estimates the card metrics with this many ISMCTS iterations instead of PIMC samples.
+        #[arg(long)]
+        ismcts: Option<u32>,
+        /// Rollout policy of ISMCTS: "random" (default), "heuristic" or "double-dummy".
+        #[arg(long, default_value = "random")]
+        rollout: String,
     },
     /// Null-specific PIMC playout. Uses a 3-tier card selection strategy:
     /// (1) PIMC win probability, (2) trick-analysis tiebreaker (declarer avoids,
@@ -133,9 +174,48 @@ pub enum Commands {
         #[arg(short, long)]
         context: Option<String>,
         /// Sampling mode for PIMC: "random" (default) or "likely-null"
-        /// (only deals where opponents hold plausible Null hands are accepted).
+        /// (the random deal gives the opponents plausible Null hands, samples
+        /// without them weigh 1%).
+        #[arg(short, long, default_value = "random")]
+        distribution: String,
+    },
+    /// Simulates a Ramsch (all players passed), by default with PIMC for all three players.
+    /// Each player minimizes his own points; the result is settled with Jungfrau and Durchmarsch.
+    RamschPlayout {
+        /// Number of PIMC samples to run per move (default: 20)
+        #[arg(short, long, default_value_t = 20)]
+        samples: u32,
+        /// Path to a JSON context file (optional). If omitted, a random deal is generated.
+        #[arg(short, long)]
+        context: Option<String>,
+    },
+    /// Duplicate tournament of the agents given with --agents (two or more
+    /// kinds, default "pimc,ismcts"): every deal is played under every seating
+    /// of every three agents and scored in the Seeger-Fabian system.
+    Tournament {
+        /// Number of deals
+        #[arg(long, default_value_t = 10)]
+        deals: u32,
+        /// Number of PIMC samples to run per move (default: 20)
+        #[arg(short, long, default_value_t = 20)]
+        samples: u32,
+        /// Game Type: "grand", "null", "clubs"
+        #[arg(long, default_value = "grand")]
+        game_type: String,
+        /// Deals of the tournament: "random" (default), "smart-grand" or "smart-suit"
+        /// (only playable hands for the declarer), also used by the PIMC sampling,
+        /// where samples with other declarer hands weigh 1%.
         #[arg(short, long, default_value = "random")]
         distribution: String,
+        /// Iterations of the "ismcts" agent (default: 1000).
+        #[arg(long)]
+        ismcts: Option<u32>,
+        /// Rollout policy of ISMCTS: "random" (default), "heuristic" or "double-dummy".
+        #[arg(long, default_value = "random")]
+        rollout: String,
+        /// Report file of all games: JSON if the name ends with ".json", else CSV
+        #[arg(short, long)]
+        output: Option<String>,
     },
     /// Plays out the game from the given state using Perfect Information.
     /// It assumes all cards are known to all players (open hand) and executes the optimal line of play to determine the final score.
@@ -157,6 +237,8 @@ pub enum Commands {
         /// Path to the JSON context file
         #[arg(short, long)]
         context: String,
+        #[command(flatten)]
+        engine: EngineArgs,
     },
     /// Evaluates the best Skat discard for a 12-card hand.
     /// It iterates through all possible 2-card discards and solves the resulting 10-card game to find the discard that maximizes the game value.
@@ -203,7 +285,8 @@ pub enum Commands {
         /// Path to the JSON context file
         #[arg(short, long)]
         context: String,
-        /// Calculation mode: "best" or "win"
+        /// Calculation mode: "best", "metrics" (win probability and points of every card with
+        /// confidence intervals), "win" or "doubling" (advise Kontra/Re, uses the bid as game value)
         #[arg(short, long, default_value = "win")]
         mode: String,
         /// Optional path to a log file to write sample details to
@@ -224,6 +307,23 @@ pub enum Commands {
         #[arg(long)]
         log_file: Option<String>,
     },
+    /// Recommends how high to bid with a 10-card hand before the skat is picked up.
+    /// Samples possible skats, finds the best discard for every game type and estimates the win
+    /// probabilities with PIMC. The highest game value reached with at least --min-probability is the limit.
+    BidAdvice {
+        /// Path to the JSON context file (10 cards in "my_cards")
+        #[arg(short, long)]
+        context: String,
+        /// Number of PIMC samples per discard evaluation
+        #[arg(short, long, default_value_t = 20)]
+        samples: u32,
+        /// Number of random skats to pick up
+        #[arg(long, default_value_t = 5)]
+        skat_samples: u32,
+        /// Minimum win probability for a contract to be bid
+        #[arg(long, default_value_t = 0.6)]
+        min_probability: f32,
+    },
     /// Analyzes Grand Hand scenarios to find the probability of winning based on hand features.
     /// Generates random hands and calculates the "Signature" of the hand (Jacks, Aces, Tens), then runs PIMC simulations.
     /// Outputs the results to CSV format.
@@ -335,6 +435,96 @@ pub enum Commands {
     },
 }
 
+/// Search settings of the perfect information solver.
+#[derive(Args, Debug)]
+pub struct EngineArgs {
+    /// Number of search threads sharing one transposition table (0: one per core)
+    #[arg(long, default_value_t = 1)]
+    pub threads: usize,
+    /// Transposition table size in MiB (default: 2^20 entries)
+    #[arg(long)]
+    pub tt_mb: Option<usize>,
+    /// Transposition table replacement policy: "depth" or "age"
+    #[arg(long, default_value = "depth")]
+    pub tt_policy: String,
+    /// Prints transposition table statistics (fill, hit and collision rates)
+    #[arg(long, default_value_t = false)]
+    pub tt_stats: bool,
+    /// Transposition table file: loaded before the search if present, saved afterwards
+    #[arg(long)]
+    pub tt_file: Option<String>,
+    /// Store of solved positions: seeds the table if present, collects new positions afterwards
+    #[arg(long)]
+    pub solved_file: Option<String>,
+}
+
+impl EngineArgs {
+    pub fn create_engine(&self, context: GameContext) -> Result<SkatEngine, &'static str> {
+        let policy: ReplacementPolicy = self.tt_policy.parse()?;
+        let tt = match self.tt_mb {
+            Some(megabytes) => TranspositionTable::with_megabytes(megabytes, policy),
+            None => TranspositionTable::with_capacity(skat_aug23::consts::general::TT_SIZE, policy),
+        };
+
+        let mut engine = SkatEngine::new(context, Some(tt));
+        engine.set_threads(self.threads)?;
+
+        // Missing, stale or foreign files are ignored and overwritten later.
+        if let Some(path) = self.tt_file.as_deref().filter(|p| Path::new(p).exists()) {
+            match engine.load_tt(path) {
+                Ok(n) => println!("Loaded {} table entries from {}", n, path),
+                Err(e) => eprintln!("Ignoring table file {}: {}", path, e),
+            }
+        }
+        if let Some(store) = self.load_solved(&engine) {
+            engine.seed_solved(&store)?;
+            println!("Seeded {} solved positions", store.len());
+        }
+
+        Ok(engine)
+    }
+
+    /// Saves the table and the solved positions after the search.
+    pub fn persist(&self, engine: &SkatEngine) -> Result<(), &'static str> {
+        if let Some(path) = &self.tt_file {
+            let n = engine
+                .save_tt(path)
+                .map_err(|_| "Unable to write the table file")?;
+            println!("Saved {} table entries to {}", n, path);
+        }
+        if let Some(path) = &self.solved_file {
+            let mut store = self
+                .load_solved(engine)
+                .unwrap_or_else(|| SolvedStore::new(engine.table_key()));
+            let added = engine.harvest_solved(&mut store)?;
+            store
+                .save_to_file(path)
+                .map_err(|_| "Unable to write the solved positions file")?;
+            println!(
+                "Saved {} solved positions ({} new) to {}",
+                store.len(),
+                added,
+                path
+            );
+        }
+        Ok(())
+    }
+
+    fn load_solved(&self, engine: &SkatEngine) -> Option<SolvedStore> {
+        let path = self.solved_file.as_deref()?;
+        if !Path::new(path).exists() {
+            return None;
+        }
+        match SolvedStore::load_from_file(engine.table_key(), path) {
+            Ok(store) => Some(store),
+            Err(e) => {
+                eprintln!("Ignoring solved positions file {}: {}", path, e);
+                None
+            }
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GameContextInput {
     pub declarer_cards: String,
@@ -348,6 +538,50 @@ pub struct GameContextInput {
     pub declarer_start_points: Option<u8>,
     pub samples: Option<u32>,
     pub god_players: Option<Vec<String>>,
+    pub contract: Option<ContractInput>,
+}
+
+/// Declared contract and bid, used to settle the game value. Kontra and Re
+/// also set the doubling of the game context.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ContractInput {
+    pub bid: Option<u16>,
+    pub hand: Option<bool>,
+    pub schneider_announced: Option<bool>,
+    pub schwarz_announced: Option<bool>,
+    pub ouvert: Option<bool>,
+    pub kontra: Option<bool>,
+    pub re: Option<bool>,
+}
+
+impl ContractInput {
+    /// Applies the announcements to `contract`. Re is rejected without a
+    /// Kontra.
+    pub fn apply(&self, mut contract: Contract) -> Result<Contract, &'static str> {
+        if self.hand.unwrap_or(false) {
+            contract = contract.hand();
+        }
+        if self.schneider_announced.unwrap_or(false) {
+            contract = contract.announce_schneider();
+        }
+        if self.schwarz_announced.unwrap_or(false) {
+            contract = contract.announce_schwarz();
+        }
+        if self.ouvert.unwrap_or(false) && !contract.game.is_null() {
+            contract = contract.ouvert();
+        }
+        if self.kontra.unwrap_or(false) {
+            contract = contract.kontra();
+        }
+        if self.re.unwrap_or(false) {
+            contract = contract.re()?;
+        }
+        Ok(contract)
+    }
+
+    pub fn bid(&self) -> u16 {
+        self.bid.unwrap_or(0)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -363,6 +597,12 @@ pub struct PimcFactsInput {
     pub no_spades: Option<bool>,
     pub no_hearts: Option<bool>,
     pub no_diamonds: Option<bool>,
+    /// Cards surely held, e.g. "CA CT".
+    pub has_cards: Option<String>,
+    /// Cards surely not held.
+    pub no_cards: Option<String>,
+    /// Card sets of which at least one card is held.
+    pub one_of: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -370,6 +610,7 @@ pub struct PimcPlayerFactsInput {
     pub declarer: Option<PimcFactsInput>,
     pub left: Option<PimcFactsInput>,
     pub right: Option<PimcFactsInput>,
+    pub skat: Option<PimcFactsInput>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -397,4 +638,7 @@ pub struct PimcContextInput {
     pub threshold: Option<u8>,
     pub samples: Option<u32>,
     pub facts: Option<PimcPlayerFactsInput>,
+    /// Bid and Kontra/Re state of the problem; the "doubling" mode advises
+    /// the next announcement.
+    pub contract: Option<ContractInput>,
 }
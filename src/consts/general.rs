@@ -3,18 +3,12 @@
 //! General constants used in core functions. Does not cover bitboard notation.
 //! See bitboard.rs for that.
 
-/// Value used in algorithm for calculating fast FNV hash.
-pub const HSH_INIT: u64 = 0xcbf29ce484222325u64;
+/// Seed of the generator for the Zobrist keys of positions.
+pub const ZOBRIST_SEED: u64 = 0xcbf29ce484222325u64;
 
-/// Value used in algorithm for calculating fast FNV hash.
-pub const HSH_MUL: u64 = 0x00000100000001B3;
-
-/// Size of transposition table as usize.
+/// Default number of transposition table entries (a power of two).
 pub const TT_SIZE: usize = 1024*1024usize;
 
-/// Size of transposition table transformed to u64 type.
-pub const TT_SIZE_U64: u64 = TT_SIZE as u64;
-
 /// Value of cards in bitboard notation from left to right. Standard order.
 /// Maps to indices 0..31
 pub const AUGENLIST: [u8; 32] = [
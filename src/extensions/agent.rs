@@ -0,0 +1,1256 @@
+//! # Agents
+//!
+//! The card players of the playout drivers. An agent sees the game from its
+//! seat, as an `Observation` of its hand, the trick, the cards played so far
+//! and the facts about the other hands, and chooses a card. The drivers seat
+//! any mix of agents, one per player.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::consts::bitboard::{
+    EIGHTS, JACKS, KINGS, NINES, NULL_CLUBS, NULL_DIAMONDS, NULL_HEARTS, NULL_SPADES, QUEENS,
+    SEVENS, TENS,
+};
+use crate::pimc::facts::FactsTracker;
+use crate::pimc::ismcts::{heuristic_card, IsmctsConfig, IsmctsSearch};
+use crate::pimc::pimc_problem::SamplingMode;
+use crate::pimc::pimc_problem_builder::PimcProblemBuilder;
+use crate::pimc::pimc_search::{AdaptiveSampling, Estimator, MoveMetrics, PimcSearch};
+use crate::skat::context::GameContext;
+use crate::skat::control::SearchControl;
+use crate::skat::counters::Counters;
+use crate::skat::defs::{Game, Player};
+use crate::skat::engine::SkatEngine;
+use crate::skat::position::Position;
+use crate::skat::rules::get_trick_winner;
+use crate::traits::{Bitboard, Points};
+
+/// Iterations of the ISMCTS agent if no configuration is given.
+const DEFAULT_ISMCTS_ITERATIONS: u32 = 1000;
+
+/// The game as the player to move sees it. The true deal is only open
+/// through `god_view`.
+#[derive(Clone, Copy)]
+pub struct Observation<'a> {
+    context: &'a GameContext,
+    position: &'a Position,
+    history: &'a [(Player, u32)],
+    facts: &'a FactsTracker,
+}
+
+impl<'a> Observation<'a> {
+    /// View of the player to move in `position` of the game `context`;
+    /// `history` are the cards played since `context` and `facts` what they
+    /// revealed.
+    pub fn new(
+        context: &'a GameContext,
+        position: &'a Position,
+        history: &'a [(Player, u32)],
+        facts: &'a FactsTracker,
+    ) -> Self {
+        Observation {
+            context,
+            position,
+            history,
+            facts,
+        }
+    }
+
+    pub fn game_type(&self) -> Game {
+        self.context.game_type()
+    }
+
+    pub fn player(&self) -> Player {
+        self.position.player
+    }
+
+    pub fn hand(&self) -> u32 {
+        self.position.player_cards
+    }
+
+    pub fn legal_moves(&self) -> u32 {
+        self.position.get_legal_moves()
+    }
+
+    /// Suit of the running trick, 0 when leading.
+    pub fn trick_suit(&self) -> u32 {
+        self.position.trick_suit
+    }
+
+    /// Cards of the running trick.
+    pub fn trick_cards(&self) -> u32 {
+        self.position.trick_cards
+    }
+
+    /// Cards played since the start of the game, in order.
+    pub fn history(&self) -> &'a [(Player, u32)] {
+        self.history
+    }
+
+    /// The cards of `history` in the running trick.
+    pub fn trick(&self) -> &'a [(Player, u32)] {
+        let n = (self.position.trick_cards_count as usize).min(self.history.len());
+        &self.history[self.history.len() - n..]
+    }
+
+    /// Card of `of` in the running trick, 0 if none. A trick card of the
+    /// starting context belongs to the player who led.
+    pub fn trick_card(&self, of: Player) -> u32 {
+        if let Some((_, card)) = self.trick().iter().find(|(player, _)| *player == of) {
+            return *card;
+        }
+
+        let known = self.trick().iter().fold(0, |cards, (_, card)| cards | card);
+        let before = self.position.trick_cards & !known;
+        let leader = match self.position.trick_cards_count {
+            2 => self.player().inc(),
+            _ => self.player().dec(),
+        };
+        if before.count_ones() == 1 && of == leader {
+            before
+        } else {
+            0
+        }
+    }
+
+    pub fn facts(&self) -> &'a FactsTracker {
+        self.facts
+    }
+
+    /// Declarer points of the tricks so far, without the Skat.
+    pub fn declarer_points(&self) -> u8 {
+        let skat_points = self
+            .context
+            .create_initial_position()
+            .declarer_points
+            .saturating_sub(self.context.declarer_start_points);
+        self.position.declarer_points.saturating_sub(skat_points)
+    }
+
+    /// PIMC problem of the player to move: the cards it cannot see are
+    /// dealt to the other hands consistent with the facts. In Ramsch the
+    /// player takes the declarer seat, so the paranoid Ramsch search
+    /// minimizes its own points.
+    pub fn pimc_problem(&self) -> PimcProblemBuilder {
+        let context = self.context;
+        let position = self.position;
+        let player = position.player;
+
+        // The declarer knows the Skat, the defenders sample it with the hands.
+        // Cards of an endgame outside of the hands stay out of the game.
+        let mut all_cards = context.declarer_cards() | context.left_cards() | context.right_cards();
+        let skat = context.get_skat();
+        let knows_skat = player == Player::Declarer && context.game_type() != Game::Ramsch;
+        if !knows_skat && skat.count_ones() == 2 {
+            all_cards |= skat;
+        }
+
+        if context.game_type() == Game::Ramsch {
+            let facts_of = |player: Player| match player {
+                Player::Declarer => self.facts.declarer,
+                Player::Left => self.facts.left,
+                Player::Right => self.facts.right,
+            };
+            return PimcProblemBuilder::new(Game::Ramsch)
+                .my_player(Player::Declarer)
+                .my_cards_val(position.player_cards)
+                .all_cards_val(all_cards & !position.played_cards)
+                .turn(Player::Declarer)
+                .threshold(61)
+                .trick_previous_player(position.trick_suit, self.trick_card(player.dec()))
+                .trick_next_player(self.trick_card(player.inc()))
+                .facts(Player::Declarer, facts_of(player))
+                .facts(Player::Left, facts_of(player.inc()))
+                .facts(Player::Right, facts_of(player.dec()));
+        }
+
+        let mut builder = PimcProblemBuilder::new(context.game_type())
+            .my_player(player)
+            .my_cards_val(position.player_cards)
+            .all_cards_val(all_cards & !position.played_cards)
+            .turn(player)
+            .threshold(context.points_to_win())
+            .declarer_start_points(self.declarer_points())
+            .doubling(context.doubling())
+            .trick_previous_player(position.trick_suit, self.trick_card(player.dec()))
+            .trick_next_player(self.trick_card(player.inc()))
+            .facts(Player::Declarer, self.facts.declarer)
+            .facts(Player::Left, self.facts.left)
+            .facts(Player::Right, self.facts.right)
+            .declarer_played_cards(context.declarer_cards() & !position.declarer_cards)
+            .all_played_cards(position.played_cards);
+        if context.game_type().is_ouvert() {
+            builder = builder.open_declarer_cards_val(context.declarer_cards());
+        }
+        builder
+    }
+
+    /// The true deal and position, for agents that play with open cards.
+    pub fn god_view(&self) -> (&'a GameContext, &'a Position) {
+        (self.context, self.position)
+    }
+}
+
+/// The card an agent plays and how it rated the cards.
+pub struct Decision {
+    pub card: u32,
+    /// Ratings of the cards for the logs, best first.
+    pub ratings: Vec<(u32, String)>,
+    /// True if a rule chose another card than the best rated one.
+    pub overridden: bool,
+    /// Win probability of the card, for the agents that estimate one.
+    pub win_prob: Option<f32>,
+    /// Effective number of samples behind the ratings of the sampling
+    /// agents, less than the samples drawn if the samples weigh unequally.
+    pub effective_samples: Option<f32>,
+}
+
+impl Decision {
+    fn new(card: u32) -> Self {
+        Decision {
+            card,
+            ratings: Vec::new(),
+            overridden: false,
+            win_prob: None,
+            effective_samples: None,
+        }
+    }
+}
+
+pub trait Agent {
+    /// Name and settings for the logs.
+    fn name(&self) -> String;
+
+    /// One of the legal cards of `observation`.
+    fn choose(&mut self, observation: &Observation) -> Decision;
+}
+
+/// Plays with open cards (the "god player"): the best card of a double
+/// dummy search, within the search limits if set.
+#[derive(Default)]
+pub struct PerfectAgent {
+    engine: Option<SkatEngine>,
+    max_nodes: u64,
+    max_millis: u64,
+}
+
+impl PerfectAgent {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Searches with `engine`, e.g. for another table size. The context of
+    /// the engine is replaced.
+    pub fn with_engine(mut self, engine: SkatEngine) -> Self {
+        self.engine = Some(engine);
+        self
+    }
+
+    /// Limits the searches to `max_nodes` nodes and `max_millis`
+    /// milliseconds; 0 removes a limit.
+    pub fn with_limits(mut self, max_nodes: u64, max_millis: u64) -> Self {
+        self.max_nodes = max_nodes;
+        self.max_millis = max_millis;
+        self
+    }
+}
+
+impl Agent for PerfectAgent {
+    fn name(&self) -> String {
+        "perfect".to_string()
+    }
+
+    fn choose(&mut self, observation: &Observation) -> Decision {
+        let (context, position) = observation.god_view();
+        let engine = match &mut self.engine {
+            Some(engine) => {
+                engine.set_context(*context);
+                engine
+            }
+            None => self.engine.insert(SkatEngine::new(*context, None)),
+        };
+
+        let mut control = SearchControl::unlimited();
+        if self.max_nodes > 0 {
+            control = control.with_node_budget(self.max_nodes);
+        }
+        if self.max_millis > 0 {
+            control = control.with_time_limit(std::time::Duration::from_millis(self.max_millis));
+        }
+
+        let variation = engine.search_anytime(position, &mut Counters::new(), &control);
+        let card = variation.best_card();
+        Decision {
+            card,
+            ratings: vec![(card, variation.value.to_string())],
+            overridden: false,
+            win_prob: None,
+            effective_samples: None,
+        }
+    }
+}
+
+/// Plays the card with the highest PIMC win probability. With the Null
+/// rules the probabilities only preselect the cards, see `select_null_card`.
+pub struct PimcAgent {
+    samples: u32,
+    adaptive: Option<AdaptiveSampling>,
+    estimator: Estimator,
+    sampling_mode: SamplingMode,
+    null_rules: bool,
+    rng: StdRng,
+}
+
+impl PimcAgent {
+    /// Agent with `samples` per card; the searches are seeded from `seed`.
+    pub fn new(samples: u32, seed: u64) -> Self {
+        PimcAgent {
+            samples,
+            adaptive: None,
+            estimator: Estimator::default(),
+            sampling_mode: SamplingMode::Random,
+            null_rules: false,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn with_adaptive(mut self, adaptive: Option<AdaptiveSampling>) -> Self {
+        self.adaptive = adaptive;
+        self
+    }
+
+    pub fn with_estimator(mut self, estimator: Estimator) -> Self {
+        self.estimator = estimator;
+        self
+    }
+
+    pub fn with_sampling_mode(mut self, sampling_mode: SamplingMode) -> Self {
+        self.sampling_mode = sampling_mode;
+        self
+    }
+
+    pub fn with_null_rules(mut self, null_rules: bool) -> Self {
+        self.null_rules = null_rules;
+        self
+    }
+}
+
+impl Agent for PimcAgent {
+    fn name(&self) -> String {
+        let samples = match self.adaptive {
+            Some(adaptive) => format!("{}..{} samples", self.samples, adaptive.max_samples),
+            None => format!("{} samples", self.samples),
+        };
+        match self.estimator {
+            Estimator::AlphaMu { max_moves } => {
+                format!("pimc({}, alpha-mu {})", samples, max_moves)
+            }
+            Estimator::Pimc => format!("pimc({})", samples),
+        }
+    }
+
+    fn choose(&mut self, observation: &Observation) -> Decision {
+        let problem = observation
+            .pimc_problem()
+            .sampling_mode(self.sampling_mode)
+            .build();
+        let (probs, effective_samples) = PimcSearch::new(problem, self.samples, None)
+            .with_seed(self.rng.gen())
+            .with_adaptive(self.adaptive)
+            .with_estimator(self.estimator)
+            .estimate_weighted_probability_of_all_cards(false);
+
+        let best = probs
+            .first()
+            .map_or_else(|| first_card(observation.legal_moves()), |(card, _)| *card);
+        let card = if self.null_rules && observation.game_type().is_null() {
+            null_card(&probs, observation)
+        } else {
+            best
+        };
+
+        Decision {
+            card,
+            ratings: probs
+                .iter()
+                .map(|(c, p)| (*c, format!("{:.0}%", p * 100.0)))
+                .collect(),
+            overridden: card != best,
+            win_prob: probs.iter().find(|(c, _)| *c == card).map(|(_, p)| *p),
+            effective_samples: Some(effective_samples),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FallbackStrategy {
+    Average,
+    Minimum,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointStrategy {
+    Average,
+    Minimum,
+    Hybrid {
+        delta: f32,
+        fallback: FallbackStrategy,
+    },
+    /// Sort by avg_points, but within `threshold` points of the best move prefer
+    /// trump (declarer) or non-trump (opponent) as a realistic move heuristic.
+    AverageWithHeuristic {
+        threshold: f32,
+    },
+}
+
+impl PointStrategy {
+    pub fn from_args(mode: &str, delta: f32, fallback: &str, heuristic: Option<f32>) -> Self {
+        if let Some(threshold) = heuristic {
+            return PointStrategy::AverageWithHeuristic { threshold };
+        }
+        let fb = match fallback.to_lowercase().as_str() {
+            "minimum" | "min" => FallbackStrategy::Minimum,
+            _ => FallbackStrategy::Average,
+        };
+        match mode.to_lowercase().as_str() {
+            "minimum" | "min" => PointStrategy::Minimum,
+            "hybrid" => PointStrategy::Hybrid {
+                delta,
+                fallback: fb,
+            },
+            _ => PointStrategy::Average,
+        }
+    }
+}
+
+/// Plays by the expected points of the cards from PIMC samples (or an
+/// ISMCTS tree), ranked by a `PointStrategy`.
+pub struct PimcPointsAgent {
+    samples: u32,
+    strategy: PointStrategy,
+    adaptive: Option<AdaptiveSampling>,
+    sampling_mode: SamplingMode,
+    ismcts: Option<IsmctsConfig>,
+    rng: StdRng,
+}
+
+impl PimcPointsAgent {
+    /// Agent with `samples` per card; the searches are seeded from `seed`.
+    pub fn new(samples: u32, strategy: PointStrategy, seed: u64) -> Self {
+        PimcPointsAgent {
+            samples,
+            strategy,
+            adaptive: None,
+            sampling_mode: SamplingMode::Random,
+            ismcts: None,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn with_adaptive(mut self, adaptive: Option<AdaptiveSampling>) -> Self {
+        self.adaptive = adaptive;
+        self
+    }
+
+    pub fn with_sampling_mode(mut self, sampling_mode: SamplingMode) -> Self {
+        self.sampling_mode = sampling_mode;
+        self
+    }
+
+    /// Takes the metrics from an ISMCTS tree instead of PIMC samples.
+    pub fn with_ismcts(mut self, ismcts: Option<IsmctsConfig>) -> Self {
+        self.ismcts = ismcts;
+        self
+    }
+
+    /// Rating of `metrics` for the logs.
+    fn rating(&self, metrics: &MoveMetrics) -> String {
+        match self.strategy {
+            PointStrategy::Average => format!("{:.0}", metrics.avg_points),
+            PointStrategy::Minimum => format!("{:.0}", metrics.min_points),
+            PointStrategy::Hybrid { fallback, .. } => {
+                let fval = match fallback {
+                    FallbackStrategy::Average => metrics.avg_points,
+                    FallbackStrategy::Minimum => metrics.min_points,
+                };
+                format!("{:.0}%/{:.0}", metrics.win_prob * 100.0, fval)
+            }
+            PointStrategy::AverageWithHeuristic { .. } => {
+                format!("{:.0}(\u{00B1}{:.1})", metrics.avg_points, metrics.std_dev)
+            }
+        }
+    }
+}
+
+impl Agent for PimcPointsAgent {
+    fn name(&self) -> String {
+        match self.ismcts {
+            Some(config) => format!(
+                "pimc-points(ISMCTS {} iterations, {:?} rollouts, {:?})",
+                config.iterations, config.rollout, self.strategy
+            ),
+            None => match self.adaptive {
+                Some(adaptive) => format!(
+                    "pimc-points({}..{} samples, {:?})",
+                    self.samples, adaptive.max_samples, self.strategy
+                ),
+                None => format!("pimc-points({} samples, {:?})", self.samples, self.strategy),
+            },
+        }
+    }
+
+    fn choose(&mut self, observation: &Observation) -> Decision {
+        let problem = observation
+            .pimc_problem()
+            .sampling_mode(self.sampling_mode)
+            .build();
+        let mut scores = match self.ismcts {
+            Some(config) => IsmctsSearch::new(problem, config)
+                .with_seed(self.rng.gen())
+                .estimate_move_metrics(),
+            None => PimcSearch::new(problem, self.samples, None)
+                .with_seed(self.rng.gen())
+                .with_adaptive(self.adaptive)
+                .estimate_move_metrics(false),
+        };
+
+        let overridden = rank_by_strategy(&mut scores, self.strategy, observation);
+        let card = scores
+            .first()
+            .map_or_else(|| first_card(observation.legal_moves()), |(card, _)| *card);
+
+        Decision {
+            card,
+            ratings: scores.iter().map(|(c, m)| (*c, self.rating(m))).collect(),
+            overridden,
+            win_prob: scores.first().map(|(_, m)| m.win_prob),
+            effective_samples: scores.first().map(|(_, m)| m.effective_samples),
+        }
+    }
+}
+
+/// Sorts `scores` best first by `strategy`. True if the trump heuristic
+/// overrode the card with the best average points.
+fn rank_by_strategy(
+    scores: &mut [(u32, MoveMetrics)],
+    strategy: PointStrategy,
+    observation: &Observation,
+) -> bool {
+    match strategy {
+        PointStrategy::Average => {
+            scores.sort_by(|a, b| {
+                b.1.avg_points
+                    .partial_cmp(&a.1.avg_points)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            false
+        }
+        PointStrategy::Minimum => {
+            scores.sort_by(|a, b| {
+                b.1.min_points
+                    .partial_cmp(&a.1.min_points)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            false
+        }
+        PointStrategy::Hybrid { delta, fallback } => {
+            // Sort primarily by win_prob. But if the difference is < delta, fallback to the secondary metric.
+            scores.sort_by(|a, b| {
+                let win_cmp =
+                    b.1.win_prob
+                        .partial_cmp(&a.1.win_prob)
+                        .unwrap_or(std::cmp::Ordering::Equal);
+                if win_cmp == std::cmp::Ordering::Equal
+                    || (b.1.win_prob - a.1.win_prob).abs() <= delta
+                {
+                    match fallback {
+                        FallbackStrategy::Average => {
+                            b.1.avg_points
+                                .partial_cmp(&a.1.avg_points)
+                                .unwrap_or(std::cmp::Ordering::Equal)
+                        }
+                        FallbackStrategy::Minimum => {
+                            b.1.min_points
+                                .partial_cmp(&a.1.min_points)
+                                .unwrap_or(std::cmp::Ordering::Equal)
+                        }
+                    }
+                } else {
+                    win_cmp
+                }
+            });
+            false
+        }
+        PointStrategy::AverageWithHeuristic { threshold } => {
+            // ── Phase 1: sort by avg_points to establish the baseline winner ──
+            scores.sort_by(|a, b| {
+                b.1.avg_points
+                    .partial_cmp(&a.1.avg_points)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            let pure_avg_best = scores.first().map(|(c, _)| *c);
+
+            if !scores.is_empty() {
+                let best_pts = scores[0].1.avg_points;
+                let game_type = observation.game_type();
+                let trump_mask = game_type.get_trump();
+                let is_declarer = observation.player() == Player::Declarer;
+
+                // Trump preference rules:
+                //   Declarer always prefers trump (Grand + Suit).
+                //   Opponents prefer non-trump ONLY in Suit games (not Grand –
+                //   Grand's only trump is the four Jacks, avoiding them makes
+                //   less tactical sense for defenders).
+                let apply_trump_pref = is_declarer || game_type == Game::Suit;
+
+                // ── Phase 2: card-value tiebreak for 3rd player ──────────
+                // When we're last to play (two cards in the trick) and all
+                // threshold-eligible candidates share the same trick outcome
+                // (all win or all lose), break ties by card point value:
+                //   winning trick → play highest value  (collect more points fast)
+                //   losing trick  → play lowest value   (don't waste good cards)
+                let played = observation.trick_cards();
+                let value_sort: Option<bool> = if played.count_ones() == 2 {
+                    let ts = observation.trick_suit();
+                    let in_range_outcomes: Vec<bool> = scores
+                        .iter()
+                        .filter(|(_, m)| m.avg_points >= best_pts - threshold)
+                        .map(|(c, _)| would_win_trick_regular(*c, played, ts, game_type))
+                        .collect();
+                    if in_range_outcomes.iter().all(|&w| w) {
+                        Some(true) // all win → pick highest card value
+                    } else if in_range_outcomes.iter().all(|&w| !w) {
+                        Some(false) // all lose → pick lowest card value
+                    } else {
+                        None // mixed – no value tiebreak
+                    }
+                } else {
+                    None
+                };
+
+                // ── Phase 3: unified sort with four-level priority ────────
+                scores.sort_by(|a, b| {
+                    use std::cmp::Ordering::*;
+                    let a_in = a.1.avg_points >= best_pts - threshold;
+                    let b_in = b.1.avg_points >= best_pts - threshold;
+
+                    // P1: in-range beats out-of-range
+                    if a_in != b_in {
+                        return if b_in { Greater } else { Less };
+                    }
+
+                    if a_in {
+                        // P2: trump preference
+                        if apply_trump_pref {
+                            let a_trump = (a.0 & trump_mask) != 0;
+                            let b_trump = (b.0 & trump_mask) != 0;
+                            if a_trump != b_trump {
+                                let a_pref = if is_declarer { a_trump } else { !a_trump };
+                                let b_pref = if is_declarer { b_trump } else { !b_trump };
+                                if a_pref != b_pref {
+                                    return if b_pref { Greater } else { Less };
+                                }
+                            }
+                        }
+
+                        // P3: card-value tiebreak (3rd player only)
+                        if let Some(prefer_high) = value_sort {
+                            let va = a.0.card_points();
+                            let vb = b.0.card_points();
+                            let vord = if prefer_high {
+                                vb.cmp(&va) // DESC – highest value first
+                            } else {
+                                va.cmp(&vb) // ASC – lowest value first
+                            };
+                            if vord != Equal {
+                                return vord;
+                            }
+                        }
+                    }
+
+                    // P4: avg_points descending (baseline)
+                    b.1.avg_points.partial_cmp(&a.1.avg_points).unwrap_or(Equal)
+                });
+            }
+
+            // Detect override: heuristic chose a different card than pure avg.
+            scores.first().map(|(c, _)| *c) != pure_avg_best
+        }
+    }
+}
+
+/// Plays the card visited most by an ISMCTS search over the cards the
+/// player cannot see.
+pub struct IsmctsAgent {
+    config: IsmctsConfig,
+    rng: StdRng,
+}
+
+impl IsmctsAgent {
+    /// The searches are seeded from `seed`.
+    pub fn new(config: IsmctsConfig, seed: u64) -> Self {
+        IsmctsAgent {
+            config,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Agent for IsmctsAgent {
+    fn name(&self) -> String {
+        format!(
+            "ismcts({} iterations, {:?} rollouts)",
+            self.config.iterations, self.config.rollout
+        )
+    }
+
+    fn choose(&mut self, observation: &Observation) -> Decision {
+        let metrics = IsmctsSearch::new(observation.pimc_problem().build(), self.config)
+            .with_seed(self.rng.gen())
+            .estimate_move_metrics();
+        let card = metrics
+            .first()
+            .map_or_else(|| first_card(observation.legal_moves()), |(card, _)| *card);
+
+        Decision {
+            card,
+            ratings: metrics
+                .iter()
+                .map(|(c, m)| (*c, format!("{:.0}", m.avg_points)))
+                .collect(),
+            overridden: false,
+            win_prob: metrics.first().map(|(_, m)| m.win_prob),
+            effective_samples: None,
+        }
+    }
+}
+
+/// Plays a random legal card.
+pub struct RandomAgent {
+    rng: StdRng,
+}
+
+impl RandomAgent {
+    pub fn new(seed: u64) -> Self {
+        RandomAgent {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Agent for RandomAgent {
+    fn name(&self) -> String {
+        "random".to_string()
+    }
+
+    fn choose(&mut self, observation: &Observation) -> Decision {
+        let (cards, n) = observation.legal_moves().__decompose();
+        Decision::new(*cards[0..n].choose(&mut self.rng).unwrap())
+    }
+}
+
+/// Plays by fixed rules without a search: the Null rules of
+/// `select_null_card`, else the heuristic rollout policy of ISMCTS (take a
+/// trick as the last player if the own side wins it, else the card with the
+/// fewest points; random leads).
+pub struct RuleAgent {
+    rng: StdRng,
+}
+
+impl RuleAgent {
+    pub fn new(seed: u64) -> Self {
+        RuleAgent {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Agent for RuleAgent {
+    fn name(&self) -> String {
+        "rules".to_string()
+    }
+
+    fn choose(&mut self, observation: &Observation) -> Decision {
+        if observation.game_type().is_null() {
+            return Decision::new(null_card(&[], observation));
+        }
+
+        // The rules only look at the own hand and the trick
+        let game_type = observation.game_type();
+        let player = observation.player();
+        let played = |of: Player, card: u32| {
+            if of == player {
+                card
+            } else {
+                observation.trick_card(of)
+            }
+        };
+        let winner = |card: u32| {
+            get_trick_winner(
+                observation.trick_cards() | card,
+                observation.trick_suit(),
+                game_type,
+                played(Player::Declarer, card),
+                played(Player::Left, card),
+                played(Player::Right, card),
+            )
+        };
+        let card = heuristic_card(
+            observation.legal_moves(),
+            observation.trick_cards(),
+            player,
+            game_type,
+            winner,
+            &mut self.rng,
+        );
+        Decision::new(card)
+    }
+}
+
+/// Settings of the agents of `create_agent`.
+#[derive(Clone, Copy)]
+pub struct AgentSettings {
+    pub samples: u32,
+    pub adaptive: Option<AdaptiveSampling>,
+    pub estimator: Estimator,
+    pub sampling_mode: SamplingMode,
+    pub point_strategy: PointStrategy,
+    /// ISMCTS configuration of the "ismcts" agent and, if set, of the
+    /// metrics of the "pimc-points" agent.
+    pub ismcts: Option<IsmctsConfig>,
+    /// Null rules of the "pimc" agent, see `select_null_card`.
+    pub null_rules: bool,
+}
+
+impl AgentSettings {
+    pub fn new(samples: u32) -> Self {
+        AgentSettings {
+            samples,
+            adaptive: None,
+            estimator: Estimator::default(),
+            sampling_mode: SamplingMode::Random,
+            point_strategy: PointStrategy::Average,
+            ismcts: None,
+            null_rules: false,
+        }
+    }
+}
+
+/// Agent of `kind` ("perfect", "pimc", "pimc-points", "ismcts", "random" or
+/// "rules") with `settings`, seeded from `seed`.
+pub fn create_agent(
+    kind: &str,
+    settings: &AgentSettings,
+    seed: u64,
+) -> Result<Box<dyn Agent>, String> {
+    let agent: Box<dyn Agent> = match kind.trim().to_lowercase().as_str() {
+        "perfect" => Box::new(PerfectAgent::new()),
+        "pimc" => Box::new(
+            PimcAgent::new(settings.samples, seed)
+                .with_adaptive(settings.adaptive)
+                .with_estimator(settings.estimator)
+                .with_sampling_mode(settings.sampling_mode)
+                .with_null_rules(settings.null_rules),
+        ),
+        "pimc-points" => Box::new(
+            PimcPointsAgent::new(settings.samples, settings.point_strategy, seed)
+                .with_adaptive(settings.adaptive)
+                .with_sampling_mode(settings.sampling_mode)
+                .with_ismcts(settings.ismcts),
+        ),
+        "ismcts" => {
+            let config = settings
+                .ismcts
+                .unwrap_or_else(|| IsmctsConfig::new(DEFAULT_ISMCTS_ITERATIONS));
+            Box::new(IsmctsAgent::new(config, seed))
+        }
+        "random" => Box::new(RandomAgent::new(seed)),
+        "rules" => Box::new(RuleAgent::new(seed)),
+        _ => return Err(format!("Unknown agent: {}", kind)),
+    };
+    Ok(agent)
+}
+
+/// Agents of the declarer, left and right seat from a comma separated list
+/// of kinds (see `create_agent`); a single kind takes all seats. The seeds
+/// are drawn from `rng`.
+pub fn create_seats<R: Rng>(
+    spec: &str,
+    settings: &AgentSettings,
+    rng: &mut R,
+) -> Result<[Box<dyn Agent>; 3], String> {
+    let kinds: Vec<&str> = spec.split(',').collect();
+    let kind = |seat: usize| match kinds.len() {
+        1 => Ok(kinds[0]),
+        3 => Ok(kinds[seat]),
+        n => Err(format!("Expected 1 or 3 agents, got {}", n)),
+    };
+
+    Ok([
+        create_agent(kind(0)?, settings, rng.gen())?,
+        create_agent(kind(1)?, settings, rng.gen())?,
+        create_agent(kind(2)?, settings, rng.gen())?,
+    ])
+}
+
+fn first_card(cards: u32) -> u32 {
+    cards.__decompose().0[0]
+}
+
+/// Returns the highest-set bit of `x` as an isolated bit mask.
+/// Panics if `x == 0`.
+#[inline]
+fn highest_bit(x: u32) -> u32 {
+    debug_assert!(x != 0);
+    1u32 << (31 - x.leading_zeros())
+}
+
+/// Returns `true` if `candidate_card` (single bit) would win the trick when
+/// played as the **last** (3rd) card.
+///
+/// `trick_cards_so_far` contains exactly 2 bits (the two already-played cards).
+/// `trick_suit` is the lead suit established by the first card of the trick.
+fn would_win_trick_regular(
+    candidate_card: u32,
+    trick_cards_so_far: u32,
+    trick_suit: u32,
+    game_type: Game,
+) -> bool {
+    if trick_cards_so_far == 0 {
+        return false;
+    }
+    let trump = game_type.get_trump();
+    let is_trump_played = (trick_cards_so_far & trump) != 0;
+    let candidate_is_trump = (candidate_card & trump) != 0;
+
+    if is_trump_played {
+        // Effective suit is trump: candidate must also be trump and beat the
+        // current best trump.
+        if !candidate_is_trump {
+            return false;
+        }
+        let trump_in_trick = trick_cards_so_far & trump;
+        return candidate_card > highest_bit(trump_in_trick);
+    }
+
+    // No trump played yet.
+    if candidate_is_trump {
+        // Playing trump on a non-trump trick always wins (legal play only).
+        return true;
+    }
+
+    // Neither trump played nor candidate is trump: compare within lead suit.
+    let candidate_in_lead = (candidate_card & trick_suit) != 0;
+    if !candidate_in_lead {
+        return false; // discarding off-suit – can never win
+    }
+
+    let lead_in_trick = trick_cards_so_far & trick_suit;
+    if lead_in_trick == 0 {
+        // Neither played card followed the lead suit – candidate is the only one.
+        return true;
+    }
+
+    candidate_card > highest_bit(lead_in_trick)
+}
+
+/// Null-order rank: 7=1, 8=2, 9=3, J=4, Q=5, K=6, T=7, A=8.
+/// Lower rank = weaker card = loses easier (good for declarer to discard).
+fn null_rank(card: u32) -> u8 {
+    if (card & SEVENS) != 0 {
+        1
+    } else if (card & EIGHTS) != 0 {
+        2
+    } else if (card & NINES) != 0 {
+        3
+    } else if (card & JACKS) != 0 {
+        4
+    } else if (card & QUEENS) != 0 {
+        5
+    } else if (card & KINGS) != 0 {
+        6
+    } else if (card & TENS) != 0 {
+        7
+    } else {
+        8
+    } // Ace
+}
+
+/// Returns `true` if `card` would WIN the current trick under Null rules.
+///
+/// Uses NULL_CLUBS/NULL_SPADES/NULL_HEARTS/NULL_DIAMONDS which include Jacks
+/// (the regular CLUBS/SPADES/HEARTS/DIAMONDS constants exclude Jacks).
+fn would_win_trick_null(
+    card: u32,
+    trick_lead_suit: u32, // 0 if this player IS the lead
+    trick_best_card: u32, // current trick winner so far (0 if no card yet)
+) -> bool {
+    if trick_lead_suit == 0 || trick_best_card == 0 {
+        return false; // this player leads — not "beating" anyone yet
+    }
+
+    // Determine lead suit from trick_best_card using null-aware suit masks.
+    let lead_suit_mask = if (trick_best_card & NULL_CLUBS) != 0 {
+        NULL_CLUBS
+    } else if (trick_best_card & NULL_SPADES) != 0 {
+        NULL_SPADES
+    } else if (trick_best_card & NULL_HEARTS) != 0 {
+        NULL_HEARTS
+    } else if (trick_best_card & NULL_DIAMONDS) != 0 {
+        NULL_DIAMONDS
+    } else {
+        return false; // unknown suit — shouldn't happen
+    };
+
+    // Card must follow the lead suit to be able to win.
+    if (card & lead_suit_mask) == 0 {
+        return false;
+    }
+
+    // Follows suit — wins if its Null rank is strictly higher.
+    null_rank(card) > null_rank(trick_best_card)
+}
+
+/// `select_null_card` for the player to move in `observation`.
+fn null_card(probs: &[(u32, f32)], observation: &Observation) -> u32 {
+    let trick_suit = observation.trick_suit();
+
+    // The card currently "winning" the trick and its player
+    let mut best: Option<(Player, u32)> = None;
+    for &(player, card) in observation.trick() {
+        match best {
+            Some((_, best_card)) if !would_win_trick_null(card, trick_suit, best_card) => {}
+            _ => best = Some((player, card)),
+        }
+    }
+    let trick_best_card = best.map_or(0, |(_, card)| card);
+    let opponent_has_trick = best.is_some_and(|(player, _)| player != Player::Declarer);
+
+    select_null_card(
+        probs,
+        observation.legal_moves(),
+        observation.player() == Player::Declarer,
+        trick_suit,
+        trick_best_card,
+        opponent_has_trick,
+    )
+}
+
+/// Null card selection — constraint-first, then PIMC, then positional tiebreaker.
+///
+/// Step 0 – Hard constraints (applied regardless of probability):
+///   Declarer not leading:
+///     Must play a non-winning (safe) card if any exist.
+///     → candidate pool = safe cards; fallback: all cards (forced win).
+///   Opponent, teammate already winning (opponent_has_trick):
+///     Must NOT steal the trick from a teammate.
+///     → candidate pool = non-winning cards; fallback: all cards (forced win).
+///   Declarer leading / Opponent in all other cases:
+///     → candidate pool = all legal cards.
+///
+/// Step 1 – PIMC probability filter:
+///   Among the candidate pool, keep only those with maximum win-probability.
+///   (Irrelevant cards below the max are excluded.)
+///
+/// Step 2 – Positional tiebreaker (among max-prob candidates):
+///   Declarer leading            → LOWEST card.
+///   Declarer not leading        → HIGHEST card (dump dangerous high values).
+///   Opponent leading            → LOWEST card (kleine Karte anführen).
+///   Opponent, opponent_has_trick→ HIGHEST card (dump high values).
+///   Opponent, declarer winning  → HIGHEST non-winning card (let declarer keep stich).
+fn select_null_card(
+    probs: &[(u32, f32)],
+    legal_mask: u32,
+    is_declarer: bool,
+    trick_lead_suit: u32,
+    trick_best_card: u32,
+    opponent_has_trick: bool,
+) -> u32 {
+    let (arr, n) = legal_mask.__decompose();
+    let legal: Vec<u32> = (0..n).map(|i| arr[i]).collect();
+    let is_lead = trick_lead_suit == 0;
+
+    let min_card = |v: &Vec<u32>| {
+        v.iter()
+            .cloned()
+            .min_by_key(|&c| null_rank(c))
+            .unwrap_or(legal[0])
+    };
+    let max_card = |v: &Vec<u32>| {
+        v.iter()
+            .cloned()
+            .max_by_key(|&c| null_rank(c))
+            .unwrap_or(legal[0])
+    };
+    let non_winning_of = |pool: &Vec<u32>| -> Vec<u32> {
+        pool.iter()
+            .cloned()
+            .filter(|&c| !would_win_trick_null(c, trick_lead_suit, trick_best_card))
+            .collect()
+    };
+
+    // ── Step 0: build hard-constrained candidate pool ────────────────────────
+    let candidates: Vec<u32> = if is_declarer && !is_lead {
+        // Declarer must not win the trick (play safe).
+        let safe = non_winning_of(&legal);
+        if safe.is_empty() {
+            legal.clone()
+        } else {
+            safe
+        }
+    } else if !is_declarer && !is_lead && opponent_has_trick {
+        // Teammate already winning: use ALL cards — dump the highest, even if it
+        // oversteps the teammate (getting rid of dangerous high cards is the goal).
+        legal.clone()
+    } else if !is_declarer && !is_lead && !opponent_has_trick {
+        // Declarer currently winning — opponent must let him keep it.
+        let non_win = non_winning_of(&legal);
+        if non_win.is_empty() {
+            legal.clone()
+        } else {
+            non_win
+        }
+    } else {
+        legal.clone() // leading: all cards are valid
+    };
+
+    // ── Step 1: keep only max-probability candidates ─────────────────────────
+    let max_prob_candidates: Vec<u32> = if probs.is_empty() {
+        candidates.clone()
+    } else {
+        let max_p = probs
+            .iter()
+            .filter(|(c, _)| candidates.contains(c))
+            .map(|(_, p)| *p)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let top: Vec<u32> = probs
+            .iter()
+            // Threshold 0.015: with 100 samples, 1 sample = 1% = 0.01 difference;
+            // treat cards within ~1 sample of each other as equal probability.
+            .filter(|(c, p)| candidates.contains(c) && (*p - max_p).abs() < 0.015)
+            .map(|(c, _)| *c)
+            .collect();
+        if top.is_empty() {
+            candidates.clone()
+        } else {
+            top
+        }
+    };
+
+    // ── Step 2: positional tiebreaker among max-prob candidates ──────────────
+    if is_declarer {
+        if is_lead {
+            min_card(&max_prob_candidates) // lead: stay small
+        } else {
+            min_card(&max_prob_candidates) // safe pool filtered; play lowest safe
+        }
+    } else if is_lead {
+        min_card(&max_prob_candidates) // opponent leads: kleine Karte
+    } else if opponent_has_trick {
+        max_card(&max_prob_candidates) // teammate has trick: dump highest
+    } else {
+        // Declarer currently winning: non-winning pool already filtered; dump highest.
+        max_card(&max_prob_candidates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::{create_seats, Agent, AgentSettings, Observation, RuleAgent};
+    use crate::extensions::cli_playout::generate_random_deal;
+    use crate::pimc::facts::FactsTracker;
+    use crate::pimc::pimc_problem::SamplingMode;
+    use crate::skat::context::GameContext;
+    use crate::skat::defs::{Game, Player};
+    use crate::traits::{BitConverter, Bitboard};
+
+    #[test]
+    fn test_agents_see_the_trick() {
+        let context = GameContext::create(
+            "HA HT H7".__bit(),
+            "HK HQ SA".__bit(),
+            "H9 ST S9".__bit(),
+            Game::Grand,
+            Player::Declarer,
+        );
+        let facts = FactsTracker::new();
+        let lead = "H7".__bit();
+        let history = [(Player::Declarer, lead)];
+        let position = context.create_initial_position().make_move(lead, &context);
+        let observation = Observation::new(&context, &position, &history, &facts);
+
+        assert_eq!(observation.player(), Player::Left);
+        assert_eq!(observation.trick(), &history[..]);
+        assert_eq!(observation.trick_card(Player::Declarer), lead);
+        assert_eq!(observation.trick_card(Player::Right), 0);
+        assert_eq!(observation.pimc_problem().build().my_player(), Player::Left);
+
+        let settings = AgentSettings::new(10);
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut agents = create_seats("perfect,rules,random", &settings, &mut rng).unwrap();
+        let hearts = "HK HQ".__bit();
+        for agent in agents.iter_mut() {
+            assert_ne!(
+                agent.choose(&observation).card & hearts,
+                0,
+                "{}",
+                agent.name()
+            );
+        }
+        assert!(create_seats("perfect,rules", &settings, &mut rng).is_err());
+        assert!(create_seats("oracle", &settings, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_rule_agent_smears_the_partner() {
+        let context = GameContext::create(
+            "H7 CA C7".__bit(),
+            "HA CT C8".__bit(),
+            "HT H9 S9".__bit(),
+            Game::Grand,
+            Player::Declarer,
+        );
+        let facts = FactsTracker::new();
+        let history = [
+            (Player::Declarer, "H7".__bit()),
+            (Player::Left, "HA".__bit()),
+        ];
+        let position = history
+            .iter()
+            .fold(context.create_initial_position(), |position, (_, card)| {
+                position.make_move(*card, &context)
+            });
+        let observation = Observation::new(&context, &position, &history, &facts);
+
+        let mut agent = RuleAgent::new(0);
+        assert_eq!(agent.choose(&observation).card, "HT".__bit());
+    }
+
+    #[test]
+    fn test_only_the_declarer_knows_the_skat() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let (context, _, _) = generate_random_deal(
+            "grand".to_string(),
+            "declarer".to_string(),
+            SamplingMode::Random,
+            &mut rng,
+        );
+        let facts = FactsTracker::new();
+        let skat = context.get_skat();
+
+        let position = context.create_initial_position();
+        let observation = Observation::new(&context, &position, &[], &facts);
+        assert_eq!(observation.pimc_problem().build().all_cards() & skat, 0);
+
+        let lead = position.get_legal_moves().__decompose().0[0];
+        let history = [(Player::Declarer, lead)];
+        let position = position.make_move(lead, &context);
+        let observation = Observation::new(&context, &position, &history, &facts);
+        assert_eq!(observation.pimc_problem().build().all_cards() & skat, skat);
+    }
+}
@@ -5,8 +5,9 @@
 use crate::extensions::skat_solving::{solve_with_skat, AccelerationMode, SolveWithSkatRet};
 use crate::extensions::solver::{solve_and_add_skat, SolveRet};
 use crate::skat::context::{GameContext, ProblemTransformation};
-use crate::skat::defs::{Game, Player};
+use crate::skat::defs::{Game, Player, CLUBS, DIAMONDS, HEARTS, SPADES};
 use crate::skat::engine::SkatEngine;
+use crate::skat::scoring::{settle, Contract, GameOutcome, Settlement};
 use rayon::prelude::*;
 
 // -----------------------------------------------------------------------------
@@ -23,6 +24,19 @@ pub enum GameKey {
     Null,
 }
 
+impl GameKey {
+    pub fn contract(&self) -> Contract {
+        match self {
+            GameKey::Eichel => Contract::suit(CLUBS),
+            GameKey::Gruen => Contract::suit(SPADES),
+            GameKey::Herz => Contract::suit(HEARTS),
+            GameKey::Schell => Contract::suit(DIAMONDS),
+            GameKey::Grand => Contract::grand(),
+            GameKey::Null => Contract::new(Game::Null),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum CalculationError {
     NoBestSkatFound(GameKey),
@@ -57,6 +71,57 @@ pub struct AllGames {
     pub null_hand: u8,
 }
 
+/// Settled score of one entry of `AllGames`.
+#[derive(Debug, Clone, Copy)]
+pub struct GameScore {
+    pub key: GameKey,
+    pub hand: bool,
+    pub value: u8,
+    pub settlement: Settlement,
+}
+
+impl AllGames {
+    /// Settles every game, Hand and with Skat pickup. `declarer_cards_with_skat`
+    /// are the declarer's ten cards plus the Skat, a bid of 0 skips the
+    /// overbid check.
+    pub fn scores(&self, declarer_cards_with_skat: u32, bid: u16) -> Vec<GameScore> {
+        let games = [
+            (GameKey::Eichel, false, self.eichel_farbe),
+            (GameKey::Gruen, false, self.gruen_farbe),
+            (GameKey::Herz, false, self.herz_farbe),
+            (GameKey::Schell, false, self.schell_farbe),
+            (GameKey::Grand, false, self.grand),
+            (GameKey::Null, false, self.null),
+            (GameKey::Eichel, true, self.eichel_hand),
+            (GameKey::Gruen, true, self.gruen_hand),
+            (GameKey::Herz, true, self.herz_hand),
+            (GameKey::Schell, true, self.schell_hand),
+            (GameKey::Grand, true, self.grand_hand),
+            (GameKey::Null, true, self.null_hand),
+        ];
+
+        games
+            .iter()
+            .map(|&(key, hand, value)| {
+                let mut contract = key.contract();
+                if hand {
+                    contract = match key {
+                        GameKey::Null => Contract::new(Game::NullHand),
+                        _ => contract.hand(),
+                    };
+                }
+                let outcome = GameOutcome::from_solver_value(contract.game, value);
+                GameScore {
+                    key,
+                    hand,
+                    value,
+                    settlement: settle(&contract, declarer_cards_with_skat, &outcome, bid),
+                }
+            })
+            .collect()
+    }
+}
+
 impl Default for AllGames {
     fn default() -> AllGames {
         AllGames {
@@ -1,71 +1,26 @@
-use crate::consts::bitboard::{
-    CLUBS, DIAMONDS, EIGHTS, HEARTS, JACKS, KINGS, NINES, NULL_CLUBS, NULL_DIAMONDS, NULL_HEARTS,
-    NULL_SPADES, QUEENS, SEVENS, SPADES, TENS,
-};
+use crate::extensions::agent::{Agent, Decision, Observation};
 use crate::extensions::skat_solving::{solve_best_game_all_variants, AccelerationMode};
 use crate::extensions::solver::{solve_all_cards_from_position, solve_optimum_from_position, OptimumMode};
-use crate::pimc::facts::Facts;
-use crate::pimc::pimc_problem_builder::PimcProblemBuilder;
-use crate::pimc::pimc_search::PimcSearch;
+use crate::pimc::facts::FactsTracker;
 use crate::skat::context::GameContext;
 use crate::skat::defs::{Game, Player};
 use crate::skat::engine::SkatEngine;
-use crate::skat::rules::get_suit_for_card;
-use crate::traits::{Bitboard, Points, StringConverter};
+use crate::skat::scoring::{collect_tricks, settle_ramsch, RamschOutcome};
+use crate::traits::{Points, StringConverter};
 use rand::prelude::*;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum FallbackStrategy {
-    Average,
-    Minimum,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum PointStrategy {
-    Average,
-    Minimum,
-    Hybrid {
-        delta: f32,
-        fallback: FallbackStrategy,
-    },
-    /// Sort by avg_points, but within `threshold` points of the best move prefer
-    /// trump (declarer) or non-trump (opponent) as a realistic move heuristic.
-    AverageWithHeuristic {
-        threshold: f32,
-    },
-}
-
-impl PointStrategy {
-    pub fn from_args(mode: &str, delta: f32, fallback: &str, heuristic: Option<f32>) -> Self {
-        if let Some(threshold) = heuristic {
-            return PointStrategy::AverageWithHeuristic { threshold };
-        }
-        let fb = match fallback.to_lowercase().as_str() {
-            "minimum" | "min" => FallbackStrategy::Minimum,
-            _ => FallbackStrategy::Average,
-        };
-        match mode.to_lowercase().as_str() {
-            "minimum" | "min" => PointStrategy::Minimum,
-            "hybrid" => PointStrategy::Hybrid {
-                delta,
-                fallback: fb,
-            },
-            _ => PointStrategy::Average,
-        }
-    }
-}
-
+/// Plays the game with the `agents` of the declarer, left and right seat and
+/// compares every card with perfect play.
 pub fn run_playout(
     initial_context: GameContext,
     game_type: Game,
     start_player: Player,
-    samples: u32,
-    sampling_mode: crate::pimc::pimc_problem::SamplingMode,
+    agents: &mut [Box<dyn Agent>; 3],
 ) {
     println!("=== Playout Configuration ===");
     println!("Game Type: {:?}", game_type);
     println!("Start Player: {:?}", start_player);
-    println!("PIMC Samples: {}", samples);
+    print_seats(agents);
     println!("=============================\n");
 
     // 2. Log Distribution
@@ -77,23 +32,23 @@ pub fn run_playout(
 
     // 4. Run PIMC Play (Comparison)
     println!("\n=== PIMC Play Simulation ===");
-    run_pimc_play(
-        initial_context,
-        game_type,
-        samples,
-        perfect_score,
-        sampling_mode,
-    );
+    run_pimc_play(initial_context, game_type, perfect_score, agents);
 }
 
+
 pub fn generate_random_deal(
     game_type_str: String,
     start_player_str: String,
     mode: crate::pimc::pimc_problem::SamplingMode,
+    rng: &mut StdRng,
 ) -> (GameContext, Game, Player) {
     let game_type = match game_type_str.to_lowercase().as_str() {
         "grand" => Game::Grand,
         "null" => Game::Null,
+        "nullhand" | "null-hand" => Game::NullHand,
+        "nullouvert" | "null-ouvert" => Game::NullOuvert,
+        "nullouverthand" | "null-ouvert-hand" => Game::NullOuvertHand,
+        "ramsch" => Game::Ramsch,
         // All suit variants use Game::Suit internally (engine uses Clubs as canonical trump)
         "clubs" | "suit" | "spades" | "hearts" | "diamonds" => Game::Suit,
         _ => panic!("Invalid game type: {}", game_type_str),
@@ -122,12 +77,10 @@ pub fn generate_random_deal(
         }
     };
 
-    let mut rng = rand::thread_rng();
-
     // Try up to 200 times to deal an acceptable hand
     for _ in 0..200 {
         let mut deck: Vec<u8> = (0..32).collect();
-        deck.shuffle(&mut rng);
+        deck.shuffle(rng);
 
         let mut declarer_cards = 0u32;
         let mut left_cards = 0u32;
@@ -158,7 +111,7 @@ pub fn generate_random_deal(
 
     // Fallback: Just return a random deal if condition not met after 200 tries
     let mut deck: Vec<u8> = (0..32).collect();
-    deck.shuffle(&mut rng);
+    deck.shuffle(rng);
     let mut declarer_cards = 0u32;
     let mut left_cards = 0u32;
     let mut right_cards = 0u32;
@@ -200,12 +153,12 @@ pub fn generate_random_deal(
 pub fn generate_smart_deal_with_min_typed(
     min_value: u8,
     type_filter: Option<Game>,
+    rng: &mut StdRng,
 ) -> Option<(GameContext, Game, Player, String, String, u32)> {
     let start_player = Player::Declarer; // Declarer always leads first trick
 
-    let mut rng = rand::thread_rng();
     let mut deck: Vec<u8> = (0..32).collect();
-    deck.shuffle(&mut rng);
+    deck.shuffle(rng);
 
     // 12 cards for declarer (includes 2 Skat cards), 10 each for Left / Right.
     let mut decl_12 = 0u32;
@@ -233,7 +186,7 @@ pub fn generate_smart_deal_with_min_typed(
     // Filter out Null; optionally restrict to Grand or Suit only; pick best value.
     let best = all_variants
         .into_iter()
-        .filter(|r| r.game_type != Game::Null)
+        .filter(|r| !r.game_type.is_null())
         .filter(|r| match type_filter {
             Some(Game::Grand) => r.game_type == Game::Grand,
             Some(Game::Suit) => r.game_type == Game::Suit,
@@ -279,14 +232,19 @@ pub fn generate_smart_deal_with_min_typed(
 }
 
 /// Backward-compatible wrapper: uses the old 50-pt threshold, no type filter.
-pub fn generate_smart_deal_with_min(min_value: u8) -> Option<(GameContext, Game, Player, String, String)> {
-    generate_smart_deal_with_min_typed(min_value, None)
+pub fn generate_smart_deal_with_min(
+    min_value: u8,
+    rng: &mut StdRng,
+) -> Option<(GameContext, Game, Player, String, String)> {
+    generate_smart_deal_with_min_typed(min_value, None, rng)
         .map(|(ctx, g, p, l, d, _)| (ctx, g, p, l, d))
 }
 
 /// Backward-compatible wrapper: uses the old 50-pt threshold.
-pub fn generate_smart_deal() -> Option<(GameContext, Game, Player, String, String)> {
-    generate_smart_deal_with_min(50)
+pub fn generate_smart_deal(
+    rng: &mut StdRng,
+) -> Option<(GameContext, Game, Player, String, String)> {
+    generate_smart_deal_with_min(50, rng)
 }
 
 use crate::skat::formatter::format_hand_for_game;
@@ -295,6 +253,10 @@ fn log_distribution(ctx: &GameContext, game_type: Game, start_player: Player) {
     let game_name = match game_type {
         Game::Grand => "Grand",
         Game::Null => "Null",
+        Game::NullHand => "Null Hand",
+        Game::NullOuvert => "Null Ouvert",
+        Game::NullOuvertHand => "Null Ouvert Hand",
+        Game::Ramsch => "Ramsch",
         Game::Suit => "Clubs", // Fallback for Suit
     };
 
@@ -319,6 +281,21 @@ fn log_distribution(ctx: &GameContext, game_type: Game, start_player: Player) {
     println!("Start Player: {:?}", start_player);
 }
 
+/// Effective samples of a sampling agent for the card line, see
+/// `Decision::effective_samples`.
+fn effective_note(decision: &Decision) -> String {
+    match decision.effective_samples {
+        Some(effective) => format!("  eff:{:.1}", effective),
+        None => String::new(),
+    }
+}
+
+fn print_seats(agents: &[Box<dyn Agent>; 3]) {
+    for player in [Player::Declarer, Player::Left, Player::Right].iter() {
+        println!("Seat {}: {}", player_abbr(*player), agents[*player as usize].name());
+    }
+}
+
 fn player_abbr(p: Player) -> &'static str {
     match p {
         Player::Declarer => "D",
@@ -353,13 +330,13 @@ fn run_perfect_play(ctx: &GameContext, _game_type: Game) -> i16 {
             trick_buf.clear();
 
             // Null: game ends the moment the declarer takes any trick.
-            if ctx.game_type() == Game::Null && position.declarer_points > 0 {
+            if ctx.game_type().is_null() && position.declarer_points > 0 {
                 break;
             }
         }
     }
 
-    let final_val = if ctx.game_type() == Game::Null {
+    let final_val = if ctx.game_type().is_null() {
         if position.declarer_points > 0 {
             1
         } else {
@@ -372,61 +349,11 @@ fn run_perfect_play(ctx: &GameContext, _game_type: Game) -> i16 {
     final_val
 }
 
-// Facts Tracker Struct
-struct FactsTracker {
-    declarer: Facts,
-    left: Facts,
-    right: Facts,
-}
-
-impl FactsTracker {
-    fn new() -> Self {
-        Self {
-            declarer: Facts::zero_fact(),
-            left: Facts::zero_fact(),
-            right: Facts::zero_fact(),
-        }
-    }
-
-    fn update_voids(&mut self, played_card: u32, player: Player, trick_suit: u32, game_type: Game) {
-        if trick_suit == 0 {
-            return;
-        } // Lead, no voids
-
-        let card_suit = get_suit_for_card(played_card, game_type);
-        if card_suit != trick_suit {
-            // Player is VOID in trick_suit
-            let facts = match player {
-                Player::Declarer => &mut self.declarer,
-                Player::Left => &mut self.left,
-                Player::Right => &mut self.right,
-            };
-
-            // Map trick_suit to Facts fields
-            let trump = game_type.get_trump();
-            if trick_suit == trump {
-                facts.no_trump = true;
-            } else {
-                if (trick_suit & CLUBS) != 0 {
-                    facts.no_clubs = true;
-                } else if (trick_suit & SPADES) != 0 {
-                    facts.no_spades = true;
-                } else if (trick_suit & HEARTS) != 0 {
-                    facts.no_hearts = true;
-                } else if (trick_suit & DIAMONDS) != 0 {
-                    facts.no_diamonds = true;
-                }
-            }
-        }
-    }
-}
-
 fn run_pimc_play(
     initial_ctx: GameContext,
     _game_type: Game,
-    samples: u32,
     _perfect_benchmark_val: i16,
-    sampling_mode: crate::pimc::pimc_problem::SamplingMode,
+    agents: &mut [Box<dyn Agent>; 3],
 ) {
     let mut engine = SkatEngine::new(initial_ctx.clone(), None);
     let mut position = engine.create_initial_position();
@@ -436,14 +363,13 @@ fn run_pimc_play(
     let mut declarer_loss = 0i16;
     let mut opponent_loss = 0i16;
     let mut facts_tracker = FactsTracker::new();
-    let mut current_trick: Vec<(Player, u32)> = Vec::new();
+    let mut history: Vec<(Player, u32)> = Vec::new();
 
     println!("Starting PIMC Play...");
     while position.get_legal_moves() != 0 {
         // ── Trick separator ─────────────────────────────────────────────────────
         if cards_in_trick == 0 {
             trick_num += 1;
-            current_trick.clear();
             println!(
                 "-- Trick {:2} ({} leads) ----------------------------------------------------------------",
                 trick_num, player_abbr(position.player)
@@ -457,65 +383,10 @@ fn run_pimc_play(
             solve_optimum_from_position(&mut engine, &position, OptimumMode::BestValue).unwrap();
         let perfect_val = perfect_val_u8 as i16;
 
-        // 2. Build PIMC problem
-        let pred = match cur_player {
-            Player::Declarer => Player::Right,
-            Player::Left => Player::Declarer,
-            Player::Right => Player::Left,
-        };
-        let succ = match cur_player {
-            Player::Declarer => Player::Left,
-            Player::Left => Player::Right,
-            Player::Right => Player::Declarer,
-        };
-        let prev_c = current_trick
-            .iter()
-            .find(|(p, _)| *p == pred)
-            .map(|(_, c)| *c)
-            .unwrap_or(0);
-        let next_c = current_trick
-            .iter()
-            .find(|(p, _)| *p == succ)
-            .map(|(_, c)| *c)
-            .unwrap_or(0);
-
-        let all_game_cards_pimc = engine.context.declarer_cards()
-            | engine.context.left_cards()
-            | engine.context.right_cards();
-
-        // position.declarer_points already includes skat (added by create_initial_position
-        // at game start). The PIMC sample's create_initial_position will add skat again,
-        // so we subtract it here to avoid double-counting.
-        let pimc_decl_pts = position
-            .declarer_points
-            .saturating_sub(engine.context.get_skat().points());
-
-        let builder = PimcProblemBuilder::new(engine.context.game_type())
-            .my_player(cur_player)
-            .my_cards_val(position.player_cards)
-            .all_cards_val(all_game_cards_pimc & !position.played_cards)
-            .turn(cur_player)
-            .threshold(engine.context.points_to_win())
-            .declarer_start_points(pimc_decl_pts)
-            .trick_previous_player(position.trick_suit, prev_c)
-            .trick_next_player(next_c)
-            .facts(Player::Declarer, facts_tracker.declarer)
-            .facts(Player::Left, facts_tracker.left)
-            .facts(Player::Right, facts_tracker.right)
-            .sampling_mode(sampling_mode)
-            .declarer_played_cards(initial_ctx.declarer_cards() & !position.declarer_cards)
-            .all_played_cards(position.played_cards);
-
-        let mut search = PimcSearch::new(builder.build(), samples, None);
-        let probs = search.estimate_probability_of_all_cards(false);
-
-        // Pick best card
-        let pimc_card = if probs.is_empty() {
-            let (arr, _) = position.get_legal_moves().__decompose();
-            arr[0]
-        } else {
-            probs[0].0
-        };
+        // 2. Card of the seated agent
+        let observation = Observation::new(&initial_ctx, &position, &history, &facts_tracker);
+        let decision = agents[cur_player as usize].choose(&observation);
+        let pimc_card = decision.card;
 
         // 3. Compare with Benchmark
         let actual_val = if pimc_card == perfect_card {
@@ -523,7 +394,7 @@ fn run_pimc_play(
         } else {
             let child_pos = position.make_move(pimc_card, &engine.context);
             if child_pos.get_legal_moves() == 0 {
-                if engine.context.game_type() == Game::Null {
+                if engine.context.game_type().is_null() {
                     if child_pos.declarer_points > 0 { 1i16 } else { 0i16 }
                 } else {
                     child_pos.declarer_points as i16
@@ -536,7 +407,7 @@ fn run_pimc_play(
             }
         };
 
-        let raw_loss = if engine.context.game_type() == Game::Null {
+        let raw_loss = if engine.context.game_type().is_null() {
             if cur_player == Player::Declarer { actual_val - perfect_val } else { perfect_val - actual_val }
         } else {
             if cur_player == Player::Declarer { perfect_val - actual_val } else { actual_val - perfect_val }
@@ -554,21 +425,22 @@ fn run_pimc_play(
             position.trick_suit,
             engine.context.game_type(),
         );
-        current_trick.push((cur_player, pimc_card));
+        history.push((cur_player, pimc_card));
         position = position.make_move(pimc_card, &engine.context);
         cards_in_trick = (cards_in_trick + 1) % 3;
 
         // 5. Print card line
         let diff_mark = if pimc_card != perfect_card && loss > 0 { "(!)" } else { "   " };
         let who = if is_decl { "D" } else { "O" };
-        let probs_str: String = probs
+        let probs_str: String = decision
+            .ratings
             .iter()
-            .map(|(c, p)| format!("{}={:.0}%", c.__str(), p * 100.0))
+            .map(|(c, r)| format!("{}={}", c.__str(), r))
             .collect::<Vec<_>>()
             .join("  ");
 
         println!(
-            "  {} {}  PIMC:{} {}  perf:{} opt={:3}  loss={}({})  probs:[{}]",
+            "  {} {}  PIMC:{} {}  perf:{} opt={:3}  loss={}({})  probs:[{}]{}",
             player_abbr(cur_player),
             if pimc_card != perfect_card { "*" } else { " " },
             diff_mark,
@@ -578,6 +450,7 @@ fn run_pimc_play(
             loss,
             who,
             probs_str,
+            effective_note(&decision),
         );
 
         if cards_in_trick == 0 {
@@ -601,78 +474,19 @@ fn run_pimc_play(
 // Average-Points PIMC Playout
 // ---------------------------------------------------------------------------
 
-/// Returns the highest-set bit of `x` as an isolated bit mask.
-/// Panics if `x == 0`.
-#[inline]
-fn highest_bit(x: u32) -> u32 {
-    debug_assert!(x != 0);
-    1u32 << (31 - x.leading_zeros())
-}
-
-/// Returns `true` if `candidate_card` (single bit) would win the trick when
-/// played as the **last** (3rd) card.
-///
-/// `trick_cards_so_far` contains exactly 2 bits (the two already-played cards).
-/// `trick_suit` is the lead suit established by the first card of the trick.
-fn would_win_trick_regular(
-    candidate_card: u32,
-    trick_cards_so_far: u32,
-    trick_suit: u32,
-    game_type: Game,
-) -> bool {
-    if trick_cards_so_far == 0 {
-        return false;
-    }
-    let trump = game_type.get_trump();
-    let is_trump_played = (trick_cards_so_far & trump) != 0;
-    let candidate_is_trump = (candidate_card & trump) != 0;
-
-    if is_trump_played {
-        // Effective suit is trump: candidate must also be trump and beat the
-        // current best trump.
-        if !candidate_is_trump {
-            return false;
-        }
-        let trump_in_trick = trick_cards_so_far & trump;
-        return candidate_card > highest_bit(trump_in_trick);
-    }
-
-    // No trump played yet.
-    if candidate_is_trump {
-        // Playing trump on a non-trump trick always wins (legal play only).
-        return true;
-    }
-
-    // Neither trump played nor candidate is trump: compare within lead suit.
-    let candidate_in_lead = (candidate_card & trick_suit) != 0;
-    if !candidate_in_lead {
-        return false; // discarding off-suit – can never win
-    }
-
-    let lead_in_trick = trick_cards_so_far & trick_suit;
-    if lead_in_trick == 0 {
-        // Neither played card followed the lead suit – candidate is the only one.
-        return true;
-    }
-
-    candidate_card > highest_bit(lead_in_trick)
-}
-
-/// Entry point for the points-maximising PIMC playout.
-/// First runs `run_perfect_play` as a benchmark, then `run_pimc_points_play`.
+/// Entry point for the points playout of the `agents` of the declarer, left
+/// and right seat. First runs `run_perfect_play` as a benchmark, then
+/// `run_pimc_points_play`.
 pub fn run_points_playout(
     initial_context: GameContext,
     game_type: Game,
     start_player: Player,
-    samples: u32,
-    sampling_mode: crate::pimc::pimc_problem::SamplingMode,
-    point_strategy: PointStrategy,
+    agents: &mut [Box<dyn Agent>; 3],
 ) {
     println!("=== Points Playout Configuration ===");
     println!("Game Type: {:?}", game_type);
     println!("Start Player: {:?}", start_player);
-    println!("PIMC Samples: {}", samples);
-    println!("Strategy: {:?}", point_strategy);
+    print_seats(agents);
     println!("=====================================\n");
 
     log_distribution(&initial_context, game_type, start_player);
@@ -681,32 +495,24 @@ pub fn run_points_playout(
     let perfect_score = run_perfect_play(&initial_context, game_type);
 
     println!("\n=== PIMC Points Play Simulation ===");
-    run_pimc_points_play(
-        initial_context,
-        game_type,
-        samples,
-        perfect_score,
-        sampling_mode,
-        point_strategy,
-    );
+    run_pimc_points_play(initial_context, game_type, perfect_score, agents);
 }
 
-/// Like `run_pimc_play` but selects moves by **average expected declarer points**
-/// instead of binary win probability.
+/// Like `run_pimc_play` but measures the loss in points and logs the rating
+/// of every card next to its perfect-play value (`rating|perfect`), from the
+/// view of the player to move.
 fn run_pimc_points_play(
     initial_ctx: GameContext,
     _game_type: Game,
-    samples: u32,
     _perfect_benchmark_val: i16,
-    sampling_mode: crate::pimc::pimc_problem::SamplingMode,
-    point_strategy: PointStrategy,
+    agents: &mut [Box<dyn Agent>; 3],
 ) {
     let mut engine = SkatEngine::new(initial_ctx.clone(), None);
     let mut position = engine.create_initial_position();
     let mut trick_num = 0usize;
     let mut cards_in_trick = 0u8;
 
-    let mut current_trick = Vec::<(Player, u32)>::new();
+    let mut history = Vec::<(Player, u32)>::new();
     let mut declarer_loss = 0i16;
     let mut opponent_loss = 0i16;
     let mut facts_tracker = FactsTracker::new();
@@ -716,7 +522,6 @@ fn run_pimc_points_play(
         // ── Trick separator ─────────────────────────────────────────────────────
         if cards_in_trick == 0 {
             trick_num += 1;
-            current_trick.clear();
             println!(
                 "-- Trick {:2} ({} leads) ----------------------------------------------------------------",
                 trick_num, player_abbr(position.player)
@@ -733,243 +538,10 @@ fn run_pimc_points_play(
         let perfect_map: std::collections::HashMap<u32, u8> =
             all_perf.results.iter().map(|(c, _, v)| (*c, *v)).collect();
 
-        // ── PIMC problem ────────────────────────────────────────────────────────
-        let unplayed = position.get_all_unplayed_cards();
-        let pred = match cur_player {
-            Player::Declarer => Player::Right,
-            Player::Left => Player::Declarer,
-            Player::Right => Player::Left,
-        };
-        let succ = match cur_player {
-            Player::Declarer => Player::Left,
-            Player::Left => Player::Right,
-            Player::Right => Player::Declarer,
-        };
-        let prev_c = current_trick
-            .iter()
-            .find(|(p, _)| *p == pred)
-            .map(|(_, c)| *c)
-            .unwrap_or(0);
-        let next_c = current_trick
-            .iter()
-            .find(|(p, _)| *p == succ)
-            .map(|(_, c)| *c)
-            .unwrap_or(0);
-
-        // Use only the cards actually in the game, not ALLCARDS (excludes the 2 Skat cards)
-        let all_game_cards = engine.context.declarer_cards()
-            | engine.context.left_cards()
-            | engine.context.right_cards();
-
-        // position.declarer_points already includes skat (added by create_initial_position
-        // at game start). The PIMC sample's create_initial_position will add skat again,
-        // so we subtract it here to avoid double-counting.
-        let pimc_decl_pts = position
-            .declarer_points
-            .saturating_sub(engine.context.get_skat().points());
-
-        let builder = PimcProblemBuilder::new(engine.context.game_type())
-            .my_player(cur_player)
-            .my_cards_val(position.player_cards)
-            .all_cards_val(all_game_cards & !position.played_cards)
-            .turn(cur_player)
-            .threshold(engine.context.points_to_win())
-            .declarer_start_points(pimc_decl_pts)
-            .trick_previous_player(position.trick_suit, prev_c)
-            .trick_next_player(next_c)
-            .facts(Player::Declarer, facts_tracker.declarer)
-            .facts(Player::Left, facts_tracker.left)
-            .facts(Player::Right, facts_tracker.right)
-            .sampling_mode(sampling_mode)
-            .declarer_played_cards(initial_ctx.declarer_cards() & !position.declarer_cards)
-            .all_played_cards(position.played_cards);
-
-        let mut scores =
-            PimcSearch::new(builder.build(), samples, None).estimate_move_metrics(false);
-
-        // Tracks whether the trump heuristic overrode the pure avg-points best card.
-        // Only set to true for AverageWithHeuristic when the chosen card changes.
-        let mut trump_heuristic_overrode = false;
-
-        match point_strategy {
-            PointStrategy::Average => {
-                scores.sort_by(|a, b| {
-                    b.1.avg_points
-                        .partial_cmp(&a.1.avg_points)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
-            }
-            PointStrategy::Minimum => {
-                scores.sort_by(|a, b| {
-                    b.1.min_points
-                        .partial_cmp(&a.1.min_points)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
-            }
-            PointStrategy::Hybrid { delta, fallback } => {
-                // Sort primarily by win_prob. But if the difference is < delta, fallback to the secondary metric.
-                scores.sort_by(|a, b| {
-                    let win_cmp =
-                        b.1.win_prob
-                            .partial_cmp(&a.1.win_prob)
-                            .unwrap_or(std::cmp::Ordering::Equal);
-                    if win_cmp == std::cmp::Ordering::Equal
-                        || (b.1.win_prob - a.1.win_prob).abs() <= delta
-                    {
-                        match fallback {
-                            FallbackStrategy::Average => {
-                                b.1.avg_points
-                                    .partial_cmp(&a.1.avg_points)
-                                    .unwrap_or(std::cmp::Ordering::Equal)
-                            }
-                            FallbackStrategy::Minimum => {
-                                b.1.min_points
-                                    .partial_cmp(&a.1.min_points)
-                                    .unwrap_or(std::cmp::Ordering::Equal)
-                            }
-                        }
-                    } else {
-                        win_cmp
-                    }
-                });
-            }
-            PointStrategy::AverageWithHeuristic { threshold } => {
-                // ── Phase 1: sort by avg_points to establish the baseline winner ──
-                scores.sort_by(|a, b| {
-                    b.1.avg_points
-                        .partial_cmp(&a.1.avg_points)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
-                let pure_avg_best = scores.first().map(|(c, _)| *c);
-
-                if !scores.is_empty() {
-                    let best_pts = scores[0].1.avg_points;
-                    let game_type = engine.context.game_type();
-                    let trump_mask = game_type.get_trump();
-                    let is_declarer = cur_player == Player::Declarer;
-
-                    // Trump preference rules:
-                    //   Declarer always prefers trump (Grand + Suit).
-                    //   Opponents prefer non-trump ONLY in Suit games (not Grand –
-                    //   Grand's only trump is the four Jacks, avoiding them makes
-                    //   less tactical sense for defenders).
-                    let apply_trump_pref =
-                        is_declarer || game_type == Game::Suit;
-
-                    // ── Phase 2: card-value tiebreak for 3rd player ──────────
-                    // When we're last to play (cards_in_trick == 2) and all
-                    // threshold-eligible candidates share the same trick outcome
-                    // (all win or all lose), break ties by card point value:
-                    //   winning trick → play highest value  (collect more points fast)
-                    //   losing trick  → play lowest value   (don't waste good cards)
-                    let value_sort: Option<bool> = if cards_in_trick == 2 {
-                        let played: u32 = current_trick
-                            .iter()
-                            .map(|(_, c)| *c)
-                            .fold(0u32, |a, c| a | c);
-                        let ts = position.trick_suit;
-                        let in_range_outcomes: Vec<bool> = scores
-                            .iter()
-                            .filter(|(_, m)| m.avg_points >= best_pts - threshold)
-                            .map(|(c, _)| {
-                                would_win_trick_regular(*c, played, ts, game_type)
-                            })
-                            .collect();
-                        if in_range_outcomes.iter().all(|&w| w) {
-                            Some(true) // all win → pick highest card value
-                        } else if in_range_outcomes.iter().all(|&w| !w) {
-                            Some(false) // all lose → pick lowest card value
-                        } else {
-                            None // mixed – no value tiebreak
-                        }
-                    } else {
-                        None
-                    };
-
-                    // ── Phase 3: unified sort with four-level priority ────────
-                    scores.sort_by(|a, b| {
-                        use std::cmp::Ordering::*;
-                        let a_in = a.1.avg_points >= best_pts - threshold;
-                        let b_in = b.1.avg_points >= best_pts - threshold;
-
-                        // P1: in-range beats out-of-range
-                        if a_in != b_in {
-                            return if b_in { Greater } else { Less };
-                        }
-
-                        if a_in {
-                            // P2: trump preference
-                            if apply_trump_pref {
-                                let a_trump = (a.0 & trump_mask) != 0;
-                                let b_trump = (b.0 & trump_mask) != 0;
-                                if a_trump != b_trump {
-                                    let a_pref =
-                                        if is_declarer { a_trump } else { !a_trump };
-                                    let b_pref =
-                                        if is_declarer { b_trump } else { !b_trump };
-                                    if a_pref != b_pref {
-                                        return if b_pref { Greater } else { Less };
-                                    }
-                                }
-                            }
-
-                            // P3: card-value tiebreak (3rd player only)
-                            if let Some(prefer_high) = value_sort {
-                                let va = a.0.card_points();
-                                let vb = b.0.card_points();
-                                let vord = if prefer_high {
-                                    vb.cmp(&va) // DESC – highest value first
-                                } else {
-                                    va.cmp(&vb) // ASC – lowest value first
-                                };
-                                if vord != Equal {
-                                    return vord;
-                                }
-                            }
-                        }
-
-                        // P4: avg_points descending (baseline)
-                        b.1.avg_points
-                            .partial_cmp(&a.1.avg_points)
-                            .unwrap_or(Equal)
-                    });
-                }
-
-                // Detect override: heuristic chose a different card than pure avg.
-                trump_heuristic_overrode =
-                    scores.first().map(|(c, _)| *c) != pure_avg_best;
-            }
-        }
-
-        let (pimc_card, all_scores_str): (u32, Vec<(u32, String)>) = if scores.is_empty() {
-            let (arr, _) = position.get_legal_moves().__decompose();
-            (arr[0], vec![])
-        } else {
-            let str_scores = scores
-                .iter()
-                .map(|(c, m)| match point_strategy {
-                    PointStrategy::Average => (*c, format!("{:.0}", m.avg_points)),
-                    PointStrategy::Minimum => (*c, format!("{:.0}", m.min_points)),
-                    PointStrategy::Hybrid { fallback, .. } => {
-                        let fval = match fallback {
-                            FallbackStrategy::Average => m.avg_points,
-                            FallbackStrategy::Minimum => m.min_points,
-                        };
-                        (*c, format!("{:.0}%/{:.0}", m.win_prob * 100.0, fval))
-                    }
-                    PointStrategy::AverageWithHeuristic { .. } => {
-                        let decl_pv = perfect_map.get(c).copied().unwrap_or(0);
-                        let pv = if cur_player == Player::Declarer {
-                            decl_pv as i16
-                        } else {
-                            120 - decl_pv as i16
-                        };
-                        (*c, format!("{:.0}(\u{00B1}{:.1})|{}", m.avg_points, m.std_dev, pv))
-                    }
-                })
-                .collect();
-            (scores[0].0, str_scores)
-        };
+        // ── Card of the seated agent ────────────────────────────────────────────
+        let observation = Observation::new(&initial_ctx, &position, &history, &facts_tracker);
+        let decision = agents[cur_player as usize].choose(&observation);
+        let pimc_card = decision.card;
 
         // ── Loss ────────────────────────────────────────────────────────────────
         let actual_val = if pimc_card == perfect_card {
@@ -977,7 +549,7 @@ fn run_pimc_points_play(
         } else {
             let child = position.make_move(pimc_card, &engine.context);
             if child.get_legal_moves() == 0 {
-                if engine.context.game_type() == Game::Null {
+                if engine.context.game_type().is_null() {
                     if child.declarer_points > 0 {
                         1i16
                     } else {
@@ -993,7 +565,7 @@ fn run_pimc_points_play(
                 v as i16
             }
         };
-        let raw_loss = if engine.context.game_type() == Game::Null {
+        let raw_loss = if engine.context.game_type().is_null() {
             if cur_player == Player::Declarer {
                 actual_val - perfect_val
             } else {
@@ -1023,7 +595,7 @@ fn run_pimc_points_play(
             position.trick_suit,
             engine.context.game_type(),
         );
-        current_trick.push((cur_player, pimc_card));
+        history.push((cur_player, pimc_card));
         position = position.make_move(pimc_card, &engine.context);
         cards_in_trick = (cards_in_trick + 1) % 3;
 
@@ -1034,18 +606,28 @@ fn run_pimc_points_play(
         } else {
             "   "
         };
-        // "^" marks when the trump heuristic overrode the pure avg-points best card.
+        // "^" marks when a rule of the agent (e.g. the trump heuristic)
+        // overrode the best rated card.
         let perf_mark = if pimc_card != perfect_card { "*" } else { " " };
-        let trump_mark = if trump_heuristic_overrode { "^" } else { " " };
+        let trump_mark = if decision.overridden { "^" } else { " " };
         let who = if is_decl { "D" } else { "O" };
-        let scores_str: String = all_scores_str
+        let scores_str: String = decision
+            .ratings
             .iter()
-            .map(|(c, v)| format!("{}={}", c.__str(), v))
+            .map(|(c, v)| {
+                let decl_pv = perfect_map.get(c).copied().unwrap_or(0);
+                let pv = if cur_player == Player::Declarer {
+                    decl_pv as i16
+                } else {
+                    120 - decl_pv as i16
+                };
+                format!("{}={}|{}", c.__str(), v, pv)
+            })
             .collect::<Vec<_>>()
             .join("  ");
 
         println!(
-            "  {} {}{}  PIMC:{} {}  perf:{} opt={:3}  loss={}({})  avgs:[{}]",
+            "  {} {}{}  PIMC:{} {}  perf:{} opt={:3}  loss={}({})  avgs:[{}]{}",
             player_abbr(cur_player),
             perf_mark,
             trump_mark,
@@ -1056,6 +638,7 @@ fn run_pimc_points_play(
             loss,
             who,
             scores_str,
+            effective_note(&decision),
         );
 
         if cards_in_trick == 0 {
@@ -1079,18 +662,17 @@ fn run_pimc_points_play(
 // Null Playout
 // ─────────────────────────────────────────────────────────────────────────────
 
-/// Entry point for the Null-specific PIMC playout.
-use crate::pimc::pimc_problem::SamplingMode;
-
-/// Runs `run_perfect_play` as a benchmark, then `run_pimc_null_play`.
-pub fn run_null_playout(initial_context: GameContext, samples: u32, mode: SamplingMode) {
+/// Entry point for the Null playout of the `agents` of the declarer, left and
+/// right seat. Runs `run_perfect_play` as a benchmark, then
+/// `run_pimc_null_play`.
+pub fn run_null_playout(initial_context: GameContext, agents: &mut [Box<dyn Agent>; 3]) {
     let game_type = Game::Null;
     let start_player = initial_context.start_player();
 
     println!("=== Null Playout Configuration ===");
     println!("Game Type: Null");
     println!("Start Player: {:?}", start_player);
-    println!("PIMC Samples: {}", samples);
+    print_seats(agents);
     println!("===================================\n");
 
     log_distribution(&initial_context, game_type, start_player);
@@ -1099,201 +681,22 @@ pub fn run_null_playout(initial_context: GameContext, samples: u32, mode: Sampli
     let perfect_score = run_perfect_play(&initial_context, game_type);
 
     println!("\n=== PIMC Null Play Simulation ===");
-    run_pimc_null_play(initial_context, samples, perfect_score, mode);
-}
-
-/// Null-order rank: 7=1, 8=2, 9=3, J=4, Q=5, K=6, T=7, A=8.
-/// Lower rank = weaker card = loses easier (good for declarer to discard).
-fn null_rank(card: u32) -> u8 {
-    if (card & SEVENS) != 0 {
-        1
-    } else if (card & EIGHTS) != 0 {
-        2
-    } else if (card & NINES) != 0 {
-        3
-    } else if (card & JACKS) != 0 {
-        4
-    } else if (card & QUEENS) != 0 {
-        5
-    } else if (card & KINGS) != 0 {
-        6
-    } else if (card & TENS) != 0 {
-        7
-    } else {
-        8
-    } // Ace
-}
-
-/// Returns `true` if `card` would WIN the current trick under Null rules.
-///
-/// Uses NULL_CLUBS/NULL_SPADES/NULL_HEARTS/NULL_DIAMONDS which include Jacks
-/// (the regular CLUBS/SPADES/HEARTS/DIAMONDS constants exclude Jacks).
-fn would_win_trick_null(
-    card: u32,
-    trick_lead_suit: u32, // 0 if this player IS the lead
-    trick_best_card: u32, // current trick winner so far (0 if no card yet)
-) -> bool {
-    if trick_lead_suit == 0 || trick_best_card == 0 {
-        return false; // this player leads — not "beating" anyone yet
-    }
-
-    // Determine lead suit from trick_best_card using null-aware suit masks.
-    let lead_suit_mask = if (trick_best_card & NULL_CLUBS) != 0 {
-        NULL_CLUBS
-    } else if (trick_best_card & NULL_SPADES) != 0 {
-        NULL_SPADES
-    } else if (trick_best_card & NULL_HEARTS) != 0 {
-        NULL_HEARTS
-    } else if (trick_best_card & NULL_DIAMONDS) != 0 {
-        NULL_DIAMONDS
-    } else {
-        return false; // unknown suit — shouldn't happen
-    };
-
-    // Card must follow the lead suit to be able to win.
-    if (card & lead_suit_mask) == 0 {
-        return false;
-    }
-
-    // Follows suit — wins if its Null rank is strictly higher.
-    null_rank(card) > null_rank(trick_best_card)
-}
-
-/// Null card selection — constraint-first, then PIMC, then positional tiebreaker.
-///
-/// Step 0 – Hard constraints (applied regardless of probability):
-///   Declarer not leading:
-///     Must play a non-winning (safe) card if any exist.
-///     → candidate pool = safe cards; fallback: all cards (forced win).
-///   Opponent, teammate already winning (opponent_has_trick):
-///     Must NOT steal the trick from a teammate.
-///     → candidate pool = non-winning cards; fallback: all cards (forced win).
-///   Declarer leading / Opponent in all other cases:
-///     → candidate pool = all legal cards.
-///
-/// Step 1 – PIMC probability filter:
-///   Among the candidate pool, keep only those with maximum win-probability.
-///   (Irrelevant cards below the max are excluded.)
-///
-/// Step 2 – Positional tiebreaker (among max-prob candidates):
-///   Declarer leading            → LOWEST card.
-///   Declarer not leading        → HIGHEST card (dump dangerous high values).
-///   Opponent leading            → LOWEST card (kleine Karte anführen).
-///   Opponent, opponent_has_trick→ HIGHEST card (dump high values).
-///   Opponent, declarer winning  → HIGHEST non-winning card (let declarer keep stich).
-fn select_null_card(
-    probs: &[(u32, f32)],
-    legal_mask: u32,
-    is_declarer: bool,
-    trick_lead_suit: u32,
-    trick_best_card: u32,
-    opponent_has_trick: bool,
-) -> u32 {
-    let (arr, n) = legal_mask.__decompose();
-    let legal: Vec<u32> = (0..n).map(|i| arr[i]).collect();
-    let is_lead = trick_lead_suit == 0;
-
-    let min_card = |v: &Vec<u32>| {
-        v.iter()
-            .cloned()
-            .min_by_key(|&c| null_rank(c))
-            .unwrap_or(legal[0])
-    };
-    let max_card = |v: &Vec<u32>| {
-        v.iter()
-            .cloned()
-            .max_by_key(|&c| null_rank(c))
-            .unwrap_or(legal[0])
-    };
-    let non_winning_of = |pool: &Vec<u32>| -> Vec<u32> {
-        pool.iter()
-            .cloned()
-            .filter(|&c| !would_win_trick_null(c, trick_lead_suit, trick_best_card))
-            .collect()
-    };
-
-    // ── Step 0: build hard-constrained candidate pool ────────────────────────
-    let candidates: Vec<u32> = if is_declarer && !is_lead {
-        // Declarer must not win the trick (play safe).
-        let safe = non_winning_of(&legal);
-        if safe.is_empty() {
-            legal.clone()
-        } else {
-            safe
-        }
-    } else if !is_declarer && !is_lead && opponent_has_trick {
-        // Teammate already winning: use ALL cards — dump the highest, even if it
-        // oversteps the teammate (getting rid of dangerous high cards is the goal).
-        legal.clone()
-    } else if !is_declarer && !is_lead && !opponent_has_trick {
-        // Declarer currently winning — opponent must let him keep it.
-        let non_win = non_winning_of(&legal);
-        if non_win.is_empty() {
-            legal.clone()
-        } else {
-            non_win
-        }
-    } else {
-        legal.clone() // leading: all cards are valid
-    };
-
-    // ── Step 1: keep only max-probability candidates ─────────────────────────
-    let max_prob_candidates: Vec<u32> = if probs.is_empty() {
-        candidates.clone()
-    } else {
-        let max_p = probs
-            .iter()
-            .filter(|(c, _)| candidates.contains(c))
-            .map(|(_, p)| *p)
-            .fold(f32::NEG_INFINITY, f32::max);
-        let top: Vec<u32> = probs
-            .iter()
-            // Threshold 0.015: with 100 samples, 1 sample = 1% = 0.01 difference;
-            // treat cards within ~1 sample of each other as equal probability.
-            .filter(|(c, p)| candidates.contains(c) && (*p - max_p).abs() < 0.015)
-            .map(|(c, _)| *c)
-            .collect();
-        if top.is_empty() {
-            candidates.clone()
-        } else {
-            top
-        }
-    };
-
-    // ── Step 2: positional tiebreaker among max-prob candidates ──────────────
-    if is_declarer {
-        if is_lead {
-            min_card(&max_prob_candidates) // lead: stay small
-        } else {
-            min_card(&max_prob_candidates) // safe pool filtered; play lowest safe
-        }
-    } else if is_lead {
-        min_card(&max_prob_candidates) // opponent leads: kleine Karte
-    } else if opponent_has_trick {
-        max_card(&max_prob_candidates) // teammate has trick: dump highest
-    } else {
-        // Declarer currently winning: non-winning pool already filtered; dump highest.
-        max_card(&max_prob_candidates)
-    }
+    run_pimc_null_play(initial_context, perfect_score, agents);
 }
 
 fn run_pimc_null_play(
     initial_ctx: GameContext,
-    samples: u32,
     _perfect_score: i16,
-    mode: SamplingMode,
+    agents: &mut [Box<dyn Agent>; 3],
 ) {
     let mut engine = SkatEngine::new(initial_ctx.clone(), None);
     let mut position = engine.create_initial_position();
     let mut trick_num = 0usize;
     let mut cards_in_trick = 0u8;
 
-    let mut current_trick = Vec::<(Player, u32)>::new();
+    let mut history = Vec::<(Player, u32)>::new();
     let mut facts_tracker = FactsTracker::new();
 
-    // The card currently "winning" the trick and its lead suit.
-    let mut trick_best_card = 0u32;
-
     let mut pimc_declarer_took = false; // did PIMC-declarer ever take a trick?
 
     println!("Starting PIMC Null Play...");
@@ -1301,8 +704,6 @@ fn run_pimc_null_play(
         // ── Trick separator ───────────────────────────────────────────────────
         if cards_in_trick == 0 {
             trick_num += 1;
-            current_trick.clear();
-            trick_best_card = 0;
             println!(
                 "-- Trick {:2} ({} leads) --",
                 trick_num,
@@ -1316,97 +717,16 @@ fn run_pimc_null_play(
         let (perfect_card, _, _) =
             solve_optimum_from_position(&mut engine, &position, OptimumMode::BestValue).unwrap();
 
-        // ── PIMC problem setup ────────────────────────────────────────────────
-        let unplayed = position.get_all_unplayed_cards();
-        let pred = match cur_player {
-            Player::Declarer => Player::Right,
-            Player::Left => Player::Declarer,
-            Player::Right => Player::Left,
-        };
-        let succ = match cur_player {
-            Player::Declarer => Player::Left,
-            Player::Left => Player::Right,
-            Player::Right => Player::Declarer,
-        };
-        let prev_c = current_trick
-            .iter()
-            .find(|(p, _)| *p == pred)
-            .map(|(_, c)| *c)
-            .unwrap_or(0);
-        let next_c = current_trick
-            .iter()
-            .find(|(p, _)| *p == succ)
-            .map(|(_, c)| *c)
-            .unwrap_or(0);
-
-        // Use only the cards actually in the game, not ALLCARDS (excludes the 2 Skat cards)
-        let all_game_cards_null = engine.context.declarer_cards()
-            | engine.context.left_cards()
-            | engine.context.right_cards();
-
-        let builder = PimcProblemBuilder::new(engine.context.game_type())
-            .my_player(cur_player)
-            .my_cards_val(position.player_cards)
-            .all_cards_val(all_game_cards_null & !position.played_cards)
-            .turn(cur_player)
-            .threshold(engine.context.points_to_win())
-            .declarer_start_points(position.declarer_points)
-            .trick_previous_player(position.trick_suit, prev_c)
-            .trick_next_player(next_c)
-            .facts(Player::Declarer, facts_tracker.declarer)
-            .facts(Player::Left, facts_tracker.left)
-            .facts(Player::Right, facts_tracker.right)
-            .sampling_mode(mode);
-
-        let problem = builder.build();
-        let search = PimcSearch::new(problem.clone(), samples, None);
-
-        // --- DEBUG SKAT ROTATION ---
-        let ctx = problem.generate_concrete_problem();
-        let total_cards =
-            ctx.declarer_cards() | ctx.left_cards() | ctx.right_cards() | ctx.trick_cards();
-        if total_cards.count_ones() != 30 {
-            eprintln!(
-                "WARNING: Total cards in PIMC is {}! Skat size = {}",
-                total_cards.count_ones(),
-                32 - total_cards.count_ones()
-            );
-        }
-        // ---------------------------
-
-        let probs = search.estimate_probability_of_all_cards(false);
-
-        // ── 3-tier card selection ─────────────────────────────────────────────
-        let legal_mask = position.get_legal_moves();
-        let is_declarer = cur_player == Player::Declarer;
-
-        // Is the current trick already won by an opponent?
-        // True when trick_best_card != 0 AND the player who played it is not the Declarer.
-        let opponent_has_trick = trick_best_card != 0
-            && current_trick
-                .iter()
-                .any(|(p, c)| *c == trick_best_card && *p != Player::Declarer);
-
-        let pimc_card = select_null_card(
-            &probs,
-            legal_mask,
-            is_declarer,
-            position.trick_suit,
-            trick_best_card,
-            opponent_has_trick,
-        );
-
-        // ── Update trick-winner tracking ──────────────────────────────────────
-        if would_win_trick_null(pimc_card, position.trick_suit, trick_best_card)
-            || position.trick_suit == 0
-        {
-            trick_best_card = pimc_card;
-        }
+        // ── Card of the seated agent ──────────────────────────────────────────
+        let observation = Observation::new(&initial_ctx, &position, &history, &facts_tracker);
+        let decision = agents[cur_player as usize].choose(&observation);
+        let pimc_card = decision.card;
 
         // ── Probability string ────────────────────────────────────────────────
-        let probs_str: String = probs
+        let probs_str: String = decision
+            .ratings
             .iter()
-            .map(|(c, p)| format!("{}={:.0}%", c.__str(), p * 100.0))
+            .map(|(c, r)| format!("{}={}", c.__str(), r))
             .collect::<Vec<_>>()
             .join("  ");
 
@@ -1414,12 +734,13 @@ fn run_pimc_null_play(
         let diff_mark = if pimc_card != perfect_card { "*" } else { " " };
 
         println!(
-            "  {} {}  PIMC:{} perf:{}  probs:[{}]",
+            "  {} {}  PIMC:{} perf:{}  probs:[{}]{}",
             player_abbr(cur_player),
             diff_mark,
             pimc_card.__str(),
             perfect_card.__str(),
             probs_str,
+            effective_note(&decision),
         );
 
         // ── Update state ──────────────────────────────────────────────────────
@@ -1429,7 +750,7 @@ fn run_pimc_null_play(
             position.trick_suit,
             engine.context.game_type(),
         );
-        current_trick.push((cur_player, pimc_card));
+        history.push((cur_player, pimc_card));
         position = position.make_move(pimc_card, &engine.context);
         cards_in_trick = (cards_in_trick + 1) % 3;
 
@@ -1473,3 +794,85 @@ fn run_pimc_null_play(
     // Emit the standard Total-Point-Loss line so the Python parser works.
     println!("Total Point Loss: {} (D:{} O:{})", loss, loss, 0);
 }
+
+/// Plays a Ramsch with the `agents` of the declarer, left and right seat.
+/// Every agent sees the game from the declarer seat, see
+/// `Observation::pimc_problem`. The Skat is unknown to everybody.
+pub fn run_ramsch_playout(initial_context: GameContext, agents: &mut [Box<dyn Agent>; 3]) {
+    let game_type = Game::Ramsch;
+    let start_player = initial_context.start_player();
+
+    println!("=== Ramsch Playout Configuration ===");
+    println!("Start Player: {:?}", start_player);
+    print_seats(agents);
+    println!("=====================================\n");
+
+    log_distribution(&initial_context, game_type, start_player);
+
+    let mut position = initial_context.create_initial_position();
+    let mut facts_tracker = FactsTracker::new();
+    let mut history = Vec::<(Player, u32)>::new();
+
+    println!("\n=== Ramsch Play Simulation ===");
+    while position.get_legal_moves() != 0 {
+        if position.trick_cards == 0 {
+            println!(
+                "-- Trick {:2} ({} leads) --",
+                history.len() / 3 + 1,
+                player_abbr(position.player)
+            );
+        }
+
+        let cur_player = position.player;
+        let observation = Observation::new(&initial_context, &position, &history, &facts_tracker);
+        let decision = agents[cur_player as usize].choose(&observation);
+        let card = decision.card;
+
+        let ratings_str: String = decision
+            .ratings
+            .iter()
+            .map(|(c, r)| format!("{}={}", c.__str(), r))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!(
+            "  {}  {}  ratings:[{}]{}",
+            player_abbr(cur_player),
+            card.__str(),
+            ratings_str,
+            effective_note(&decision)
+        );
+
+        facts_tracker.update_voids(card, cur_player, position.trick_suit, game_type);
+        history.push((cur_player, card));
+        position = position.make_move(card, &initial_context);
+    }
+
+    let played: Vec<u32> = history.iter().map(|(_, card)| *card).collect();
+    let tricks = collect_tricks(
+        &played,
+        game_type,
+        initial_context.declarer_cards(),
+        initial_context.left_cards(),
+        initial_context.right_cards(),
+    );
+    let outcome = RamschOutcome::from_tricks(&tricks, initial_context.get_skat());
+    let settlement = settle_ramsch(&outcome);
+
+    println!("\nRamsch Finished.");
+    for player in [Player::Declarer, Player::Left, Player::Right].iter() {
+        let i = *player as usize;
+        println!(
+            "  {}: {:3} points, {:2} tricks, score {}",
+            player_abbr(*player),
+            outcome.points[i],
+            outcome.tricks[i],
+            settlement.scores[i]
+        );
+    }
+    if let Some(player) = settlement.durchmarsch {
+        println!("Durchmarsch by {}", player_abbr(player));
+    }
+    if settlement.jungfrau > 0 {
+        println!("Jungfrau: {}", settlement.jungfrau);
+    }
+}
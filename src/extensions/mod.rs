@@ -1,6 +1,8 @@
 pub mod all_games;
 
+pub mod agent;
 pub mod cli_playout;
 pub mod playout;
 pub mod skat_solving;
 pub mod solver;
+pub mod tournament;
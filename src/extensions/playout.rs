@@ -43,6 +43,7 @@ pub fn playout(engine: &mut SkatEngine) -> Vec<PlayoutLine> {
 
     while i < n {
         let mut row: PlayoutLine = Default::default();
+        engine.new_search();
 
         row.declarer_cards = position.declarer_cards;
         row.left_cards = position.left_cards;
@@ -100,6 +101,7 @@ pub fn playout_all_cards(engine: &mut SkatEngine) -> Vec<PlayoutAllCardsRetLine>
     while i < n {
         // Assuming n cards to play.
         let mut row: PlayoutAllCardsRetLine = Default::default();
+        engine.new_search();
 
         let mut cnt: Counters = Counters::new();
 
@@ -117,7 +119,10 @@ pub fn playout_all_cards(engine: &mut SkatEngine) -> Vec<PlayoutAllCardsRetLine>
             let (best_response, val) = engine.search(&child_pos, &mut cnt, 0, 120);
             results.push((*mov, best_response, val));
         }
-        let resall = SolveAllCardsRet { results };
+        let resall = SolveAllCardsRet {
+            results,
+            counters: cnt,
+        };
 
         row.player = position.player;
         row.best_card = best_card;
@@ -139,6 +144,7 @@ impl Default for SolveAllCardsRet {
     fn default() -> Self {
         SolveAllCardsRet {
             results: Vec::new(),
+            counters: Counters::new(),
         }
     }
 }
@@ -75,11 +75,16 @@ pub fn solve_with_skat(
     let twelve_cards = twelve_cards_bitmask.__decompose_twelve();
 
     let mut alpha = 0;
-    if game == Game::Null {
+    if game.is_null() {
         alpha = 1;
     }
 
-    let skat_combinations = generate_skat_combinations(&twelve_cards);
+    // Hand games are played with the skat as dealt; there is nothing to choose.
+    let skat_combinations = if game.is_hand() {
+        hand_skat_combination(skatcards_bitmask)
+    } else {
+        generate_skat_combinations(&twelve_cards)
+    };
 
     for (skat_card_1, skat_card_2) in skat_combinations {
         let skat_bitmask = skat_card_1 | skat_card_2;
@@ -115,7 +120,7 @@ pub fn solve_with_skat(
 
         if (accelerating_mode == AccelerationMode::AlphaBetaAccelerating
             || accelerating_mode == AccelerationMode::WinningOnly)
-            && game == Game::Null
+            && game.is_null()
             && game_value == 0
         {
             break;
@@ -137,7 +142,7 @@ fn evaluate_skat_combination(
     let mut lower = 0;
     let mut upper = 120;
 
-    if game != Game::Null {
+    if !game.is_null() {
         match mode {
             AccelerationMode::AlphaBetaAccelerating => {
                 if alpha > skat_value {
@@ -159,14 +164,14 @@ fn evaluate_skat_combination(
     }
 
     let result = match game {
-        Game::Null => solve_double_dummy(engine, 0, 1, 1),
+        g if g.is_null() => solve_double_dummy(engine, 0, 1, 1),
         _ => solve_double_dummy(engine, lower, upper, 1),
     };
 
     cnt.add(result.counters);
 
     match game {
-        Game::Null => result.best_value,
+        g if g.is_null() => result.best_value,
         _ => result.best_value + skat_value,
     }
 }
@@ -180,7 +185,7 @@ fn update_best_skat(
     alpha: &mut u8,
 ) {
     match game {
-        Game::Null => {
+        g if g.is_null() => {
             if game_value < *alpha || ret.best_skat.is_none() {
                 ret.best_skat = Some(SolveWithSkatRetLine {
                     skat_card_1,
@@ -213,6 +218,14 @@ pub fn generate_skat_combinations(cards: &[u32]) -> Vec<(u32, u32)> {
     combinations
 }
 
+fn hand_skat_combination(skat: u32) -> Vec<(u32, u32)> {
+    if skat.count_ones() != 2 {
+        return Vec::new();
+    }
+    let skat_card_1 = skat & skat.wrapping_neg();
+    vec![(skat_card_1, skat ^ skat_card_1)]
+}
+
 use crate::skat::context::ProblemTransformation;
 
 #[derive(Clone, Debug)]
@@ -235,6 +248,9 @@ pub fn solve_best_game_all_variants(
     let games_to_check = vec![
         (Game::Grand, None, "Grand"),
         (Game::Null, None, "Null"),
+        (Game::NullHand, None, "Null Hand"),
+        (Game::NullOuvert, None, "Null Ouvert"),
+        (Game::NullOuvertHand, None, "Null Ouvert Hand"),
         (Game::Suit, None, "Clubs"),
         (
             Game::Suit,
@@ -3,9 +3,9 @@
 //! High-level solving functions built on top of the SkatEngine.
 
 use crate::skat::counters::Counters;
-use crate::skat::defs::Game;
 use crate::skat::engine::SkatEngine;
 use crate::skat::position::Position;
+use crate::skat::search::Variation;
 use crate::traits::Points;
 
 // Return types (formerly retargs)
@@ -15,14 +15,28 @@ pub struct SolveWinRet {
     pub counters: Counters,
 }
 
+pub struct SolveTargetsRet {
+    pub declarer_wins: bool,
+    pub schneider: bool,
+    pub schwarz: bool,
+    pub avoids_schneider: bool,
+    pub counters: Counters,
+}
+
 pub struct SolveRet {
     pub best_card: u32,
     pub best_value: u8,
     pub counters: Counters,
 }
 
+pub struct SolvePvRet {
+    pub variation: Variation,
+    pub counters: Counters,
+}
+
 pub struct SolveAllCardsRet {
     pub results: Vec<(u32, u32, u8)>, // card, follow_up, value
+    pub counters: Counters,
 }
 
 // -----------------------------------------------------------------------------
@@ -35,7 +49,7 @@ pub fn solve_win(engine: &mut SkatEngine) -> SolveWinRet {
     let mut alpha = engine.context.points_to_win() - 1;
     let mut beta = engine.context.points_to_win();
 
-    if engine.context.game_type() == Game::Null {
+    if engine.context.game_type().is_null() {
         alpha = 0;
         beta = 1;
     }
@@ -49,7 +63,7 @@ pub fn solve_win(engine: &mut SkatEngine) -> SolveWinRet {
 
     let mut declarer_wins = value > alpha;
 
-    if engine.context.game_type() == Game::Null {
+    if engine.context.game_type().is_null() {
         declarer_wins = !declarer_wins;
     }
 
@@ -60,6 +74,43 @@ pub fn solve_win(engine: &mut SkatEngine) -> SolveWinRet {
     }
 }
 
+/// Answers all game targets of the declarer in one call: win (threshold of the
+/// context), Schneider (90), Schwarz (all tricks) and avoiding Schneider (31).
+/// Null games only have the win target; the Schneider fields are reported as
+/// not reached and Schneider as avoided.
+pub fn solve_targets(engine: &mut SkatEngine) -> SolveTargetsRet {
+    let win = solve_win(engine);
+    let mut cnt = win.counters;
+
+    if engine.context.game_type().is_null() {
+        return SolveTargetsRet {
+            declarer_wins: win.declarer_wins,
+            schneider: false,
+            schwarz: false,
+            avoids_schneider: true,
+            counters: cnt,
+        };
+    }
+
+    let position = engine.create_initial_position();
+    let mut reaches = |points: u8| -> bool {
+        let (_, value) = engine.search(&position, &mut cnt, points - 1, points);
+        value > points - 1
+    };
+
+    let schneider = win.declarer_wins && reaches(90);
+    let avoids_schneider = win.declarer_wins || reaches(31);
+    let schwarz = schneider && engine.search_schwarz(&position, &mut cnt).1;
+
+    SolveTargetsRet {
+        declarer_wins: win.declarer_wins,
+        schneider,
+        schwarz,
+        avoids_schneider,
+        counters: cnt,
+    }
+}
+
 pub fn solve_double_dummy(engine: &mut SkatEngine, alpha: u8, beta: u8, width: u8) -> SolveRet {
     let mut cnt = Counters::new();
     let mut result = (0u32, 0u8);
@@ -89,6 +140,18 @@ pub fn solve(engine: &mut SkatEngine) -> SolveRet {
     solve_double_dummy(engine, 0, 120, 1)
 }
 
+/// Solves the game and returns the optimal line of play to the end of the hand.
+pub fn solve_pv(engine: &mut SkatEngine) -> SolvePvRet {
+    let mut cnt = Counters::new();
+    let position = engine.create_initial_position();
+    let variation = engine.search_variation(&position, &mut cnt);
+
+    SolvePvRet {
+        variation,
+        counters: cnt,
+    }
+}
+
 pub fn solve_all_cards(engine: &mut SkatEngine, alpha: u8, beta: u8) -> SolveAllCardsRet {
     let position = engine.create_initial_position();
     solve_all_cards_from_position(engine, &position, alpha, beta)
@@ -122,7 +185,102 @@ pub fn solve_all_cards_from_position(
         results.push((*mov, best_response, value));
     }
 
-    SolveAllCardsRet { results }
+    SolveAllCardsRet {
+        results,
+        counters: cnt,
+    }
+}
+
+/// Finds the exact value with zero-window probes (MTD(f)), see `search_mtdf`.
+pub fn solve_mtdf(engine: &mut SkatEngine) -> SolveRet {
+    let mut cnt = Counters::new();
+    let position = engine.create_initial_position();
+    let (best_card, best_value) = search_mtdf(engine, &position, &mut cnt, None);
+
+    SolveRet {
+        best_card,
+        best_value,
+        counters: cnt,
+    }
+}
+
+/// MTD(f): narrows the bounds of the exact value of `position` with
+/// zero-window searches. The first probe tests `guess`, each following one
+/// the bound returned by the probe before. All probes share the engine's
+/// transposition table, so later probes mostly re-read bounds stored by
+/// earlier ones.
+///
+/// The card is the one proving the value for the player to move.
+pub fn search_mtdf(
+    engine: &mut SkatEngine,
+    position: &Position,
+    cnt: &mut Counters,
+    guess: Option<u8>,
+) -> (u32, u8) {
+    let game = engine.context.game_type();
+    let mut lower = 0u8;
+    let mut upper = if game.is_null() { 1 } else { 120 };
+
+    // Player to move maximizes the declarer points
+    let maximizes =
+        (position.player == Player::Declarer) != (game.is_null() || game == Game::Ramsch);
+
+    let mut test = guess.unwrap_or(upper / 2 + 1).clamp(lower + 1, upper);
+    let mut best_card = 0;
+    let mut fallback_card = 0;
+
+    while lower < upper {
+        let (card, value) = engine.search(position, cnt, test - 1, test);
+        fallback_card = card;
+
+        if value >= test {
+            lower = value;
+            if maximizes {
+                best_card = card;
+            }
+        } else {
+            upper = value;
+            if !maximizes {
+                best_card = card;
+            }
+        }
+
+        // Next probe right at the bound just found
+        test = if value >= test { lower + 1 } else { upper };
+    }
+
+    if best_card == 0 {
+        best_card = fallback_card;
+    }
+    (best_card, lower)
+}
+
+/// Like `solve_all_cards_from_position` with exact values from `search_mtdf`.
+/// Every card starts from the value of the card before.
+pub fn solve_all_cards_mtdf_from_position(
+    engine: &mut SkatEngine,
+    position: &Position,
+) -> SolveAllCardsRet {
+    let mut results = Vec::new();
+
+    let moves_word = position.get_legal_moves();
+    let (moves, n) = crate::skat::rules::get_sorted_by_value(moves_word);
+
+    let mut cnt = Counters::new();
+    let mut guess = None;
+
+    for mov in &moves[0..n] {
+        let child_pos = position.make_move(*mov, &engine.context);
+        let (best_response, value) = search_mtdf(engine, &child_pos, &mut cnt, guess);
+        guess = Some(value);
+
+        results.push((*mov, best_response, value));
+    }
+
+    SolveAllCardsRet {
+        results,
+        counters: cnt,
+    }
 }
 
 pub fn solve_and_add_skat(engine: &mut SkatEngine) -> SolveRet {
@@ -135,13 +293,29 @@ pub fn solve_and_add_skat(engine: &mut SkatEngine) -> SolveRet {
 // OPTIMUM SOLVER
 // -----------------------------------------------------------------------------
 
-use crate::skat::defs::Player;
-use crate::skat::search::search_optimum;
+use crate::skat::defs::{Game, Player};
+use crate::skat::search::{search_optimum, MoveOrder};
+use crate::skat::tt::SharedTranspositionTable;
+use rayon::prelude::*;
 
+/// Candidates of the optimum search and how their exact values are found:
+/// with a full window (`BestValue`, `AllWinning`) or with zero-window probes
+/// (the `Mtdf` variants, see `search_mtdf`).
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum OptimumMode {
     BestValue,
     AllWinning,
+    BestValueMtdf,
+    AllWinningMtdf,
+}
+
+impl OptimumMode {
+    pub fn uses_mtdf(&self) -> bool {
+        matches!(
+            self,
+            OptimumMode::BestValueMtdf | OptimumMode::AllWinningMtdf
+        )
+    }
 }
 
 pub fn solve_optimum(
@@ -158,7 +332,11 @@ pub fn solve_optimum_from_position(
     mode: OptimumMode,
 ) -> Result<(u32, i16, u8), &'static str> {
     // Phase 1: Get all outcomes (Exact values)
-    let phase1_results = solve_all_cards_from_position(engine, position, 0, 120);
+    let phase1_results = if mode.uses_mtdf() {
+        solve_all_cards_mtdf_from_position(engine, position)
+    } else {
+        solve_all_cards_from_position(engine, position, 0, 120)
+    };
 
     if phase1_results.results.is_empty() {
         return Err("No legal moves found");
@@ -166,13 +344,13 @@ pub fn solve_optimum_from_position(
 
     // Filter Logic
     let candidates: Vec<u32> = match mode {
-        OptimumMode::BestValue => {
+        OptimumMode::BestValue | OptimumMode::BestValueMtdf => {
             // Find best value based on player perspective?
             // solve_all_cards returns 'Value' (Declarer Points).
             // If Declarer: Max Value is best.
             // If Defender: Min Value is best.
             let is_declarer = position.player == Player::Declarer;
-            let is_null = engine.context.game_type() == Game::Null;
+            let is_null = engine.context.game_type().is_null();
 
             if is_declarer {
                 if is_null {
@@ -239,9 +417,9 @@ pub fn solve_optimum_from_position(
                 }
             }
         }
-        OptimumMode::AllWinning => {
+        OptimumMode::AllWinning | OptimumMode::AllWinningMtdf => {
             let is_declarer = position.player == Player::Declarer;
-            let is_null = engine.context.game_type() == Game::Null;
+            let is_null = engine.context.game_type().is_null();
 
             if is_null {
                 // Null Win Condition:
@@ -297,8 +475,6 @@ pub fn solve_optimum_from_position(
         return Err("No candidates after filtering");
     }
 
-    let mut cnt = Counters::new(); // Local counters for phase 2
-    let mut tt = crate::skat::tt::TranspositionTable::new();
     let is_declarer = position.player == Player::Declarer;
 
     // Use i16::MIN/MAX for score tracking
@@ -309,19 +485,60 @@ pub fn solve_optimum_from_position(
     let mut best_move = candidates[0];
     let mut best_score_so_far = if is_declarer { i16::MIN } else { i16::MAX };
 
-    for mov in candidates {
-        let child_pos = position.make_move(mov, &engine.context);
-
-        let (_, score) = search_optimum(
-            &engine.context,
-            &child_pos,
-            &mut tt,
-            &mut cnt,
-            i16::MIN + 1,
-            i16::MAX - 1,
-            1, // Depth 1
+    // With several search threads the candidates are searched in parallel on a shared table.
+    let scores: Vec<(u32, i16)> = if engine.threads() > 1 {
+        let tt = SharedTranspositionTable::with_capacity(engine.tt.capacity(), engine.tt.policy());
+        let context = &engine.context;
+        let ordering = engine.move_ordering();
+        engine.install(|| {
+            candidates
+                .par_iter()
+                .map(|mov| {
+                    let mut shared = &tt;
+                    let mut order = MoveOrder::new(ordering, context.game_type);
+                    let mut cnt = Counters::new();
+                    let child_pos = position.make_move(*mov, context);
+                    let (_, score) = search_optimum(
+                        context,
+                        &child_pos,
+                        &mut shared,
+                        &mut order,
+                        &mut cnt,
+                        i16::MIN + 1,
+                        i16::MAX - 1,
+                        1, // Depth 1
+                    );
+                    (*mov, score)
+                })
+                .collect()
+        })
+    } else {
+        let mut cnt = Counters::new(); // Local counters for phase 2
+        let mut tt = crate::skat::tt::TranspositionTable::with_capacity(
+            engine.tt.capacity(),
+            engine.tt.policy(),
         );
+        let mut order = MoveOrder::new(engine.move_ordering(), engine.context.game_type);
+        candidates
+            .iter()
+            .map(|mov| {
+                let child_pos = position.make_move(*mov, &engine.context);
+                let (_, score) = search_optimum(
+                    &engine.context,
+                    &child_pos,
+                    &mut tt,
+                    &mut order,
+                    &mut cnt,
+                    i16::MIN + 1,
+                    i16::MAX - 1,
+                    1, // Depth 1
+                );
+                (*mov, score)
+            })
+            .collect()
+    };
 
+    for (mov, score) in scores {
         if is_declarer {
             if score > best_score_so_far {
                 best_score_so_far = score;
@@ -0,0 +1,370 @@
+//! # Tournament
+//!
+//! Duplicate tournament of two or more agents. Every deal is played once
+//! for each seating of the agents, so that each agent holds every hand of
+//! the deal and card luck cancels out. With more than three agents, every
+//! three of them take the table in turn. The games are scored in the
+//! Seeger-Fabian system (see `seeger_fabian`).
+//!
+//! The deals are the only unit of independent results: the games of one
+//! deal share their cards. The intervals of the standings are therefore
+//! taken over the scores per deal.
+
+use rand::rngs::StdRng;
+use rand::Rng;
+use serde::Serialize;
+
+use crate::extensions::agent::{create_agent, Agent, AgentSettings, Observation};
+use crate::extensions::cli_playout::generate_random_deal;
+use crate::pimc::facts::FactsTracker;
+use crate::pimc::pimc_problem::SamplingMode;
+use crate::pimc::pimc_search::{half_width, Z_95};
+use crate::skat::context::GameContext;
+use crate::skat::defs::{Game, Player};
+use crate::skat::scoring::{collect_tricks, seeger_fabian, settle, Contract, GameOutcome};
+
+/// The leading player rotates with the deals.
+const START_PLAYERS: [&str; 3] = ["declarer", "left", "right"];
+
+/// One game of the tournament.
+#[derive(Clone, Debug, Serialize)]
+pub struct GameRecord {
+    pub deal: u32,
+    pub start_player: Player,
+    /// Agent (index) of the declarer, left and right seat.
+    pub seats: [usize; 3],
+    pub declarer_points: u8,
+    pub declarer_tricks: u8,
+    pub won: bool,
+    pub game_value: u16,
+    /// Seeger-Fabian scores of the seats.
+    pub scores: [i32; 3],
+}
+
+impl GameRecord {
+    pub fn to_csv_header() -> String {
+        "Deal,StartPlayer,Declarer,Left,Right,DeclarerPoints,DeclarerTricks,Won,GameValue,DeclarerScore,LeftScore,RightScore".to_string()
+    }
+
+    /// Row of the game with the names of the `agents`.
+    pub fn to_csv_row(&self, agents: &[String]) -> String {
+        format!(
+            "{},{:?},{},{},{},{},{},{},{},{},{},{}",
+            self.deal,
+            self.start_player,
+            agents[self.seats[0]],
+            agents[self.seats[1]],
+            agents[self.seats[2]],
+            self.declarer_points,
+            self.declarer_tricks,
+            self.won,
+            self.game_value,
+            self.scores[0],
+            self.scores[1],
+            self.scores[2]
+        )
+    }
+}
+
+/// Results of one agent over all games.
+#[derive(Clone, Debug, Serialize)]
+pub struct Standing {
+    pub agent: String,
+    pub games: u32,
+    pub declarer_games: u32,
+    pub declarer_wins: u32,
+    /// Games as defender the declarer lost.
+    pub defender_wins: u32,
+    pub total: i64,
+    /// Mean score per deal (all its games) and its 95% interval, infinite
+    /// for a single deal.
+    pub mean_per_deal: f32,
+    pub interval: (f32, f32),
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TournamentReport {
+    pub agents: Vec<String>,
+    pub game_type: Game,
+    pub deals: u32,
+    pub games: Vec<GameRecord>,
+    /// Sorted by total score, the best agent first.
+    pub standings: Vec<Standing>,
+}
+
+pub struct Tournament {
+    agents: Vec<String>,
+    settings: AgentSettings,
+    game_type: String,
+    sampling_mode: SamplingMode,
+}
+
+impl Tournament {
+    /// Tournament of the agent kinds of `create_agent` with `settings`,
+    /// playing Grand deals unless set otherwise.
+    pub fn new(agents: &[&str], settings: AgentSettings) -> Result<Self, String> {
+        if agents.len() < 2 {
+            return Err(format!("Expected at least 2 agents, got {}", agents.len()));
+        }
+        for kind in agents {
+            create_agent(kind, &settings, 0)?;
+        }
+
+        Ok(Tournament {
+            agents: agents.iter().map(|kind| kind.trim().to_string()).collect(),
+            settings,
+            game_type: "grand".to_string(),
+            sampling_mode: SamplingMode::Random,
+        })
+    }
+
+    /// Game type of the deals (see `generate_random_deal`) and the filter of
+    /// the declarer hands. Ramsch has no declarer to score.
+    pub fn with_game(
+        mut self,
+        game_type: &str,
+        sampling_mode: SamplingMode,
+    ) -> Result<Self, String> {
+        if game_type.eq_ignore_ascii_case("ramsch") {
+            return Err("Ramsch cannot be played in a tournament".to_string());
+        }
+        self.game_type = game_type.to_string();
+        self.sampling_mode = sampling_mode;
+        Ok(self)
+    }
+
+    /// Agents of the declarer, left and right seat in the games of a deal:
+    /// the permutations of every three agents, or each of two agents once as
+    /// declarer against the other.
+    pub fn seatings(&self) -> Vec<[usize; 3]> {
+        let n = self.agents.len();
+        if n == 2 {
+            return vec![[0, 1, 1], [1, 0, 0]];
+        }
+
+        let mut seatings = Vec::new();
+        for a in 0..n {
+            for b in a + 1..n {
+                for c in b + 1..n {
+                    seatings.extend_from_slice(&[
+                        [a, b, c],
+                        [a, c, b],
+                        [b, a, c],
+                        [b, c, a],
+                        [c, a, b],
+                        [c, b, a],
+                    ]);
+                }
+            }
+        }
+        seatings
+    }
+
+    /// Plays `deals` deals from `rng` under every seating and calls
+    /// `on_game` after each game.
+    pub fn run<F>(&self, deals: u32, rng: &mut StdRng, mut on_game: F) -> TournamentReport
+    where
+        F: FnMut(&GameRecord),
+    {
+        let mut games = Vec::new();
+        let mut game_type = Game::Grand;
+
+        for deal in 0..deals {
+            let start_player = START_PLAYERS[deal as usize % 3].to_string();
+            let (context, g, _) = generate_random_deal(
+                self.game_type.clone(),
+                start_player,
+                self.sampling_mode,
+                rng,
+            );
+            game_type = g;
+
+            for seats in self.seatings() {
+                let record = self.play_game(deal, &context, seats, rng);
+                on_game(&record);
+                games.push(record);
+            }
+        }
+
+        TournamentReport {
+            standings: self.standings(deals, &games),
+            agents: self.agents.clone(),
+            game_type,
+            deals,
+            games,
+        }
+    }
+
+    fn play_game(
+        &self,
+        deal: u32,
+        context: &GameContext,
+        seats: [usize; 3],
+        rng: &mut StdRng,
+    ) -> GameRecord {
+        let game_type = context.game_type();
+        let settings = AgentSettings {
+            null_rules: game_type.is_null(),
+            ..self.settings
+        };
+        let mut agents: Vec<Box<dyn Agent>> = seats
+            .iter()
+            .map(|&agent| create_agent(&self.agents[agent], &settings, rng.gen()).unwrap())
+            .collect();
+
+        let played = play(context, &mut agents);
+
+        let skat = context.get_skat();
+        let tricks = collect_tricks(
+            &played,
+            game_type,
+            context.declarer_cards(),
+            context.left_cards(),
+            context.right_cards(),
+        );
+        let outcome = GameOutcome::from_tricks(&tricks, skat);
+        let settlement = settle(
+            &Contract::new(game_type).with_doubling(context.doubling()),
+            context.declarer_cards() | skat,
+            &outcome,
+            0,
+        );
+
+        GameRecord {
+            deal,
+            start_player: context.start_player(),
+            seats,
+            declarer_points: outcome.declarer_points,
+            declarer_tricks: outcome.declarer_tricks.unwrap_or(0),
+            won: settlement.won,
+            game_value: settlement.game_value,
+            scores: seeger_fabian(&settlement),
+        }
+    }
+
+    fn standings(&self, deals: u32, games: &[GameRecord]) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = (0..self.agents.len())
+            .map(|agent| {
+                let mut per_deal = vec![0i64; deals as usize];
+                let mut standing = Standing {
+                    agent: self.agents[agent].clone(),
+                    games: 0,
+                    declarer_games: 0,
+                    declarer_wins: 0,
+                    defender_wins: 0,
+                    total: 0,
+                    mean_per_deal: 0.0,
+                    interval: (0.0, 0.0),
+                };
+
+                for game in games.iter().filter(|game| game.seats.contains(&agent)) {
+                    let score: i64 = (0..3)
+                        .filter(|&seat| game.seats[seat] == agent)
+                        .map(|seat| game.scores[seat] as i64)
+                        .sum();
+                    per_deal[game.deal as usize] += score;
+                    standing.total += score;
+                    standing.games += 1;
+
+                    if game.seats[Player::Declarer as usize] == agent {
+                        standing.declarer_games += 1;
+                        standing.declarer_wins += game.won as u32;
+                    } else {
+                        standing.defender_wins += !game.won as u32;
+                    }
+                }
+
+                let n = per_deal.len().max(1) as f32;
+                let mean = per_deal.iter().sum::<i64>() as f32 / n;
+                let variance = per_deal
+                    .iter()
+                    .map(|&score| (score as f32 - mean).powi(2))
+                    .sum::<f32>()
+                    / n;
+                let ci = half_width(variance.sqrt(), n, Z_95);
+                standing.mean_per_deal = mean;
+                standing.interval = (mean - ci, mean + ci);
+                standing
+            })
+            .collect();
+
+        standings.sort_by_key(|standing| std::cmp::Reverse(standing.total));
+        standings
+    }
+}
+
+/// Plays the game of `context` from its start with the agents of the
+/// declarer, left and right seat; returns the cards in playing order.
+fn play(context: &GameContext, agents: &mut [Box<dyn Agent>]) -> Vec<u32> {
+    let mut position = context.create_initial_position();
+    let mut facts = FactsTracker::new();
+    let mut history: Vec<(Player, u32)> = Vec::new();
+
+    while position.player_cards != 0 {
+        let player = position.player;
+        let observation = Observation::new(context, &position, &history, &facts);
+        let card = agents[player as usize].choose(&observation).card;
+
+        facts.update_voids(card, player, position.trick_suit, context.game_type());
+        history.push((player, card));
+        position = position.make_move(card, context);
+    }
+    history.into_iter().map(|(_, card)| card).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::Tournament;
+    use crate::extensions::agent::AgentSettings;
+    use crate::pimc::pimc_problem::SamplingMode;
+
+    #[test]
+    fn test_duplicate_tournament() {
+        let settings = AgentSettings::new(5);
+        assert!(Tournament::new(&["rules"], settings).is_err());
+        assert!(Tournament::new(&["rules", "oracle"], settings).is_err());
+
+        let tournament = Tournament::new(&["rules", "random", "random"], settings)
+            .unwrap()
+            .with_game("clubs", SamplingMode::SmartSuit)
+            .unwrap();
+        let mut rng = StdRng::seed_from_u64(3);
+        let report = tournament.run(2, &mut rng, |_| {});
+
+        assert_eq!(report.games.len(), 12);
+        for standing in &report.standings {
+            assert_eq!(standing.games, 12);
+            assert_eq!(standing.declarer_games, 4);
+            assert!(standing.interval.0 <= standing.mean_per_deal);
+        }
+        // Every game hands out its scores to the agents of its seats
+        let total: i64 = report
+            .games
+            .iter()
+            .flat_map(|g| g.scores)
+            .map(|s| s as i64)
+            .sum();
+        assert_eq!(report.standings.iter().map(|s| s.total).sum::<i64>(), total);
+        assert!(report.games.iter().all(|g| g.declarer_points <= 120));
+    }
+
+    #[test]
+    fn test_every_three_agents_take_the_table() {
+        let settings = AgentSettings::new(5);
+        let tournament =
+            Tournament::new(&["rules", "random", "rules", "random"], settings).unwrap();
+        assert_eq!(tournament.seatings().len(), 24);
+
+        let mut rng = StdRng::seed_from_u64(5);
+        let report = tournament.run(1, &mut rng, |_| {});
+        assert_eq!(report.games.len(), 24);
+        for standing in &report.standings {
+            // Three of the four tables, six seatings each
+            assert_eq!(standing.games, 18);
+            assert_eq!(standing.declarer_games, 6);
+        }
+    }
+}
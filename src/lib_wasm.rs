@@ -1,13 +1,36 @@
+use crate::extensions::agent::{Agent, IsmctsAgent, Observation, PerfectAgent};
 use crate::extensions::solver::{solve_optimum_from_position, OptimumMode};
+use crate::pimc::facts::FactsTracker;
+use crate::pimc::ismcts::{IsmctsConfig, RolloutPolicy};
 use crate::skat::context::{GameContext, ProblemTransformation};
+use crate::skat::control::SearchControl;
+use crate::skat::counters::Counters;
 use crate::skat::defs::{Game, Player, ALLCARDS};
 use crate::skat::engine::SkatEngine;
 use crate::skat::position::Position;
+use crate::skat::scoring::Doubling;
+use crate::skat::tt::{ReplacementPolicy, TranspositionTable};
 use crate::traits::{BitConverter, Bitboard, Points, StringConverter};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
 use std::panic;
 use wasm_bindgen::prelude::*;
 
+/// Transposition table size in the browser; the default size of the native
+/// build is larger than most open-hand positions of a played game need.
+const WASM_TT_MB: usize = 8;
+
+/// Default limits of the AI move and hint searches, so hard positions do not
+/// freeze the browser tab; see `SkatGame::set_search_limits`.
+const WASM_SEARCH_NODES: u64 = 5_000_000;
+const WASM_SEARCH_MILLIS: u64 = 3_000;
+
+fn new_engine(context: GameContext) -> SkatEngine {
+    let tt = TranspositionTable::with_megabytes(WASM_TT_MB, ReplacementPolicy::DepthPreferred);
+    SkatEngine::new(context, Some(tt))
+}
+
 #[wasm_bindgen]
 pub fn init_panic_hook() {
     console_error_panic_hook::set_once();
@@ -51,6 +74,8 @@ pub struct GameStateJson {
     pub skat_cards: String,
     pub move_history: Vec<MoveLogEntry>, // New Log
     pub legal_moves: Vec<String>,
+    /// Kontra/Re said so far: "None", "Kontra" or "Re".
+    pub doubling: String,
     pub debug_info: String, // Debug/Mode Info
 }
 
@@ -58,6 +83,8 @@ pub struct GameStateJson {
 pub struct HintJson {
     pub best_card: String,
     pub value: i32,
+    /// False if the search was interrupted by the search limits.
+    pub exact: bool,
 }
 
 #[derive(Serialize)]
@@ -69,7 +96,7 @@ pub struct BestGameResult {
 }
 
 #[wasm_bindgen]
-pub fn calc_best_game_12(my_12_cards_str: &str, samples: u32) -> JsValue {
+pub fn calc_best_game_12(my_12_cards_str: &str, samples: u32, seed: u32) -> JsValue {
     // Deprecated? User wants Perfect Info now.
     // Keeping for compatibility or we can redirect to perfect info if samples provided?
     // User said "Do not use PIMC".
@@ -84,6 +111,7 @@ pub fn calc_best_game_12(my_12_cards_str: &str, samples: u32) -> JsValue {
         my_12_cards_str,
         Player::Declarer,
         samples,
+        seed as u64,
         None,
         false,
     );
@@ -127,11 +155,26 @@ pub struct SkatGame {
 
     // Transposition
     active_transformation: Option<ProblemTransformation>,
+
+    // Search limits of AI moves and hints, 0 is unlimited
+    search_nodes: u64,
+    search_millis: u64,
+
+    // ISMCTS iterations of AI moves, 0 plays with open cards
+    ai_iterations: u32,
+
+    // Seed of the deal
+    seed: u32,
 }
 
 #[wasm_bindgen]
 impl SkatGame {
     pub fn new_random() -> SkatGame {
+        Self::new_seeded(rand::random())
+    }
+
+    /// Deals the cards from `seed`, the same seed gives the same game.
+    pub fn new_seeded(seed: u32) -> SkatGame {
         let (mut deck, _count) = ALLCARDS.__decompose();
 
         let mut deck_vec = deck.to_vec();
@@ -155,7 +198,7 @@ impl SkatGame {
         // I will double check `new_random` logic to ensure no cards are lost.
         // Actually, let's explicitely verify the bitwise OR in `get_state_json` is working on valid data.
 
-        let mut rng = rand::thread_rng();
+        let mut rng = StdRng::seed_from_u64(seed as u64);
         let mut deck_vec = Vec::with_capacity(32);
         for i in 0..32 {
             deck_vec.push(1u32 << i);
@@ -203,7 +246,7 @@ impl SkatGame {
         let skat_points = skat_cards.points();
         // context.set_declarer_start_points(skat_points); // Not yet, Skat not pressed
 
-        let engine = SkatEngine::new(context, None);
+        let engine = new_engine(context);
         let current_position = engine.create_initial_position();
 
         SkatGame {
@@ -225,9 +268,18 @@ impl SkatGame {
             game_selection_phase: true,
             initial_deal: Some(initial_deal_context),
             active_transformation: None,
+            search_nodes: WASM_SEARCH_NODES,
+            search_millis: WASM_SEARCH_MILLIS,
+            ai_iterations: 0,
+            seed,
         }
     }
 
+    /// Seed of the deal, to replay the game with `new_seeded`.
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
     pub fn calculate_best_game_perfect_info(&self) -> JsValue {
         if self.initial_deal.is_none() {
             return JsValue::NULL;
@@ -237,7 +289,6 @@ impl SkatGame {
         let my_12_cards = deal.declarer_cards | self.skat_cards;
 
         use crate::extensions::skat_solving::{solve_best_game_all_variants, AccelerationMode};
-        use crate::skat::defs::Game;
 
         let results_info = solve_best_game_all_variants(
             my_12_cards, // We pass 12 cards as declarer_cards
@@ -253,7 +304,7 @@ impl SkatGame {
             // Calculate "Win Rate" (for Null 0 is Win, for others >= 61)
             // We'll normalize to 1.0 = Win, 0.0 = Loss for UI consistency
             let is_win = match info.game_type {
-                Game::Null => info.value == 0,
+                g if g.is_null() => info.value == 0,
                 _ => info.value >= 61,
             };
 
@@ -291,6 +342,9 @@ impl SkatGame {
         let (game_type, transform) = match game_type_str {
             "Grand" => (Game::Grand, None),
             "Null" => (Game::Null, None),
+            "Null Hand" => (Game::NullHand, None),
+            "Null Ouvert" => (Game::NullOuvert, None),
+            "Null Ouvert Hand" => (Game::NullOuvertHand, None),
             "Clubs" => (Game::Suit, None),
             "Spades" => (Game::Suit, Some(ProblemTransformation::SpadesSwitch)),
             "Hearts" => (Game::Suit, Some(ProblemTransformation::HeartsSwitch)),
@@ -368,14 +422,14 @@ impl SkatGame {
         );
 
         // Points Calculation
-        if game_type != Game::Null {
+        if !game_type.is_null() {
             context.set_declarer_start_points(final_skat.points());
             context.set_threshold_upper(120);
         } else {
             context.set_threshold_upper(1);
         }
 
-        self.engine = SkatEngine::new(context, None);
+        self.engine = new_engine(context);
         self.current_position = self.engine.create_initial_position();
         self.skat_cards = final_skat; // Store in Engine Representation
         self.game_selection_phase = false;
@@ -515,6 +569,7 @@ impl SkatGame {
             skat_cards: skat_str,
             move_history: self.get_move_history_json_transformed(), // New helper needed
             legal_moves: legal_strs,
+            doubling: format!("{:?}", self.engine.context.doubling()),
             debug_info: debug_info_str,
         };
 
@@ -615,12 +670,7 @@ impl SkatGame {
             return false;
         }
 
-        // This is safe because `solve_best_move` creates its own mutable engine copy or uses &self.
-        // Wait, solve_best_move might need &mut self?
-        // `solve_best_move` calls `solve_optimum_from_position` which takes `&mut engine`.
-        // So `self.solve_best_move()` needs `&mut self`?
-        // Yes, likely.
-        let (best_card, _) = self.solve_best_move();
+        let best_card = self.agent_move();
         if best_card == 0 {
             return false;
         }
@@ -628,13 +678,42 @@ impl SkatGame {
         self.perform_move(best_card, &pos)
     }
 
+    /// Lets the AI play without seeing the other hands: with `iterations`
+    /// of ISMCTS per card (rollouts with the heuristic policy). 0 goes back
+    /// to perfect play with open cards.
+    pub fn set_ai_ismcts(&mut self, iterations: u32) {
+        self.ai_iterations = iterations;
+    }
+
+    /// Kontra of the defenders; false once something was said, during the
+    /// game selection or after the game.
+    pub fn say_kontra(&mut self) -> bool {
+        self.announce(Doubling::Kontra)
+    }
+
+    /// Re of the declarer, only after a Kontra.
+    pub fn say_re(&mut self) -> bool {
+        self.announce(Doubling::Re)
+    }
+
+    fn announce(&mut self, doubling: Doubling) -> bool {
+        if self.game_selection_phase || self.is_game_over() {
+            return false;
+        }
+        if self.engine.context.doubling().next() != Some(doubling) {
+            return false;
+        }
+        self.engine.context.set_doubling(doubling);
+        true
+    }
+
     fn get_game_label(&self) -> String {
         use crate::skat::context::ProblemTransformation::*;
         use crate::skat::defs::Game;
 
         match (self.engine.context.game_type, self.active_transformation) {
             (Game::Grand, _) => "Grand".to_string(),
-            (Game::Null, _) => "Null".to_string(),
+            (g, _) if g.is_null() => g.convert_to_string(),
             (Game::Suit, None) => "Clubs".to_string(),
             (Game::Suit, Some(SpadesSwitch)) => "Spades".to_string(),
             (Game::Suit, Some(HeartsSwitch)) => "Hearts".to_string(),
@@ -657,6 +736,9 @@ impl SkatGame {
             "Clubs" => (Game::Suit, None),
             "Grand" => (Game::Grand, None),
             "Null" => (Game::Null, None),
+            "Null Hand" => (Game::NullHand, None),
+            "Null Ouvert" => (Game::NullOuvert, None),
+            "Null Ouvert Hand" => (Game::NullOuvertHand, None),
             _ => (Game::Suit, None),
         };
 
@@ -704,7 +786,7 @@ impl SkatGame {
         let trans = self.active_transformation;
         let mut sorted_str = Vec::new();
 
-        if game_type == Game::Null {
+        if game_type.is_null() {
             // Null Sort: Suits (C, S, H, D) -> Ranks (A, K, Q, J, 10, 9, 8, 7)
             // Iterate Suits: Clubs, Spades, Hearts, Diamonds
             let suits_data = [
@@ -820,7 +902,7 @@ impl SkatGame {
     pub fn calculate_max_points(&mut self) -> u8 {
         // Recalculate if 0 or dummy? Assuming 120 is dummy.
         // Actually, just run it.
-        let mut temp_engine = SkatEngine::new(self.engine.context, None);
+        let mut temp_engine = new_engine(self.engine.context);
         // We need INITIAL position for max points of the DEAL.
         // current_position might be mid-game.
         // But Max Points usually means "Max points achieveable from START with open cards"?
@@ -879,7 +961,7 @@ impl SkatGame {
                 };
 
                 // Solve
-                let mut temp_engine = SkatEngine::new(self.engine.context, None);
+                let mut temp_engine = new_engine(self.engine.context);
                 let res = solve_optimum_from_position(
                     &mut temp_engine,
                     &pos_to_solve,
@@ -972,11 +1054,19 @@ impl SkatGame {
         }
     }
 
+    /// Limits the searches of AI moves and hints to `max_nodes` nodes and
+    /// `max_millis` milliseconds; 0 removes a limit.
+    pub fn set_search_limits(&mut self, max_nodes: u32, max_millis: u32) {
+        self.search_nodes = max_nodes as u64;
+        self.search_millis = max_millis as u64;
+    }
+
     pub fn get_hint_json(&self) -> JsValue {
-        let (card, val) = self.solve_best_move();
+        let (card, val, exact) = self.solve_best_move_within_limits();
         let hint = HintJson {
             best_card: card.__str(),
-            value: val as i32,
+            value: val,
+            exact,
         };
         serde_wasm_bindgen::to_value(&hint).unwrap()
     }
@@ -989,8 +1079,60 @@ impl SkatGame {
             && pos.trick_cards == 0
     }
 
+    /// Best card, its value and whether the value is exact, searched within
+    /// the search limits.
+    fn solve_best_move_within_limits(&self) -> (u32, i32, bool) {
+        let mut control = SearchControl::unlimited();
+        if self.search_nodes > 0 {
+            control = control.with_node_budget(self.search_nodes);
+        }
+        if self.search_millis > 0 {
+            control = control.with_time_limit(std::time::Duration::from_millis(self.search_millis));
+        }
+
+        let mut temp_engine = new_engine(self.engine.context);
+        let variation =
+            temp_engine.search_anytime(&self.current_position, &mut Counters::new(), &control);
+        (
+            variation.best_card(),
+            variation.value as i32,
+            variation.is_complete(),
+        )
+    }
+
+    /// Card of the AI agent of the player to move: ISMCTS over the cards the
+    /// player cannot see, or perfect play within the search limits.
+    fn agent_move(&self) -> u32 {
+        let context = &self.engine.context;
+        let mut agent: Box<dyn Agent> = if self.ai_iterations > 0 {
+            let config =
+                IsmctsConfig::new(self.ai_iterations).with_rollout(RolloutPolicy::Heuristic);
+            let seed = ((self.seed as u64) << 32) | self.move_sequence.len() as u64;
+            Box::new(IsmctsAgent::new(config, seed))
+        } else {
+            Box::new(
+                PerfectAgent::new()
+                    .with_engine(new_engine(*context))
+                    .with_limits(self.search_nodes, self.search_millis),
+            )
+        };
+
+        let mut facts = FactsTracker::new();
+        for ((before, _), (card, who)) in self.history.iter().zip(&self.move_sequence) {
+            facts.update_voids(*card, *who, before.trick_suit, context.game_type());
+        }
+        let history: Vec<(Player, u32)> = self
+            .move_sequence
+            .iter()
+            .map(|(card, who)| (*who, *card))
+            .collect();
+
+        let observation = Observation::new(context, &self.current_position, &history, &facts);
+        agent.choose(&observation).card
+    }
+
     fn solve_best_move(&self) -> (u32, i32) {
-        let mut temp_engine = SkatEngine::new(self.engine.context, None);
+        let mut temp_engine = new_engine(self.engine.context);
         let pos = self.current_position;
         let res = solve_optimum_from_position(&mut temp_engine, &pos, OptimumMode::BestValue);
 
@@ -1013,7 +1155,7 @@ impl SkatGame {
         let mut results = Vec::new();
         let legal_mask = pos.get_legal_moves();
 
-        let mut temp_engine = SkatEngine::new(self.engine.context, None);
+        let mut temp_engine = new_engine(self.engine.context);
 
         for i in 0..32 {
             let card_bit = 1 << i;
@@ -1,19 +1,26 @@
 mod args;
 
 use clap::Parser;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use skat_aug23::consts::bitboard::*;
-use skat_aug23::extensions::solver::{solve, solve_optimum, solve_win, OptimumMode};
+use skat_aug23::extensions::agent::{create_seats, Agent, AgentSettings, PointStrategy};
+use skat_aug23::extensions::solver::{solve, solve_optimum, solve_pv, solve_win, OptimumMode};
 use skat_aug23::pimc::analysis::{
     analyze_general_pre_discard, analyze_hand, analyze_hand_with_pickup, analyze_null_detailed,
 };
+use skat_aug23::pimc::doubling::advise_doubling;
 use skat_aug23::pimc::facts::Facts;
+use skat_aug23::pimc::ismcts::{IsmctsConfig, RolloutPolicy};
 use skat_aug23::pimc::pimc_problem_builder::PimcProblemBuilder;
-use skat_aug23::pimc::pimc_search::PimcSearch;
+use skat_aug23::pimc::pimc_search::{AdaptiveSampling, Estimator, MoveMetrics, PimcSearch};
 use skat_aug23::skat::context::GameContext;
+use skat_aug23::skat::counters::Counters;
 use skat_aug23::skat::defs::Player;
 use skat_aug23::skat::defs::{CLUBS, DIAMONDS, HEARTS, SPADES};
 use skat_aug23::skat::engine::SkatEngine;
+use skat_aug23::skat::scoring::{settle, Contract, Doubling, GameOutcome, Settlement};
 use skat_aug23::skat::signature::HandSignature;
 use skat_aug23::traits::{BitConverter, Bitboard, Points, StringConverter};
 use std::fs;
@@ -22,10 +29,23 @@ use std::io::Write;
 fn main() {
     let output = args::Cli::parse();
 
+    // All random choices of a command come from this generator.
+    let seed = output.seed.unwrap_or_else(rand::random);
+    eprintln!("Seed: {}", seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let adaptive = output.max_samples.map(AdaptiveSampling::up_to);
+    let estimator = output
+        .alpha_mu
+        .map_or(Estimator::Pimc, |max_moves| Estimator::AlphaMu {
+            max_moves,
+        });
+    let agents = output.agents;
+
     match output.command {
         args::Commands::ValueCalc {
             context,
             optimum_mode,
+            engine: engine_args,
         } => {
             let context_content = fs::read_to_string(context).expect("Unable to read context file");
             let input: args::GameContextInput =
@@ -56,7 +76,8 @@ fn main() {
                             game_context.game_type()
                         );
                         match game_context.game_type() {
-                            skat_aug23::skat::defs::Game::Grand => {
+                            skat_aug23::skat::defs::Game::Grand
+                            | skat_aug23::skat::defs::Game::Ramsch => {
                                 eprintln!("DEBUG: ValueCalc Using TRUMP_GRAND");
                                 skat_aug23::consts::bitboard::TRUMP_GRAND
                             }
@@ -82,19 +103,20 @@ fn main() {
                     + game_context.right_cards().count_ones()
                     + game_context.trick_cards().count_ones();
 
+                // The initial position already credits the Skat to the declarer.
                 if total_cards == 30
-                    && game_context.game_type() != skat_aug23::skat::defs::Game::Null
+                    && !game_context.game_type().is_null()
                 {
                     let skat = game_context.get_skat();
-                    let points = skat.points();
-                    println!(
-                        "Auto-Skat: Determined {} points in Skat. Adding to declarer start points.",
-                        points
-                    );
-                    game_context.set_declarer_start_points(points);
+                    println!("Auto-Skat: Determined {} points in Skat.", skat.points());
                 }
             }
 
+            if let Some(contract_input) = &input.contract {
+                let contract = apply_contract(contract_input, Contract::new(input.game_type));
+                game_context.set_doubling(contract.doubling);
+            }
+
             let _threshold_upper = match input.mode.as_ref() {
                 Some(args::SearchMode::Win) => 61,
                 Some(args::SearchMode::Value) => 120,
@@ -106,15 +128,18 @@ fn main() {
                 std::process::exit(1);
             }
 
-            let mut engine = SkatEngine::new(game_context, None);
+            let mut engine = create_engine(&engine_args, game_context);
+            let mut cnt = Counters::new();
 
             if let Some(opt_str) = optimum_mode {
                 let opt_mode = match opt_str.to_lowercase().as_str() {
                     "best_value" => OptimumMode::BestValue,
                     "all_winning" => OptimumMode::AllWinning,
+                    "best_value_mtdf" => OptimumMode::BestValueMtdf,
+                    "all_winning_mtdf" => OptimumMode::AllWinningMtdf,
                     _ => {
                         eprintln!(
-                            "Invalid optimum mode: {}. Use 'best_value' or 'all_winning'.",
+                            "Invalid optimum mode: {}. Use 'best_value', 'all_winning', 'best_value_mtdf' or 'all_winning_mtdf'.",
                             opt_str
                         );
                         std::process::exit(1);
@@ -136,6 +161,7 @@ fn main() {
                 match mode {
                     args::SearchMode::Win => {
                         let result = solve_win(&mut engine);
+                        cnt.add(result.counters);
                         println!(
                             "Declarer Wins: {}, Best Card: {}",
                             result.declarer_wins,
@@ -144,14 +170,42 @@ fn main() {
                     }
                     args::SearchMode::Value => {
                         let result = solve(&mut engine);
+                        cnt.add(result.counters);
                         println!(
                             "Value: {}, Best Card: {}",
                             result.best_value,
                             result.best_card.__str()
                         );
+
+                        let pv_result = solve_pv(&mut engine);
+                        cnt.add(pv_result.counters);
+                        let pv = pv_result.variation;
+                        let line: Vec<String> = pv.cards.iter().map(|c| c.__str()).collect();
+                        println!("Principal Variation: {}", line.join(" "));
+
+                        // Matadors are only known for a full deal
+                        let context = &engine.context;
+                        if context.declarer_cards().count_ones() == 10
+                            && context.trick_cards() == 0
+                            && context.get_skat().count_ones() == 2
+                        {
+                            let settlement = settle_value(
+                                &input.contract,
+                                Contract::new(context.game_type())
+                                    .with_doubling(context.doubling()),
+                                context.declarer_cards() | context.get_skat(),
+                                result.best_value,
+                            );
+                            println!("{}", format_settlement(&settlement));
+                        }
                     }
                 }
             }
+
+            if engine_args.tt_stats {
+                println!("{}", format_tt_stats(&engine, &cnt));
+            }
+            persist_engine(&engine_args, &engine);
         }
         args::Commands::AnalyzeNull {
             count,
@@ -174,6 +228,7 @@ fn main() {
                 count,
                 samples,
                 hand,
+                rng.gen(),
                 move |(hand_val, skat_val, won, points, moves, probs, duration, start_player)| {
                     let mut moves_probs_str = String::new();
                     for (i, card_val) in moves.iter().enumerate() {
@@ -295,7 +350,6 @@ fn main() {
 
             writeln!(writer, "{}", HandSignature::to_csv_header()).unwrap();
 
-            let mut rng = rand::thread_rng();
             // We need 10 cards from 32.
             // Loop count times
 
@@ -319,10 +373,10 @@ fn main() {
                 }
 
                 let (sig, prob, _) = if hand {
-                    let (s, p) = analyze_hand(my_hand, samples);
+                    let (s, p) = analyze_hand(my_hand, samples, rng.gen());
                     (s, p, 0) // analyze_hand returns 2 values
                 } else {
-                    analyze_hand_with_pickup(my_hand, skat, samples, post_discard)
+                    analyze_hand_with_pickup(my_hand, skat, samples, post_discard, rng.gen())
                 };
 
                 writeln!(writer, "{}", sig.to_csv_row(my_hand, skat, prob)).unwrap();
@@ -388,6 +442,7 @@ fn main() {
             analyze_general_pre_discard(
                 count,
                 samples,
+                rng.gen(),
                 |(hand, skat, discard, sig, probs, prob_null, best_variant, duration_micros)| {
                     use skat_aug23::skat::formatter::format_hand_for_game;
 
@@ -509,6 +564,7 @@ fn main() {
                 game_type.clone(),
                 start_player.clone(),
                 mode,
+                &mut rng,
             );
 
             use skat_aug23::traits::{Bitboard, StringConverter};
@@ -524,6 +580,7 @@ fn main() {
                 mode: None,
                 samples: None,
                 god_players: None,
+                contract: None,
             };
             let json = serde_json::to_string_pretty(&input).unwrap();
             std::fs::write(&out, json).expect("Failed to write deal");
@@ -538,7 +595,7 @@ fn main() {
                 "suit" | "clubs" => Some(GDef::Suit),
                 _ => None,
             };
-            match generate_smart_deal_with_min_typed(min_value, type_filter) {
+            match generate_smart_deal_with_min_typed(min_value, type_filter, &mut rng) {
                 None => {
                     println!("SMART_DEAL_SKIP: deal did not qualify (best value < {}).", min_value);
                     std::process::exit(2);
@@ -594,6 +651,7 @@ fn main() {
                         mode: None,
                         samples: None,
                         god_players: None,
+                        contract: None,
                     };
                     let json = serde_json::to_string_pretty(&input).unwrap();
                     std::fs::write(&out, json).expect("Failed to write deal");
@@ -612,7 +670,6 @@ fn main() {
             );
             fs::create_dir_all(&output_dir).expect("Could not create output directory");
 
-            let mut rng = rand::thread_rng();
             let mut vec: Vec<usize> = (0..32).collect();
             let mut found = 0;
             let mut attempts = 0;
@@ -642,12 +699,14 @@ fn main() {
 
                 if jacks_count == 1 && sig.aces == 2 {
                     // Potential candidate, run analysis
-                    let (_, prob, _) = analyze_hand_with_pickup(my_hand, skat, 20, false); // Fast check
+                    let search_seed = rng.gen();
+                    let (_, prob, _) =
+                        analyze_hand_with_pickup(my_hand, skat, 20, false, search_seed); // Fast check
 
                     if prob >= min_win {
                         // Double check with more samples
                         let (_, prob_refined, _) =
-                            analyze_hand_with_pickup(my_hand, skat, 100, false);
+                            analyze_hand_with_pickup(my_hand, skat, 100, false, search_seed);
 
                         if prob_refined >= min_win {
                             found += 1;
@@ -683,6 +742,7 @@ fn main() {
                                 declarer_start_points: None,
                                 samples: Some(100),
                                 god_players: None,
+                                contract: None,
                             };
 
                             let filename = format!(
@@ -747,7 +807,6 @@ fn main() {
                 let mut my_hand_val: u32 = 0;
                 let mut skat_val: u32 = 0;
                 {
-                    let mut rng = rand::thread_rng();
                     let mut all_cards_lists = (0..32).collect::<Vec<u8>>();
                     all_cards_lists.shuffle(&mut rng);
 
@@ -761,8 +820,12 @@ fn main() {
 
                 if hand {
                     // Hand game (no pickup) - behavior unchanged, Post cols will be 0/Empty
-                    let (sig, prob) =
-                        skat_aug23::pimc::analysis::analyze_suit(my_hand_val, suit_id, samples);
+                    let (sig, prob) = skat_aug23::pimc::analysis::analyze_suit(
+                        my_hand_val,
+                        suit_id,
+                        samples,
+                        rng.gen(),
+                    );
 
                     use skat_aug23::traits::StringConverter;
                     writeln!(
@@ -781,6 +844,7 @@ fn main() {
                             suit_id,
                             samples,
                             post_discard,
+                            rng.gen(),
                         );
 
                     use skat_aug23::traits::StringConverter;
@@ -882,6 +946,7 @@ fn main() {
             analyze_general_hand(
                 count,
                 samples,
+                rng.gen(),
                 |(
                     hand_val,
                     _skat,
@@ -983,6 +1048,7 @@ fn main() {
                 &input.my_cards,
                 input.start_player,
                 samples,
+                rng.gen(),
                 log_file.clone(),
                 true,
             );
@@ -996,6 +1062,48 @@ fn main() {
             }
             println!("--------------------------------------------------");
         }
+        args::Commands::BidAdvice {
+            context,
+            samples,
+            skat_samples,
+            min_probability,
+        } => {
+            let content = fs::read_to_string(&context).expect("Could not read file");
+            let input: args::PimcBestGameInput =
+                serde_json::from_str(&content).expect("JSON was not well-formatted");
+
+            println!("My Cards: {}", input.my_cards);
+            println!("Skat samples: {}, PIMC samples: {}", skat_samples, samples);
+
+            let advice = skat_aug23::pimc::analysis::analyze_bid(
+                input.my_cards.__bit(),
+                skat_samples,
+                samples,
+                min_probability,
+                rng.gen(),
+            );
+
+            println!("--------------------------------------------------");
+            println!("{:<15} | {:<10} | {:<10}", "Game", "Win Prob", "Value");
+            println!("--------------------------------------------------");
+            for estimate in &advice.estimates {
+                println!(
+                    "{:<15} | {:<10.4} | {:<10}",
+                    format_contract(&estimate.contract),
+                    estimate.win_probability,
+                    estimate.game_value
+                );
+            }
+            println!("--------------------------------------------------");
+            match advice.contract {
+                Some(estimate) => println!(
+                    "Bid up to {} ({})",
+                    advice.max_bid,
+                    format_contract(&estimate.contract)
+                ),
+                None => println!("Pass"),
+            }
+        }
         args::Commands::Playout {
             game_type,
             start_player,
@@ -1009,6 +1117,12 @@ fn main() {
                 "likely-null" => skat_aug23::pimc::pimc_problem::SamplingMode::LikelyNull,
                 _ => skat_aug23::pimc::pimc_problem::SamplingMode::Random,
             };
+            let settings = AgentSettings {
+                adaptive,
+                estimator,
+                sampling_mode: mode,
+                ..AgentSettings::new(samples)
+            };
 
             if let Some(ctx_path) = context {
                 println!("Reading context file: {}", ctx_path);
@@ -1038,7 +1152,8 @@ fn main() {
                         "hearts" | "h" => HEARTS,
                         "diamonds" | "d" => DIAMONDS,
                         "trump" | "t" => match game_context.game_type() {
-                            skat_aug23::skat::defs::Game::Grand => {
+                            skat_aug23::skat::defs::Game::Grand
+                            | skat_aug23::skat::defs::Game::Ramsch => {
                                 skat_aug23::consts::bitboard::TRUMP_GRAND
                             }
                             _ => skat_aug23::consts::bitboard::TRUMP_SUIT,
@@ -1060,12 +1175,12 @@ fn main() {
                 }
 
                 println!("Calling skat_aug23::extensions::cli_playout::run_playout...");
+                let mut seats = seat_agents(&agents, "pimc", &settings, &mut rng);
                 skat_aug23::extensions::cli_playout::run_playout(
                     game_context,
                     input.game_type,
                     input.start_player,
-                    samples,
-                    mode,
+                    &mut seats,
                 );
             } else {
                 println!("No context file provided. Generating random deal...");
@@ -1074,9 +1189,11 @@ fn main() {
                         game_type,
                         start_player,
                         mode,
+                        &mut rng,
                     );
 
-                skat_aug23::extensions::cli_playout::run_playout(game_context, g, p, samples, mode);
+                let mut seats = seat_agents(&agents, "pimc", &settings, &mut rng);
+                skat_aug23::extensions::cli_playout::run_playout(game_context, g, p, &mut seats);
             }
         }
         args::Commands::PointsPlayout {
@@ -1090,13 +1207,16 @@ fn main() {
             hybrid_fallback,
             trump_heuristic,
             trump_heuristic_threshold,
+            ismcts,
+            rollout,
         } => {
+            let ismcts = ismcts_config(ismcts, &rollout);
             let heuristic_opt = if trump_heuristic {
                 Some(trump_heuristic_threshold)
             } else {
                 None
             };
-            let point_strategy = skat_aug23::extensions::cli_playout::PointStrategy::from_args(
+            let point_strategy = PointStrategy::from_args(
                 &points_mode,
                 hybrid_delta,
                 &hybrid_fallback,
@@ -1108,6 +1228,14 @@ fn main() {
                 "likely-null" => skat_aug23::pimc::pimc_problem::SamplingMode::LikelyNull,
                 _ => skat_aug23::pimc::pimc_problem::SamplingMode::Random,
             };
+            let settings = AgentSettings {
+                adaptive,
+                estimator,
+                sampling_mode: mode,
+                point_strategy,
+                ismcts,
+                ..AgentSettings::new(samples)
+            };
 
             if let Some(ctx_path) = context {
                 println!("Reading context file: {}", ctx_path);
@@ -1135,7 +1263,8 @@ fn main() {
                         "hearts" | "h" => HEARTS,
                         "diamonds" | "d" => DIAMONDS,
                         "trump" | "t" => match game_context.game_type() {
-                            skat_aug23::skat::defs::Game::Grand => {
+                            skat_aug23::skat::defs::Game::Grand
+                            | skat_aug23::skat::defs::Game::Ramsch => {
                                 skat_aug23::consts::bitboard::TRUMP_GRAND
                             }
                             _ => skat_aug23::consts::bitboard::TRUMP_SUIT,
@@ -1156,13 +1285,12 @@ fn main() {
                     std::process::exit(1);
                 }
 
+                let mut seats = seat_agents(&agents, "pimc-points", &settings, &mut rng);
                 skat_aug23::extensions::cli_playout::run_points_playout(
                     game_context,
                     input.game_type,
                     input.start_player,
-                    samples,
-                    mode,
-                    point_strategy,
+                    &mut seats,
                 );
             } else {
                 println!("No context file provided. Generating random deal...");
@@ -1171,15 +1299,15 @@ fn main() {
                         game_type,
                         start_player,
                         mode,
+                        &mut rng,
                     );
 
+                let mut seats = seat_agents(&agents, "pimc-points", &settings, &mut rng);
                 skat_aug23::extensions::cli_playout::run_points_playout(
                     game_context,
                     g,
                     p,
-                    samples,
-                    mode,
-                    point_strategy,
+                    &mut seats,
                 );
             }
         }
@@ -1188,15 +1316,14 @@ fn main() {
             points_mode,
             hybrid_delta,
             hybrid_fallback,
+            ismcts,
+            rollout,
         } => {
-            let point_strategy = skat_aug23::extensions::cli_playout::PointStrategy::from_args(
-                &points_mode,
-                hybrid_delta,
-                &hybrid_fallback,
-                None,
-            );
+            let ismcts = ismcts_config(ismcts, &rollout);
+            let point_strategy =
+                PointStrategy::from_args(&points_mode, hybrid_delta, &hybrid_fallback, None);
             use skat_aug23::extensions::cli_playout::generate_smart_deal;
-            match generate_smart_deal() {
+            match generate_smart_deal(&mut rng) {
                 None => {
                     // Deal did not qualify (best game < 50 pts or no Grand/Suit).
                     // Print a short marker so the Python loop can detect the skip.
@@ -1216,13 +1343,20 @@ fn main() {
                         _ => skat_aug23::pimc::pimc_problem::SamplingMode::Random,
                     };
 
+                    let settings = AgentSettings {
+                        adaptive,
+                        estimator,
+                        sampling_mode,
+                        point_strategy,
+                        ismcts,
+                        ..AgentSettings::new(samples)
+                    };
+                    let mut seats = seat_agents(&agents, "pimc-points", &settings, &mut rng);
                     skat_aug23::extensions::cli_playout::run_points_playout(
                         ctx,
                         game_type,
                         start_player,
-                        samples,
-                        sampling_mode,
-                        point_strategy,
+                        &mut seats,
                     );
                 }
             }
@@ -1251,11 +1385,141 @@ fn main() {
                     input.start_player,
                 )
             } else {
-                let (c, _, _) =
-                    generate_random_deal("null".to_string(), "declarer".to_string(), mode);
+                let (c, _, _) = generate_random_deal(
+                    "null".to_string(),
+                    "declarer".to_string(),
+                    mode,
+                    &mut rng,
+                );
+                c
+            };
+            let settings = AgentSettings {
+                adaptive,
+                estimator,
+                sampling_mode: mode,
+                null_rules: true,
+                ..AgentSettings::new(samples)
+            };
+            let mut seats = seat_agents(&agents, "pimc", &settings, &mut rng);
+            run_null_playout(ctx, &mut seats);
+        }
+        args::Commands::RamschPlayout { samples, context } => {
+            use skat_aug23::extensions::cli_playout::{generate_random_deal, run_ramsch_playout};
+            use skat_aug23::pimc::pimc_problem::SamplingMode;
+            let ctx = if let Some(ctx_path) = context {
+                let content = fs::read_to_string(&ctx_path).expect("Unable to read context file");
+                let input: args::GameContextInput =
+                    serde_json::from_str(&content).expect("Invalid JSON context");
+                GameContext::create(
+                    input.declarer_cards.__bit(),
+                    input.left_cards.__bit(),
+                    input.right_cards.__bit(),
+                    skat_aug23::skat::defs::Game::Ramsch,
+                    input.start_player,
+                )
+            } else {
+                let (c, _, _) = generate_random_deal(
+                    "ramsch".to_string(),
+                    "declarer".to_string(),
+                    SamplingMode::Random,
+                    &mut rng,
+                );
                 c
             };
-            run_null_playout(ctx, samples, mode);
+            let settings = AgentSettings {
+                adaptive,
+                ..AgentSettings::new(samples)
+            };
+            let mut seats = seat_agents(&agents, "pimc-points", &settings, &mut rng);
+            run_ramsch_playout(ctx, &mut seats);
+        }
+        args::Commands::Tournament {
+            deals,
+            samples,
+            game_type,
+            distribution,
+            ismcts,
+            rollout,
+            output,
+        } => {
+            use skat_aug23::extensions::tournament::{GameRecord, Tournament};
+            let mode = match distribution.as_str() {
+                "smart-grand" => skat_aug23::pimc::pimc_problem::SamplingMode::SmartGrand,
+                "smart-suit" => skat_aug23::pimc::pimc_problem::SamplingMode::SmartSuit,
+                "likely-null" => skat_aug23::pimc::pimc_problem::SamplingMode::LikelyNull,
+                _ => skat_aug23::pimc::pimc_problem::SamplingMode::Random,
+            };
+            let settings = AgentSettings {
+                adaptive,
+                estimator,
+                sampling_mode: mode,
+                ismcts: ismcts_config(ismcts, &rollout),
+                ..AgentSettings::new(samples)
+            };
+            let spec = agents.unwrap_or_else(|| "pimc,ismcts".to_string());
+            let kinds: Vec<&str> = spec.split(',').collect();
+            let tournament = Tournament::new(&kinds, settings)
+                .and_then(|tournament| tournament.with_game(&game_type, mode))
+                .unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                });
+
+            let report = tournament.run(deals, &mut rng, |game| {
+                println!(
+                    "Deal {:3} | D: {:12} L: {:12} R: {:12} | {:3} points, {} | Scores: {:5} {:5} {:5}",
+                    game.deal,
+                    kinds[game.seats[0]].trim(),
+                    kinds[game.seats[1]].trim(),
+                    kinds[game.seats[2]].trim(),
+                    game.declarer_points,
+                    if game.won { "won " } else { "lost" },
+                    game.scores[0],
+                    game.scores[1],
+                    game.scores[2]
+                );
+            });
+
+            println!(
+                "\n=== Standings ({} deals, {} games) ===",
+                report.deals,
+                report.games.len()
+            );
+            println!(
+                "{:12} {:>6} {:>10} {:>10} {:>8} {:>10}  95% CI",
+                "Agent", "Games", "Decl. Won", "Def. Won", "Total", "Per Deal"
+            );
+            for standing in &report.standings {
+                println!(
+                    "{:12} {:>6} {:>6}/{:<3} {:>10} {:>8} {:>10.1}  [{:.1}, {:.1}]",
+                    standing.agent,
+                    standing.games,
+                    standing.declarer_wins,
+                    standing.declarer_games,
+                    standing.defender_wins,
+                    standing.total,
+                    standing.mean_per_deal,
+                    standing.interval.0,
+                    standing.interval.1
+                );
+            }
+
+            if let Some(path) = output {
+                let content = if path.ends_with(".json") {
+                    serde_json::to_string_pretty(&report).unwrap()
+                } else {
+                    let mut rows = vec![GameRecord::to_csv_header()];
+                    rows.extend(
+                        report
+                            .games
+                            .iter()
+                            .map(|game| game.to_csv_row(&report.agents)),
+                    );
+                    rows.join("\n") + "\n"
+                };
+                fs::write(&path, content).expect("Failed to write tournament report");
+                println!("Wrote tournament report to {}", path);
+            }
         }
         args::Commands::StandardPlayout { context } => {
             println!("Reading context file: {}", context);
@@ -1282,7 +1546,8 @@ fn main() {
                     "hearts" | "h" => HEARTS,
                     "diamonds" | "d" => DIAMONDS,
                     "trump" | "t" => match game_context.game_type() {
-                        skat_aug23::skat::defs::Game::Grand => {
+                        skat_aug23::skat::defs::Game::Grand
+                        | skat_aug23::skat::defs::Game::Ramsch => {
                             skat_aug23::consts::bitboard::TRUMP_GRAND
                         }
                         _ => skat_aug23::consts::bitboard::TRUMP_SUIT,
@@ -1296,6 +1561,11 @@ fn main() {
                 game_context.set_declarer_start_points(points);
             }
 
+            if let Some(contract_input) = &input.contract {
+                let contract = apply_contract(contract_input, Contract::new(input.game_type));
+                game_context.set_doubling(contract.doubling);
+            }
+
             // Standard playout uses full information, so threshold might matter less for 'playout' line by line,
             // but engine needs it.
             game_context.set_threshold_upper(61);
@@ -1309,7 +1579,7 @@ fn main() {
             println!("Calling skat_aug23::extensions::playout::playout...");
             let lines = skat_aug23::extensions::playout::playout(&mut engine);
 
-            for line in lines {
+            for line in &lines {
                 println!(
                     "Player {:?} played {}. (Decl: {}, Team: {})",
                     line.player,
@@ -1318,6 +1588,32 @@ fn main() {
                     line.team_points
                 );
             }
+
+            if lines.len() == 30 && game_context.trick_cards() == 0 {
+                let played: Vec<u32> = lines.iter().map(|line| line.card).collect();
+                let tricks = skat_aug23::skat::scoring::collect_tricks(
+                    &played,
+                    game_context.game_type(),
+                    game_context.declarer_cards(),
+                    game_context.left_cards(),
+                    game_context.right_cards(),
+                );
+                let default_contract = args::ContractInput::default();
+                let contract_input = input.contract.as_ref().unwrap_or(&default_contract);
+                let skat = game_context.get_skat();
+                let settlement = skat_aug23::skat::scoring::settle_game(
+                    &apply_contract(
+                        contract_input,
+                        Contract::new(game_context.game_type())
+                            .with_doubling(game_context.doubling()),
+                    ),
+                    game_context.declarer_cards() | skat,
+                    skat,
+                    &tricks,
+                    contract_input.bid(),
+                );
+                println!("{}", format_settlement(&settlement));
+            }
         }
         args::Commands::AnalysisPlayout { context } => {
             println!("Reading context file: {}", context);
@@ -1344,7 +1640,8 @@ fn main() {
                     "hearts" | "h" => HEARTS,
                     "diamonds" | "d" => DIAMONDS,
                     "trump" | "t" => match game_context.game_type() {
-                        skat_aug23::skat::defs::Game::Grand => {
+                        skat_aug23::skat::defs::Game::Grand
+                        | skat_aug23::skat::defs::Game::Ramsch => {
                             skat_aug23::consts::bitboard::TRUMP_GRAND
                         }
                         _ => skat_aug23::consts::bitboard::TRUMP_SUIT,
@@ -1362,16 +1659,12 @@ fn main() {
                     + game_context.right_cards().count_ones()
                     + game_context.trick_cards().count_ones();
 
+                // The initial position already credits the Skat to the declarer.
                 if total_cards == 30
-                    && game_context.game_type() != skat_aug23::skat::defs::Game::Null
+                    && !game_context.game_type().is_null()
                 {
                     let skat = game_context.get_skat();
-                    let points = skat.points();
-                    println!(
-                        "Auto-Skat: Determined {} points in Skat. Adding to declarer start points.",
-                        points
-                    );
-                    game_context.set_declarer_start_points(points);
+                    println!("Auto-Skat: Determined {} points in Skat.", skat.points());
                 }
             }
 
@@ -1405,7 +1698,10 @@ fn main() {
                 println!("--------------------------------------------------");
             }
         }
-        args::Commands::Analysis { context } => {
+        args::Commands::Analysis {
+            context,
+            engine: engine_args,
+        } => {
             println!("Reading context file: {}", context);
             let context_content = fs::read_to_string(context).expect("Unable to read context file");
             let input: args::GameContextInput =
@@ -1430,7 +1726,8 @@ fn main() {
                     "hearts" | "h" => HEARTS,
                     "diamonds" | "d" => DIAMONDS,
                     "trump" | "t" => match game_context.game_type() {
-                        skat_aug23::skat::defs::Game::Grand => {
+                        skat_aug23::skat::defs::Game::Grand
+                        | skat_aug23::skat::defs::Game::Ramsch => {
                             skat_aug23::consts::bitboard::TRUMP_GRAND
                         }
                         _ => skat_aug23::consts::bitboard::TRUMP_SUIT,
@@ -1451,7 +1748,7 @@ fn main() {
                 std::process::exit(1);
             }
 
-            let mut engine = SkatEngine::new(game_context, None);
+            let mut engine = create_engine(&engine_args, game_context);
             println!("Calling skat_aug23::extensions::solver::solve_all_cards...");
             let result = skat_aug23::extensions::solver::solve_all_cards(&mut engine, 0, 120);
 
@@ -1463,6 +1760,11 @@ fn main() {
             for (card, _resp, val) in sorted_results {
                 println!("    Card: {} -> Value: {}", card.__str(), val);
             }
+
+            if engine_args.tt_stats {
+                println!("{}", format_tt_stats(&engine, &result.counters));
+            }
+            persist_engine(&engine_args, &engine);
         }
         args::Commands::SkatCalc { context, mode } => {
             println!("Reading context file: {}", context);
@@ -1508,14 +1810,25 @@ fn main() {
                 acc_mode,
             );
 
+            let score = |value: u8| -> i32 {
+                settle_value(
+                    &input.contract,
+                    Contract::new(game_type),
+                    declarer_cards,
+                    value,
+                )
+                .score
+            };
+
             match mode.to_lowercase().as_str() {
                 "best" => {
                     if let Some(best) = ret.best_skat {
                         println!(
-                            "Best Skat: {} {}, Value: {}",
+                            "Best Skat: {} {}, Value: {}, Score: {}",
                             best.skat_card_1.__str(),
                             best.skat_card_2.__str(),
-                            best.value
+                            best.value,
+                            score(best.value)
                         );
                     } else {
                         println!("No skat solution found.");
@@ -1527,10 +1840,11 @@ fn main() {
                     sorted.sort_by(|a, b| b.value.cmp(&a.value));
                     for line in sorted {
                         println!(
-                            "Skat: {} {}, Value: {}",
+                            "Skat: {} {}, Value: {}, Score: {}",
                             line.skat_card_1.__str(),
                             line.skat_card_2.__str(),
-                            line.value
+                            line.value,
+                            score(line.value)
                         );
                     }
                 }
@@ -1539,7 +1853,7 @@ fn main() {
 
                     let is_win = |val: u8, game: skat_aug23::skat::defs::Game| -> bool {
                         match game {
-                            skat_aug23::skat::defs::Game::Null => val == 0,
+                            g if g.is_null() => val == 0,
                             _ => val >= 61,
                         }
                     };
@@ -1606,12 +1920,19 @@ fn main() {
             let mut results: Vec<_> = results_info
                 .iter()
                 .map(|info| {
+                    let settlement = settle_value(
+                        &input.contract,
+                        Contract::from_transformation(info.game_type, info.transformation),
+                        declarer_cards,
+                        info.value,
+                    );
                     (
                         info.label.clone(),
                         info.skat_1,
                         info.skat_2,
                         info.value,
                         info.game_type,
+                        settlement.score,
                     )
                 })
                 .collect();
@@ -1623,20 +1944,21 @@ fn main() {
 
                 let is_win = |val: u8, game: skat_aug23::skat::defs::Game| -> bool {
                     match game {
-                        skat_aug23::skat::defs::Game::Null => val == 0,
+                        g if g.is_null() => val == 0,
                         _ => val >= 61,
                     }
                 };
 
                 let mut found_win = false;
-                for (label, s1, s2, val, g_type) in results {
+                for (label, s1, s2, val, g_type, score) in results {
                     if is_win(val, g_type) {
                         println!(
-                            "Game: {:<10} Skat: {} {} -> WIN (Value: {})",
+                            "Game: {:<10} Skat: {} {} -> WIN (Value: {}, Score: {})",
                             label,
                             s1.__str(),
                             s2.__str(),
-                            val
+                            val,
+                            score
                         );
                         found_win = true;
                     }
@@ -1646,15 +1968,16 @@ fn main() {
                 }
             } else {
                 // Best mode (default)
-                results.sort_by(|a, b| b.3.cmp(&a.3));
+                results.sort_by(|a, b| b.5.cmp(&a.5));
                 println!("Best Games Ranking:");
-                for (label, s1, s2, val, _) in results {
+                for (label, s1, s2, val, _, score) in results {
                     println!(
-                        "Game: {:<10} Skat: {} {} -> Value: {}",
+                        "Game: {:<10} Skat: {} {} -> Value: {}, Score: {}",
                         label,
                         s1.__str(),
                         s2.__str(),
-                        val
+                        val,
+                        score
                     );
                 }
             }
@@ -1714,7 +2037,7 @@ fn main() {
             if let Some(threshold) = input.threshold {
                 builder = builder.threshold(threshold);
             } else {
-                if input.game_type == skat_aug23::skat::defs::Game::Null {
+                if input.game_type.is_null() {
                     builder = builder.threshold(1);
                 } else {
                     builder = builder.threshold(61);
@@ -1739,6 +2062,10 @@ fn main() {
                 builder = builder.declarer_start_points(points);
             }
 
+            let contract_input = input.contract.unwrap_or_default();
+            let contract = apply_contract(&contract_input, Contract::new(input.game_type));
+            builder = builder.doubling(contract.doubling);
+
             if let Some(facts_input) = input.facts {
                 let convert_facts = |f_in: Option<args::PimcFactsInput>| -> Facts {
                     if let Some(f) = f_in {
@@ -1758,6 +2085,18 @@ fn main() {
                         if let Some(true) = f.no_diamonds {
                             facts.no_diamonds = true;
                         }
+                        if let Some(cards) = f.has_cards {
+                            facts = facts.holds(cards.__bit());
+                        }
+                        if let Some(cards) = f.no_cards {
+                            facts = facts.lacks(cards.__bit());
+                        }
+                        for cards in f.one_of.unwrap_or_default() {
+                            facts = facts.holds_one_of(cards.__bit()).unwrap_or_else(|e| {
+                                eprintln!("{}", e);
+                                std::process::exit(1);
+                            });
+                        }
                         facts
                     } else {
                         Facts::zero_fact()
@@ -1767,11 +2106,15 @@ fn main() {
                 builder = builder.facts(Player::Declarer, convert_facts(facts_input.declarer));
                 builder = builder.facts(Player::Left, convert_facts(facts_input.left));
                 builder = builder.facts(Player::Right, convert_facts(facts_input.right));
+                builder = builder.facts_skat(convert_facts(facts_input.skat));
             }
 
             let problem = builder.build();
             let samples = input.samples.unwrap_or(100);
-            let search = PimcSearch::new(problem, samples, log_file);
+            let search = PimcSearch::new(problem, samples, log_file)
+                .with_seed(rng.gen())
+                .with_adaptive(adaptive)
+                .with_estimator(estimator);
 
             match mode.to_lowercase().as_str() {
                 "win" => {
@@ -1787,10 +2130,179 @@ fn main() {
                         println!("Card: {} -> Win Prob: {:.4}", card.__str(), prob);
                     }
                 }
+                "metrics" => {
+                    println!("Estimating Move Metrics ({} samples)...", samples);
+                    let results = search.estimate_move_metrics(false);
+                    if let Some((_, m)) = results.first() {
+                        let exact = if m.exact { " (all consistent deals)" } else { "" };
+                        println!(
+                            "Samples used: {}{}, effective: {:.1}",
+                            m.samples, exact, m.effective_samples
+                        );
+                    }
+                    for (card, metrics) in results {
+                        println!("Card: {} -> {}", card.__str(), format_metrics(&metrics));
+                    }
+                }
+                "doubling" => {
+                    let game_value = contract_input.bid().max(contract.base_value());
+                    println!(
+                        "Estimating Kontra/Re ({} samples, game value {})...",
+                        samples, game_value
+                    );
+                    match advise_doubling(&search, game_value) {
+                        Ok(advice) => {
+                            println!(
+                                "Declarer Win Probability: {:.4}",
+                                advice.declarer_win_probability
+                            );
+                            println!(
+                                "Expected Declarer Score: {:.2} -> {:.2} with {:?}",
+                                advice.expected_score,
+                                advice.expected_score_doubled,
+                                advice.doubling
+                            );
+                            println!(
+                                "{} (swing {:+.2})",
+                                if advice.announce {
+                                    format!("Say {:?}", advice.doubling)
+                                } else {
+                                    "Stay silent".to_string()
+                                },
+                                advice.swing
+                            );
+                        }
+                        Err(e) => eprintln!("{}", e),
+                    }
+                }
                 _ => {
-                    eprintln!("Invalid mode: {}. Use 'best' or 'win'.", mode);
+                    eprintln!(
+                        "Invalid mode: {}. Use 'best', 'metrics', 'win' or 'doubling'.",
+                        mode
+                    );
                 }
             }
         }
     }
 }
+
+/// Settles a double dummy value with the contract given in the input file.
+fn settle_value(
+    contract_input: &Option<args::ContractInput>,
+    contract: Contract,
+    declarer_cards_with_skat: u32,
+    value: u8,
+) -> Settlement {
+    let default_contract = args::ContractInput::default();
+    let contract_input = contract_input.as_ref().unwrap_or(&default_contract);
+    let contract = apply_contract(contract_input, contract);
+    let outcome = GameOutcome::from_solver_value(contract.game, value);
+    settle(
+        &contract,
+        declarer_cards_with_skat,
+        &outcome,
+        contract_input.bid(),
+    )
+}
+
+/// Contract with the announcements of the input file, exits on an invalid
+/// Kontra/Re state.
+fn apply_contract(contract_input: &args::ContractInput, contract: Contract) -> Contract {
+    contract_input.apply(contract).unwrap_or_else(|e| {
+        eprintln!("Validation Error: {}", e);
+        std::process::exit(1);
+    })
+}
+
+fn format_settlement(settlement: &Settlement) -> String {
+    format!(
+        "Score: {} ({}{}{}, Game Value: {}, Matadors: {})",
+        settlement.score,
+        if settlement.won { "won" } else { "lost" },
+        if settlement.overbid { ", overbid" } else { "" },
+        match settlement.doubling {
+            Doubling::None => "",
+            Doubling::Kontra => ", Kontra",
+            Doubling::Re => ", Re",
+        },
+        settlement.game_value,
+        settlement.matadors
+    )
+}
+
+/// Agents of the playout seats from `--agents`, `default` for all seats if
+/// not given.
+fn seat_agents(
+    spec: &Option<String>,
+    default: &str,
+    settings: &AgentSettings,
+    rng: &mut StdRng,
+) -> [Box<dyn Agent>; 3] {
+    create_seats(spec.as_deref().unwrap_or(default), settings, rng).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    })
+}
+
+/// ISMCTS settings of the points playouts, `None` plays with PIMC.
+fn ismcts_config(iterations: Option<u32>, rollout: &str) -> Option<IsmctsConfig> {
+    let rollout: RolloutPolicy = rollout.parse().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    iterations.map(|iterations| IsmctsConfig::new(iterations).with_rollout(rollout))
+}
+
+fn format_metrics(metrics: &MoveMetrics) -> String {
+    format!(
+        "Win Prob: {:.4} [{:.4}, {:.4}], Points: {:.1} [{:.1}, {:.1}], Min: {:.0}",
+        metrics.win_prob,
+        metrics.win_interval.0,
+        metrics.win_interval.1,
+        metrics.avg_points,
+        metrics.points_interval.0,
+        metrics.points_interval.1,
+        metrics.min_points
+    )
+}
+
+fn format_contract(contract: &Contract) -> String {
+    match contract.game {
+        skat_aug23::skat::defs::Game::Suit => match contract.trump_suit {
+            SPADES => "Spades".to_string(),
+            HEARTS => "Hearts".to_string(),
+            DIAMONDS => "Diamonds".to_string(),
+            _ => "Clubs".to_string(),
+        },
+        game => game.convert_to_string(),
+    }
+}
+
+fn create_engine(engine_args: &args::EngineArgs, context: GameContext) -> SkatEngine {
+    engine_args.create_engine(context).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    })
+}
+
+fn persist_engine(engine_args: &args::EngineArgs, engine: &SkatEngine) {
+    if let Err(e) = engine_args.persist(engine) {
+        eprintln!("{}", e);
+    }
+}
+
+fn format_tt_stats(engine: &SkatEngine, cnt: &Counters) -> String {
+    let (occupied, capacity) = engine.tt_fill();
+    format!(
+        "TT: {} of {} entries ({:.1}% filled), probes: {}, hits: {:.1}%, misses: {}, collisions: {:.1}%, writes: {}, threads: {}",
+        occupied,
+        capacity,
+        100.0 * occupied as f32 / capacity as f32,
+        cnt.probes(),
+        100.0 * cnt.hit_rate(),
+        cnt.misses,
+        100.0 * cnt.collision_rate(),
+        cnt.writes,
+        engine.threads()
+    )
+}
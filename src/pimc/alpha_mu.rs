@@ -0,0 +1,311 @@
+//! # Alpha-Mu Search
+//!
+//! PIMC solves every sampled deal (world) on its own and so assumes that the
+//! searching player could play another card in each world (strategy fusion).
+//! The αμ search (Cazenave and Ventos) plays one card in all worlds at the
+//! moves of the searching player (Max): it searches the next `max_moves` of
+//! them over all worlds together and solves the worlds double dummy below.
+//!
+//! The outcome of a line is the set of worlds the searching player wins, one
+//! bit per world. As Max cannot tell the worlds apart, a position has no
+//! single outcome but a Pareto front of them: the sets not included in
+//! another set, one for each way Max can go on. The other players see all
+//! cards (as in PIMC) and choose per world, the opponents the worst outcome
+//! for Max, the partner the best.
+
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::skat::context::GameContext;
+use crate::skat::counters::Counters;
+use crate::skat::defs::{Game, Player};
+use crate::skat::engine::SkatEngine;
+use crate::skat::position::Position;
+use crate::skat::tt::{ReplacementPolicy, TranspositionTable};
+use crate::traits::Bitboard;
+
+/// Most worlds of a search, one bit each.
+pub const MAX_WORLDS: usize = 64;
+
+/// Entries of the transposition table of every world.
+const WORLD_TT_SIZE: usize = 1 << 16;
+
+/// Set of worlds, bit i = world i.
+pub type Worlds = u64;
+
+/// Public state of a node (played cards, trick cards, trick suit, declarer
+/// points, player to move), the Max moves left and the worlds searched.
+type NodeKey = (u32, u32, u32, u8, Player, u8, Worlds);
+
+pub struct AlphaMu<F> {
+    /// One engine per world, keeping its table through the search.
+    engines: Vec<SkatEngine>,
+    my_player: Player,
+    /// Window of the double dummy solves.
+    alpha: u8,
+    beta: u8,
+    /// True if the declarer points are a win for the searching player.
+    is_win: F,
+    memo: HashMap<NodeKey, Vec<Worlds>>,
+}
+
+impl<F> AlphaMu<F>
+where
+    F: Fn(u8) -> bool + Sync,
+{
+    /// Search over `worlds` (at most `MAX_WORLDS`) for `my_player`, who is to
+    /// move in all of them.
+    pub fn new(worlds: &[GameContext], my_player: Player, alpha: u8, beta: u8, is_win: F) -> Self {
+        assert!(
+            worlds.len() <= MAX_WORLDS,
+            "Too many worlds for the αμ search"
+        );
+
+        let engines = worlds
+            .iter()
+            .map(|context| {
+                let tt = TranspositionTable::with_capacity(
+                    WORLD_TT_SIZE,
+                    ReplacementPolicy::DepthPreferred,
+                );
+                SkatEngine::new(*context, Some(tt))
+            })
+            .collect();
+
+        AlphaMu {
+            engines,
+            my_player,
+            alpha,
+            beta,
+            is_win,
+            memo: HashMap::new(),
+        }
+    }
+
+    /// Pareto front of every card of the searching player, searching
+    /// `max_moves` (at least one) of its moves over the worlds.
+    pub fn search(&mut self, max_moves: u8) -> Vec<(u32, Vec<Worlds>)> {
+        if self.engines.is_empty() {
+            return Vec::new();
+        }
+
+        let positions: Vec<Position> = self
+            .engines
+            .iter()
+            .map(|engine| engine.create_initial_position())
+            .collect();
+        let all = self.all_worlds();
+        let (moves, n) = positions[0].get_legal_moves().__decompose();
+
+        moves[0..n]
+            .iter()
+            .map(|&card| {
+                let children = self.play(&positions, all, card);
+                (card, self.front(&children, all, max_moves.max(1) - 1))
+            })
+            .collect()
+    }
+
+    fn all_worlds(&self) -> Worlds {
+        match self.engines.len() {
+            MAX_WORLDS => Worlds::MAX,
+            n => (1 << n) - 1,
+        }
+    }
+
+    /// Positions after `card` in `worlds`; the other positions stay.
+    fn play(&self, positions: &[Position], worlds: Worlds, card: u32) -> Vec<Position> {
+        positions
+            .iter()
+            .zip(&self.engines)
+            .enumerate()
+            .map(|(i, (position, engine))| {
+                if contains(worlds, i) {
+                    position.make_move(card, &engine.context)
+                } else {
+                    *position
+                }
+            })
+            .collect()
+    }
+
+    fn front(&mut self, positions: &[Position], worlds: Worlds, max_moves: u8) -> Vec<Worlds> {
+        let position = positions[worlds.trailing_zeros() as usize];
+        let game_over = position.player_cards == 0;
+        let is_max = position.player == self.my_player;
+
+        if game_over || (is_max && max_moves == 0) {
+            return vec![self.solve(positions, worlds)];
+        }
+
+        let key = (
+            position.played_cards,
+            position.trick_cards,
+            position.trick_suit,
+            position.declarer_points,
+            position.player,
+            max_moves,
+            worlds,
+        );
+        if let Some(front) = self.memo.get(&key) {
+            return front.clone();
+        }
+
+        let front = if is_max {
+            self.max_front(positions, worlds, max_moves)
+        } else {
+            self.other_front(positions, worlds, max_moves)
+        };
+        self.memo.insert(key, front.clone());
+        front
+    }
+
+    /// Max plays the same card in all worlds: any outcome of any card.
+    fn max_front(&mut self, positions: &[Position], worlds: Worlds, max_moves: u8) -> Vec<Worlds> {
+        let position = positions[worlds.trailing_zeros() as usize];
+        let (moves, n) = position.get_legal_moves().__decompose();
+
+        let mut front = Vec::new();
+        for &card in &moves[0..n] {
+            let children = self.play(positions, worlds, card);
+            front.extend(self.front(&children, worlds, max_moves - 1));
+            front = pareto(front);
+            if front == [worlds] {
+                break;
+            }
+        }
+        front
+    }
+
+    /// The other players choose per world. Each card is searched in the
+    /// worlds it is legal in and combined with the other cards world by
+    /// world; Max still chooses freely after each card.
+    fn other_front(
+        &mut self,
+        positions: &[Position],
+        worlds: Worlds,
+        max_moves: u8,
+    ) -> Vec<Worlds> {
+        let player = positions[worlds.trailing_zeros() as usize].player;
+        let partner = self.is_partner(player);
+
+        let mut legal = [0u32; MAX_WORLDS];
+        let mut moves = 0;
+        for i in (0..positions.len()).filter(|i| contains(worlds, *i)) {
+            legal[i] = positions[i].get_legal_moves();
+            moves |= legal[i];
+        }
+
+        let mut front = vec![if partner { 0 } else { worlds }];
+        let (cards, n) = moves.__decompose();
+        for &card in &cards[0..n] {
+            let holding = (0..positions.len())
+                .filter(|i| contains(worlds, *i) && legal[*i] & card != 0)
+                .fold(0, |set, i| set | 1 << i);
+
+            let children = self.play(positions, holding, card);
+            let card_front = self.front(&children, holding, max_moves);
+
+            let combined = front
+                .iter()
+                .flat_map(|a| {
+                    card_front
+                        .iter()
+                        .map(move |b| if partner { a | b } else { a & (b | !holding) })
+                })
+                .collect();
+            front = pareto(combined);
+
+            let decided = if partner { worlds } else { 0 };
+            if front == [decided] {
+                break;
+            }
+        }
+        front
+    }
+
+    /// True if `player` plays on the side of the searching player. In Ramsch
+    /// everybody plays alone.
+    fn is_partner(&self, player: Player) -> bool {
+        let game_type = self.engines[0].context.game_type();
+        game_type != Game::Ramsch
+            && (player == Player::Declarer) == (self.my_player == Player::Declarer)
+    }
+
+    /// Worlds won by the searching player, solved double dummy each.
+    fn solve(&mut self, positions: &[Position], worlds: Worlds) -> Worlds {
+        let (alpha, beta) = (self.alpha, self.beta);
+        let is_win = &self.is_win;
+
+        self.engines
+            .par_iter_mut()
+            .zip(positions)
+            .enumerate()
+            .filter(|(i, _)| contains(worlds, *i))
+            .map(|(i, (engine, position))| {
+                let value = if position.player_cards == 0 {
+                    position.declarer_points
+                } else {
+                    let mut cnt = Counters::new();
+                    engine.search(position, &mut cnt, alpha, beta).1
+                };
+                if is_win(value) {
+                    1 << i
+                } else {
+                    0
+                }
+            })
+            .reduce(|| 0, |a, b| a | b)
+    }
+}
+
+/// Weighted share of the worlds won with the best outcome of `front`.
+pub fn best_share(front: &[Worlds], weights: &[f32]) -> f32 {
+    let total: f32 = weights.iter().sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+
+    front
+        .iter()
+        .map(|&won| {
+            let sum: f32 = (0..weights.len())
+                .filter(|i| contains(won, *i))
+                .map(|i| weights[i])
+                .sum();
+            sum / total
+        })
+        .fold(0.0, f32::max)
+}
+
+fn contains(worlds: Worlds, i: usize) -> bool {
+    worlds & (1 << i) != 0
+}
+
+/// Removes the outcomes included in other outcomes (and duplicates).
+fn pareto(mut front: Vec<Worlds>) -> Vec<Worlds> {
+    front.sort_by_key(|worlds| std::cmp::Reverse(worlds.count_ones()));
+
+    let mut maximal: Vec<Worlds> = Vec::with_capacity(front.len());
+    for worlds in front {
+        if maximal.iter().all(|kept| worlds & !kept != 0) {
+            maximal.push(worlds);
+        }
+    }
+    maximal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{best_share, pareto};
+
+    #[test]
+    fn test_pareto() {
+        let front = pareto(vec![0b0011, 0b0001, 0b0110, 0b0011, 0b0100, 0]);
+        assert_eq!(front, vec![0b0011, 0b0110]);
+
+        assert_eq!(best_share(&front, &[1.0, 1.0, 1.0, 1.0]), 0.5);
+        assert_eq!(best_share(&front, &[1.0, 0.0, 3.0, 0.0]), 0.75);
+    }
+}
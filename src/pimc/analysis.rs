@@ -1,12 +1,13 @@
 use crate::consts::bitboard::ALLCARDS;
 use crate::pimc::pimc_problem_builder::PimcProblemBuilder;
 use crate::pimc::pimc_search::PimcSearch;
+use crate::skat::bidding::BidAdvice;
 use crate::skat::defs::Player;
 use crate::skat::signature::HandSignature;
 use crate::traits::StringConverter;
 use rayon::prelude::*;
 
-pub fn analyze_hand(my_hand: u32, samples: u32) -> (HandSignature, f32) {
+pub fn analyze_hand(my_hand: u32, samples: u32, seed: u64) -> (HandSignature, f32) {
     let sig = HandSignature::from_hand(my_hand);
 
     // Build PIMC problem
@@ -22,7 +23,7 @@ pub fn analyze_hand(my_hand: u32, samples: u32) -> (HandSignature, f32) {
         .threshold(61);
 
     let problem = builder.build();
-    let search = PimcSearch::new(problem, samples, None);
+    let search = PimcSearch::new(problem, samples, None).with_seed(seed);
 
     // estimate_win returns (win_prob, wins_count)
     let (prob, _) = search.estimate_win(false);
@@ -35,6 +36,7 @@ pub fn analyze_hand_with_pickup(
     skat: u32,
     samples: u32,
     post_discard: bool,
+    seed: u64,
 ) -> (HandSignature, f32, u32) {
     let sig_initial = HandSignature::from_hand(my_hand);
     let cards_12 = my_hand | skat;
@@ -58,7 +60,8 @@ pub fn analyze_hand_with_pickup(
 
     let selection_samples = if samples > 20 { 20 } else { samples };
 
-    // Find best discard using parallel iterator
+    // Find best discard using parallel iterator. All discards are rated with
+    // the same seed, so they are compared on alike samples.
     let best_option = discards
         .par_iter()
         .map(|(keep, discard)| {
@@ -72,7 +75,7 @@ pub fn analyze_hand_with_pickup(
                 .threshold(61);
 
             let problem = builder.build();
-            let search = PimcSearch::new(problem, selection_samples, None);
+            let search = PimcSearch::new(problem, selection_samples, None).with_seed(seed);
             let (prob, _) = search.estimate_win(false);
             (prob, *keep, *discard)
         })
@@ -99,7 +102,7 @@ pub fn analyze_hand_with_pickup(
             .threshold(61);
 
         let problem = builder.build();
-        let search = PimcSearch::new(problem, samples, None);
+        let search = PimcSearch::new(problem, samples, None).with_seed(seed);
         let (prob, _) = search.estimate_win(false);
         return (result_sig, prob, best_discard);
     }
@@ -109,7 +112,7 @@ pub fn analyze_hand_with_pickup(
 
 use crate::skat::context::{GameContext, ProblemTransformation};
 
-pub fn analyze_suit(my_hand: u32, suit: u8, samples: u32) -> (HandSignature, f32) {
+pub fn analyze_suit(my_hand: u32, suit: u8, samples: u32, seed: u64) -> (HandSignature, f32) {
     let sig = HandSignature::from_hand_and_skat_suit(my_hand, 0, Some(suit));
 
     let trans = match suit {
@@ -136,7 +139,7 @@ pub fn analyze_suit(my_hand: u32, suit: u8, samples: u32) -> (HandSignature, f32
         .threshold(61);
 
     let problem = builder.build();
-    let search = PimcSearch::new(problem, samples, None);
+    let search = PimcSearch::new(problem, samples, None).with_seed(seed);
     let (prob, _) = search.estimate_win(false);
 
     (sig, prob)
@@ -148,6 +151,7 @@ pub fn analyze_suit_with_pickup(
     suit: u8,
     samples: u32,
     _post_discard: bool, // Argument ignored, we always return both now
+    seed: u64,
 ) -> (HandSignature, HandSignature, f32, u32, u32) {
     let sig_initial = HandSignature::from_hand_and_skat_suit(my_hand, 0, Some(suit));
     let cards_12 = my_hand | skat;
@@ -198,7 +202,7 @@ pub fn analyze_suit_with_pickup(
                 .threshold(61);
 
             let problem = builder.build();
-            let search = PimcSearch::new(problem, selection_samples, None);
+            let search = PimcSearch::new(problem, selection_samples, None).with_seed(seed);
             let (prob, _) = search.estimate_win(false);
             (prob, *keep, *discard)
         })
@@ -230,7 +234,7 @@ pub fn analyze_suit_with_pickup(
             .threshold(61);
 
         let problem = builder.build();
-        let search = PimcSearch::new(problem, samples, None);
+        let search = PimcSearch::new(problem, samples, None).with_seed(seed);
         let (prob, _) = search.estimate_win(false);
         return (sig_initial, sig_post, prob, best_keep, best_discard);
     }
@@ -238,17 +242,19 @@ pub fn analyze_suit_with_pickup(
     (sig_initial, sig_post, best_prob, best_keep, best_discard)
 }
 
-pub fn analyze_general_pre_discard<F>(count: u32, samples: u32, on_result: F)
+/// Hand `i` of the `count` hands is dealt with the seed `seed + i`.
+pub fn analyze_general_pre_discard<F>(count: u32, samples: u32, seed: u64, on_result: F)
 where
     F: Fn((u32, u32, u32, HandSignature, [f32; 5], f32, u8, u128)) + Sync + Send,
 {
+    use rand::rngs::StdRng;
     use rand::seq::SliceRandom;
-    use rand::thread_rng;
+    use rand::{Rng, SeedableRng};
     use std::time::Instant;
 
-    (0..count).into_iter().for_each(|_| {
+    (0..count).into_iter().for_each(|i| {
         let start_time = Instant::now();
-        let mut rng = thread_rng();
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(i as u64));
         let mut deck: Vec<u32> = (0..32).map(|i| 1 << i).collect();
         deck.shuffle(&mut rng);
 
@@ -267,8 +273,9 @@ where
 
         // 1. Analyze Grand
         // analyze_hand_with_pickup now returns (sig, prob, discard)
+        let search_seed = rng.gen();
         let (_, prob_grand, discard_grand) =
-            analyze_hand_with_pickup(my_hand, skat, samples, false);
+            analyze_hand_with_pickup(my_hand, skat, samples, false, search_seed);
 
         // 2. Analyze Suits
         let mut suit_probs = [0.0; 4];
@@ -276,7 +283,7 @@ where
         for suit in 0..4 {
             // analyze_suit_with_pickup returns (sig_init, sig_post, prob, keep, discard)
             let (_, _, prob, _, discard) =
-                analyze_suit_with_pickup(my_hand, skat, suit, samples, false);
+                analyze_suit_with_pickup(my_hand, skat, suit, samples, false, search_seed);
             suit_probs[suit as usize] = prob;
             suit_discards[suit as usize] = discard;
         }
@@ -291,7 +298,7 @@ where
 
         // 3. Analyze Null
         let (_, _, prob_null, _, discard_null) =
-            analyze_null_with_pickup(my_hand, skat, samples, false);
+            analyze_null_with_pickup(my_hand, skat, samples, false, search_seed);
 
         // Determine Best Variant (Prob > Value > Preference)
         use crate::skat::rules::calculate_game_value;
@@ -376,6 +383,7 @@ pub fn analyze_null_with_pickup(
     skat: u32,
     samples: u32,
     post_discard: bool,
+    seed: u64,
 ) -> (HandSignature, HandSignature, f32, u32, u32) {
     use crate::consts::bitboard::ALLCARDS;
     use crate::pimc::pimc_problem_builder::PimcProblemBuilder;
@@ -419,7 +427,7 @@ pub fn analyze_null_with_pickup(
             // Threshold is set by new_null()
 
             let problem = builder.build();
-            let search = PimcSearch::new(problem, selection_samples, None);
+            let search = PimcSearch::new(problem, selection_samples, None).with_seed(seed);
             let (prob, _) = search.estimate_win(false);
             (prob, *keep, *discard)
         })
@@ -445,7 +453,7 @@ pub fn analyze_null_with_pickup(
             .remaining_cards(&remaining.__str());
 
         let problem = builder.build();
-        let search = PimcSearch::new(problem, samples, None);
+        let search = PimcSearch::new(problem, samples, None).with_seed(seed);
         let (prob, _) = search.estimate_win(false);
         prob
     } else {
@@ -455,7 +463,70 @@ pub fn analyze_null_with_pickup(
     (sig_initial, sig_post, final_prob, best_keep, best_discard)
 }
 
-pub fn analyze_general_hand<F>(count: u32, samples: u32, on_result: F)
+/// Recommends a maximum bid for a 10-card hand before the skat is known.
+///
+/// For `skat_samples` random skats the pickup analyses (best discard, then
+/// PIMC) are run for Grand, the four suits and Null, like in
+/// `analyze_general_pre_discard`. The averaged win probabilities are combined
+/// with the game values in `bidding::recommend_max_bid`.
+pub fn analyze_bid(
+    my_hand: u32,
+    skat_samples: u32,
+    samples: u32,
+    min_probability: f32,
+    seed: u64,
+) -> BidAdvice {
+    use crate::skat::bidding::{recommend_max_bid, ContractEstimate};
+    use crate::skat::defs::{Game, CLUBS, DIAMONDS, HEARTS, SPADES};
+    use crate::skat::scoring::Contract;
+    use crate::traits::Bitboard;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    let (remaining, n) = (ALLCARDS ^ my_hand).__decompose();
+    let mut remaining = remaining[0..n].to_vec();
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    // Grand, Clubs, Spades, Hearts, Diamonds, Null
+    let mut probs = [0.0f32; 6];
+    let skat_samples = skat_samples.max(1);
+
+    for _ in 0..skat_samples {
+        remaining.shuffle(&mut rng);
+        let skat = remaining[0] | remaining[1];
+        let search_seed = rng.gen();
+
+        probs[0] += analyze_hand_with_pickup(my_hand, skat, samples, false, search_seed).1;
+        for suit in 0..4 {
+            probs[suit as usize + 1] +=
+                analyze_suit_with_pickup(my_hand, skat, suit, samples, false, search_seed).2;
+        }
+        probs[5] += analyze_null_with_pickup(my_hand, skat, samples, false, search_seed).2;
+    }
+
+    let contracts = [
+        Contract::grand(),
+        Contract::suit(CLUBS),
+        Contract::suit(SPADES),
+        Contract::suit(HEARTS),
+        Contract::suit(DIAMONDS),
+        Contract::new(Game::Null),
+    ];
+
+    let estimates = contracts
+        .iter()
+        .zip(probs.iter())
+        .map(|(contract, prob)| {
+            ContractEstimate::new(*contract, my_hand, prob / skat_samples as f32)
+        })
+        .collect();
+
+    recommend_max_bid(estimates, min_probability)
+}
+
+/// Hand `i` of the `count` hands is dealt with the seed `seed + i`.
+pub fn analyze_general_hand<F>(count: u32, samples: u32, seed: u64, on_result: F)
 where
     F: Fn((u32, u32, u32, HandSignature, [f32; 5], f32, u8, u128)) + Sync + Send,
 {
@@ -463,14 +534,15 @@ where
     use crate::skat::defs::Game; // Fix import
     use crate::skat::defs::Player; // Ensure Player available
     use crate::skat::rules::calculate_game_value; // We will use this but boost it
+    use rand::rngs::StdRng;
     use rand::seq::SliceRandom;
-    use rand::thread_rng;
+    use rand::SeedableRng;
     use std::time::Instant;
 
     // Parallel iterator for the main count loop
-    (0..count).into_par_iter().for_each(|_| {
+    (0..count).into_par_iter().for_each(|i| {
         let start_time = Instant::now();
-        let mut rng = thread_rng();
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(i as u64));
         let mut deck: Vec<u32> = (0..32).map(|i| 1 << i).collect();
         deck.shuffle(&mut rng);
 
@@ -538,7 +610,7 @@ where
                 let mut engine = SkatEngine::new(context, None);
                 let result = solve_and_add_skat(&mut engine);
 
-                if *game_type == Game::Null {
+                if game_type.is_null() {
                     if result.best_value == 0 {
                         wins[*idx] += 1;
                     }
@@ -631,7 +703,8 @@ where
     });
 }
 
-pub fn analyze_null_detailed<F>(count: u32, samples: u32, hand_game: bool, on_result: F)
+/// Hand `i` of the `count` hands is dealt with the seed `seed + i`.
+pub fn analyze_null_detailed<F>(count: u32, samples: u32, hand_game: bool, seed: u64, on_result: F)
 where
     F: Fn(
             (
@@ -647,6 +720,7 @@ where
         ) + Sync
         + Send,
 {
+    use crate::extensions::agent::{create_seats, AgentSettings};
     use crate::pimc::playout::playout_with_history;
     use crate::skat::context::GameContext;
     use crate::skat::defs::{Game, Player};
@@ -655,9 +729,9 @@ where
     use std::time::Instant;
     // use crate::pimc::analysis::analyze_null_with_pickup; // Available in same module
 
-    (0..count).into_par_iter().for_each(|_| {
+    (0..count).into_par_iter().for_each(|i| {
         let start = Instant::now();
-        let mut rng = thread_rng();
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(i as u64));
         // Generate Deck
         let mut deck: Vec<u32> = (0..32).map(|i| 1 << i).collect();
         deck.shuffle(&mut rng);
@@ -683,7 +757,7 @@ where
             // Run optimization to find best discard
             // Using local function analyze_null_with_pickup
             let (_, _, _, best_keep, best_discard) =
-                analyze_null_with_pickup(my_hand, skat, 20, false);
+                analyze_null_with_pickup(my_hand, skat, 20, false, rng.gen());
             my_hand = best_keep;
             skat = best_discard;
         }
@@ -694,7 +768,12 @@ where
 
         let context = GameContext::create(my_hand, left, right, Game::Null, start_player);
 
-        let trace = playout_with_history(context, samples);
+        let settings = AgentSettings {
+            null_rules: true,
+            ..AgentSettings::new(samples)
+        };
+        let mut agents = create_seats("pimc", &settings, &mut rng).unwrap();
+        let trace = playout_with_history(context, &mut agents);
 
         let duration = start.elapsed().as_millis();
 
@@ -2,14 +2,16 @@ use crate::extensions::skat_solving::{solve_with_skat, AccelerationMode};
 use crate::skat::context::{GameContext, ProblemTransformation};
 use crate::skat::defs::{Game, Player, ALLCARDS};
 use crate::traits::{BitConverter, Bitboard, StringConverter};
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::SeedableRng;
 use std::io::{self, Write};
 
 pub fn calculate_best_game(
     my_cards_str: &str,
     start_player: Player,
     samples: u32,
+    seed: u64,
     log_file: Option<String>,
     verbose: bool,
 ) -> Vec<(String, f32)> {
@@ -32,6 +34,7 @@ pub fn calculate_best_game(
     let mut wins_diamonds = 0;
     let mut wins_grand = 0;
     let mut wins_null = 0;
+    let mut wins_null_hand = 0;
 
     let mut log_writer = if let Some(path) = &log_file {
         Some(
@@ -49,10 +52,11 @@ pub fn calculate_best_game(
         writeln!(w, "Best Game Analysis (Skat Pickup Simulation)").unwrap();
         writeln!(w, "My Cards: {}", my_cards_str).unwrap();
         writeln!(w, "Samples: {}", samples).unwrap();
+        writeln!(w, "Seed: {}", seed).unwrap();
         writeln!(w, "--------------------------------------------------").unwrap();
     }
 
-    let mut rng = thread_rng();
+    let mut rng = StdRng::seed_from_u64(seed);
 
     for i in 0..samples {
         remaining_vec.shuffle(&mut rng);
@@ -113,16 +117,24 @@ pub fn calculate_best_game(
             ),
             ("Grand", Game::Grand, None),
             ("Null", Game::Null, None),
+            ("Null Hand", Game::NullHand, None),
         ];
 
         let mut scores = Vec::new();
 
         for (_, (name, game_type, transform)) in game_configs.iter().enumerate() {
+            // Hand games keep the skat as dealt
+            let declarer_c = if game_type.is_hand() {
+                my_cards
+            } else {
+                my_cards_with_skat
+            };
+
             // Apply transformations
             let my_c = if let Some(t) = transform {
-                GameContext::get_switched_cards(my_cards_with_skat, *t)
+                GameContext::get_switched_cards(declarer_c, *t)
             } else {
-                my_cards_with_skat
+                declarer_c
             };
             let left_c = if let Some(t) = transform {
                 GameContext::get_switched_cards(left_mask, *t)
@@ -230,12 +242,17 @@ pub fn calculate_best_game(
         if scores[5] == 0 {
             wins_null += 1;
         }
+        if scores[6] == 0 {
+            wins_null_hand += 1;
+        }
     }
 
     if verbose {
         println!(); // Newline after dots
     }
 
+    // With all hands known, laying the cards open changes nothing, so the
+    // ouvert variants share the win rates of their closed counterparts.
     let f_samples = samples as f32;
     let mut results_vec = vec![
         ("Clubs".to_string(), wins_clubs as f32 / f_samples),
@@ -244,6 +261,12 @@ pub fn calculate_best_game(
         ("Diamonds".to_string(), wins_diamonds as f32 / f_samples),
         ("Grand".to_string(), wins_grand as f32 / f_samples),
         ("Null".to_string(), wins_null as f32 / f_samples),
+        ("Null Hand".to_string(), wins_null_hand as f32 / f_samples),
+        ("Null Ouvert".to_string(), wins_null as f32 / f_samples),
+        (
+            "Null Ouvert Hand".to_string(),
+            wins_null_hand as f32 / f_samples,
+        ),
     ];
 
     results_vec.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
@@ -254,6 +277,7 @@ pub fn calculate_best_game_from_12(
     my_12_cards_str: &str,
     start_player: Player,
     samples: u32,
+    seed: u64,
     log_file: Option<String>,
     verbose: bool,
 ) -> Vec<(String, f32)> {
@@ -300,10 +324,11 @@ pub fn calculate_best_game_from_12(
         writeln!(w, "Best Game Analysis (12 Cards - Discard Optimization)").unwrap();
         writeln!(w, "My 12 Cards: {}", my_12_cards_str).unwrap();
         writeln!(w, "Samples: {}", samples).unwrap();
+        writeln!(w, "Seed: {}", seed).unwrap();
         writeln!(w, "--------------------------------------------------").unwrap();
     }
 
-    let mut rng = thread_rng();
+    let mut rng = StdRng::seed_from_u64(seed);
 
     for i in 0..samples {
         remaining_vec.shuffle(&mut rng);
@@ -513,6 +538,9 @@ pub fn calculate_best_game_from_12(
         ("Diamonds".to_string(), wins_diamonds as f32 / f_samples),
         ("Grand".to_string(), wins_grand as f32 / f_samples),
         ("Null".to_string(), wins_null as f32 / f_samples), // Note: Need to fix logic above first
+        // Hand games are out of reach once the skat is picked up; Null Ouvert
+        // is decided exactly like Null when all hands are known.
+        ("Null Ouvert".to_string(), wins_null as f32 / f_samples),
     ];
 
     results_vec.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
@@ -0,0 +1,356 @@
+//! # Deal Space
+//!
+//! The cards hidden from the searching player lie in the hands of the next
+//! and the previous player or in the Skat. Facts, open cards and the sizes of
+//! the hands allow each card in some of these places only; the deals
+//! consistent with them are counted, sampled uniformly and enumerated here.
+//!
+//! Cards allowed in the same places are interchangeable, so the deals are
+//! counted per group of such cards: how many cards of a group go to each
+//! place, times the ways to pick them. Card-count constraints do not fit this
+//! scheme and are checked on the sampled deals.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use super::facts::Facts;
+use crate::traits::Bitboard;
+
+/// Places of the hidden cards, the indices of `DealSpace` arrays.
+pub const NEXT: usize = 0;
+pub const PREVIOUS: usize = 1;
+pub const SKAT: usize = 2;
+
+/// Deals drawn until one fits the card counts of the facts.
+const MAX_TRIES: u32 = 1000;
+
+/// Groups of cards by the places they are allowed in (bit i = place i).
+const GROUPS: usize = 8;
+
+#[derive(Clone, Copy)]
+pub struct DealSpace {
+    hidden: u32,
+    allowed: [u32; 3],
+    sizes: [u32; 3],
+    facts: [Facts; 3],
+}
+
+impl DealSpace {
+    /// The `hidden` cards lie in the three places with `sizes` cards each;
+    /// place i holds only cards of `allowed[i]` and fits `facts[i]`. Cards a
+    /// place surely holds are not allowed anywhere else.
+    pub fn new(hidden: u32, allowed: [u32; 3], sizes: [u32; 3], facts: [Facts; 3]) -> DealSpace {
+        let mut allowed = allowed.map(|cards| cards & hidden);
+        let mut facts = facts;
+        for (place_facts, cards) in facts.iter_mut().zip(allowed.iter_mut()) {
+            place_facts.has_cards &= hidden;
+            *cards &= !place_facts.no_cards;
+        }
+        let has_cards = facts.map(|place_facts| place_facts.has_cards);
+        for (place, cards) in allowed.iter_mut().enumerate() {
+            let elsewhere = (0..3)
+                .filter(|&other| other != place)
+                .fold(0, |acc, other| acc | has_cards[other]);
+            *cards &= !elsewhere;
+        }
+
+        DealSpace {
+            hidden,
+            allowed,
+            sizes,
+            facts,
+        }
+    }
+
+    /// Number of deals allowed by the places, 0 if there is none. Card counts
+    /// of the facts are not taken into account.
+    pub fn count(&self) -> u128 {
+        self.ways()[0][self.sizes[NEXT] as usize][self.sizes[PREVIOUS] as usize]
+    }
+
+    /// Draws a deal (the cards of each place): every deal allowed by the
+    /// places is equally likely, deals not fitting the card counts are drawn
+    /// anew. `None` if there is no deal or none of `MAX_TRIES` deals fits the
+    /// card counts.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Option<[u32; 3]> {
+        let ways = self.ways();
+        if ways[0][self.sizes[NEXT] as usize][self.sizes[PREVIOUS] as usize] == 0 {
+            return None;
+        }
+
+        (0..MAX_TRIES)
+            .map(|_| self.sample_places(&ways, rng))
+            .find(|deal| self.fits_counts(deal))
+    }
+
+    /// All deals fitting the facts, `None` if there are none or more than
+    /// `limit` allowed by the places.
+    pub fn enumerate(&self, limit: u128) -> Option<Vec<[u32; 3]>> {
+        let count = self.count();
+        if count == 0 || count > limit {
+            return None;
+        }
+
+        let mut deals = Vec::with_capacity(count as usize);
+        for next in card_combinations(self.allowed[NEXT], self.sizes[NEXT]) {
+            let previous_pool = self.allowed[PREVIOUS] & !next;
+            for previous in card_combinations(previous_pool, self.sizes[PREVIOUS]) {
+                let skat = self.hidden & !next & !previous;
+                let deal = [next, previous, skat];
+                if skat & !self.allowed[SKAT] == 0 && self.fits_counts(&deal) {
+                    deals.push(deal);
+                }
+            }
+        }
+
+        if deals.is_empty() {
+            None
+        } else {
+            Some(deals)
+        }
+    }
+
+    fn fits_counts(&self, deal: &[u32; 3]) -> bool {
+        (0..3).all(|place| self.facts[place].allows(deal[place]))
+    }
+
+    /// Cards of each group.
+    fn groups(&self) -> [u32; GROUPS] {
+        let mut groups = [0; GROUPS];
+        let (cards, nr) = self.hidden.__decompose();
+        for &card in &cards[..nr] {
+            let group = (0..3)
+                .filter(|&place| self.allowed[place] & card != 0)
+                .fold(0, |group, place| group | 1 << place);
+            groups[group] |= card;
+        }
+        groups
+    }
+
+    /// `ways[g][n][p]`: number of ways to deal the cards of the groups `g..`
+    /// with `n` cards to the next and `p` cards to the previous player, the
+    /// rest to the Skat.
+    fn ways(&self) -> Vec<Vec<Vec<u128>>> {
+        let groups = self.groups();
+        let next_size = self.sizes[NEXT] as usize;
+        let previous_size = self.sizes[PREVIOUS] as usize;
+
+        let mut ways = vec![vec![vec![0u128; previous_size + 1]; next_size + 1]; GROUPS + 1];
+        if self.sizes.iter().sum::<u32>() != self.hidden.count_ones() {
+            return ways;
+        }
+
+        ways[GROUPS][0][0] = 1;
+        for group in (0..GROUPS).rev() {
+            for n in 0..=next_size {
+                for p in 0..=previous_size {
+                    ways[group][n][p] = splits(group, groups[group].count_ones())
+                        .filter(|&(to_next, to_previous, _)| {
+                            to_next as usize <= n && to_previous as usize <= p
+                        })
+                        .map(|(to_next, to_previous, picks)| {
+                            picks * ways[group + 1][n - to_next as usize][p - to_previous as usize]
+                        })
+                        .sum();
+                }
+            }
+        }
+        ways
+    }
+
+    /// Draws the number of cards each group gives to each place in
+    /// proportion to the deals they allow, then the cards themselves.
+    fn sample_places<R: Rng>(&self, ways: &[Vec<Vec<u128>>], rng: &mut R) -> [u32; 3] {
+        let groups = self.groups();
+        let mut n = self.sizes[NEXT] as usize;
+        let mut p = self.sizes[PREVIOUS] as usize;
+        let mut deal = [0; 3];
+
+        for (group, &cards) in groups.iter().enumerate() {
+            let mut pick = rng.gen_range(0..ways[group][n][p]);
+            let (to_next, to_previous, _) = splits(group, cards.count_ones())
+                .filter(|&(to_next, to_previous, _)| {
+                    to_next as usize <= n && to_previous as usize <= p
+                })
+                .find(|&(to_next, to_previous, picks)| {
+                    let deals =
+                        picks * ways[group + 1][n - to_next as usize][p - to_previous as usize];
+                    if pick < deals {
+                        return true;
+                    }
+                    pick -= deals;
+                    false
+                })
+                .expect("Deal counts are inconsistent.");
+
+            let (mut cards, nr) = cards.__decompose();
+            let cards = &mut cards[..nr];
+            cards.shuffle(rng);
+            let (next_cards, rest) = cards.split_at(to_next as usize);
+            let (previous_cards, skat_cards) = rest.split_at(to_previous as usize);
+            deal[NEXT] |= next_cards.iter().fold(0, |acc, card| acc | card);
+            deal[PREVIOUS] |= previous_cards.iter().fold(0, |acc, card| acc | card);
+            deal[SKAT] |= skat_cards.iter().fold(0, |acc, card| acc | card);
+
+            n -= to_next as usize;
+            p -= to_previous as usize;
+        }
+        deal
+    }
+}
+
+/// Ways to split `nr` cards of a group among the places it is allowed in:
+/// (cards to the next player, to the previous player, number of picks).
+fn splits(group: usize, nr: u32) -> impl Iterator<Item = (u32, u32, u128)> {
+    let allows = move |place: usize| group & (1 << place) != 0;
+    (0..=nr)
+        .filter(move |&to_next| to_next == 0 || allows(NEXT))
+        .flat_map(move |to_next| {
+            (0..=nr - to_next)
+                .filter(move |&to_previous| to_previous == 0 || allows(PREVIOUS))
+                .filter(move |&to_previous| to_next + to_previous == nr || allows(SKAT))
+                .map(move |to_previous| {
+                    let picks = binomial(nr, to_next) * binomial(nr - to_next, to_previous);
+                    (to_next, to_previous, picks)
+                })
+        })
+}
+
+/// Number of ways to choose `k` of `n` cards.
+fn binomial(n: u32, k: u32) -> u128 {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1, |acc, i| acc * (n - i) as u128 / (i + 1) as u128)
+}
+
+/// All sets of `nr` cards out of `cards`.
+fn card_combinations(cards: u32, nr: u32) -> Vec<u32> {
+    if nr == 0 {
+        return vec![0];
+    }
+    if cards.count_ones() < nr {
+        return Vec::new();
+    }
+
+    let lowest = cards & cards.wrapping_neg();
+    let rest = cards & !lowest;
+    let mut ret: Vec<u32> = card_combinations(rest, nr - 1)
+        .into_iter()
+        .map(|combination| combination | lowest)
+        .collect();
+    ret.extend(card_combinations(rest, nr));
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DealSpace, NEXT, PREVIOUS, SKAT};
+    use crate::pimc::facts::{Facts, MAX_CARD_COUNTS};
+    use crate::traits::BitConverter;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashMap;
+
+    fn four_cards(allowed_next: &str, facts: [Facts; 3]) -> DealSpace {
+        let hidden = "CA CT SA ST".__bit();
+        DealSpace::new(
+            hidden,
+            [allowed_next.__bit(), hidden, hidden],
+            [1, 1, 2],
+            facts,
+        )
+    }
+
+    #[test]
+    fn test_count_and_enumerate() {
+        let none = [Facts::zero_fact(); 3];
+        let space = four_cards("CA CT", none);
+        // Next: CA or CT; previous: one of the other three
+        assert_eq!(space.count(), 6);
+        let deals = space.enumerate(10).unwrap();
+        assert_eq!(deals.len(), 6);
+        assert!(deals.iter().all(|deal| deal[NEXT] & "CA CT".__bit() != 0));
+        assert!(space.enumerate(5).is_none());
+
+        // The Skat holds ST
+        let skat_facts = [none[0], none[1], Facts::zero_fact().holds("ST".__bit())];
+        let space = DealSpace::new(
+            "CA CT SA ST".__bit(),
+            [
+                "CA CT".__bit(),
+                "CA CT SA ST".__bit(),
+                "CA CT SA ST".__bit(),
+            ],
+            [1, 1, 2],
+            skat_facts,
+        );
+        assert_eq!(space.count(), 4);
+        for deal in space.enumerate(10).unwrap() {
+            assert_ne!(deal[SKAT] & "ST".__bit(), 0);
+        }
+    }
+
+    #[test]
+    fn test_samples_are_uniform() {
+        // Cards the next player cannot hold may still lie in the Skat.
+        let space = four_cards("CA CT", [Facts::zero_fact(); 3]);
+        let mut rng = StdRng::seed_from_u64(20);
+        let mut seen: HashMap<[u32; 3], u32> = HashMap::new();
+        for _ in 0..6000 {
+            *seen.entry(space.sample(&mut rng).unwrap()).or_default() += 1;
+        }
+        assert_eq!(seen.len(), 6);
+        assert!(seen.values().all(|&n| (850..1150).contains(&n)));
+    }
+
+    #[test]
+    fn test_card_counts() {
+        // The previous player signalled to hold CA or SA
+        let facts = [
+            Facts::zero_fact(),
+            Facts::zero_fact().holds_one_of("CA SA".__bit()).unwrap(),
+            Facts::zero_fact(),
+        ];
+        let space = four_cards("CA CT", facts);
+        assert_eq!(space.count(), 6);
+        let deals = space.enumerate(10).unwrap();
+        // Next CA: previous SA; next CT: previous CA or SA
+        assert_eq!(deals.len(), 3);
+
+        let mut rng = StdRng::seed_from_u64(20);
+        for _ in 0..100 {
+            let deal = space.sample(&mut rng).unwrap();
+            assert_ne!(deal[PREVIOUS] & "CA SA".__bit(), 0);
+        }
+
+        // More counts than a player keeps are rejected
+        let mut facts = Facts::zero_fact();
+        for _ in 0..MAX_CARD_COUNTS {
+            facts = facts.holds_one_of("CA SA".__bit()).unwrap();
+        }
+        assert!(facts.holds_one_of("CT".__bit()).is_err());
+    }
+
+    #[test]
+    fn test_contradicting_facts() {
+        let facts = [
+            Facts::zero_fact(),
+            Facts::zero_fact().lacks("CA CT SA".__bit()),
+            Facts::zero_fact().lacks("ST".__bit()),
+        ];
+        let space = four_cards("CA CT", facts);
+        // ST lies with the previous player
+        assert_eq!(space.count(), 2);
+
+        let facts = [
+            Facts::zero_fact(),
+            Facts::zero_fact().lacks("CA CT SA ST".__bit()),
+            Facts::zero_fact(),
+        ];
+        let space = four_cards("CA CT", facts);
+        assert_eq!(space.count(), 0);
+        assert!(space.sample(&mut StdRng::seed_from_u64(1)).is_none());
+        assert!(space.enumerate(10).is_none());
+    }
+}
@@ -0,0 +1,151 @@
+//! # Kontra and Re advice
+//!
+//! Decides whether a defender should say Kontra, or the declarer answer with
+//! Re, from the PIMC win estimate and the expected change of the declarer's
+//! score. The defenders profit from every point the declarer loses.
+
+use crate::pimc::pimc_search::PimcSearch;
+use crate::skat::defs::Player;
+use crate::skat::scoring::Doubling;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DoublingAdvice {
+    /// The announcement under consideration (Kontra or Re).
+    pub doubling: Doubling,
+    pub declarer_win_probability: f32,
+    /// Expected declarer score without and with the announcement.
+    pub expected_score: f32,
+    pub expected_score_doubled: f32,
+    /// Expected gain of the advised player's side by announcing.
+    pub swing: f32,
+    pub announce: bool,
+}
+
+/// Expected declarer score (won: +value, lost: -2 x value) for a game value
+/// before doubling.
+pub fn expected_declarer_score(
+    game_value: u16,
+    declarer_win_probability: f32,
+    doubling: Doubling,
+) -> f32 {
+    let value = (game_value * doubling.factor()) as f32;
+    declarer_win_probability * value - (1.0 - declarer_win_probability) * 2.0 * value
+}
+
+/// Advice for the next announcement after the Kontra/Re state of the
+/// searched problem: Kontra for a defender if nothing was said yet, Re for
+/// the declarer after a Kontra.
+///
+/// `game_value` is the undoubled value the game is expected to count; a
+/// defender who doesn't know the declarer's matadors can use the bid.
+pub fn advise_doubling(
+    search: &PimcSearch,
+    game_value: u16,
+) -> Result<DoublingAdvice, &'static str> {
+    let current = search.uproblem.doubling();
+    let next = current.next().ok_or("No announcement after Re")?;
+    let is_declarer = search.uproblem.my_player() == Player::Declarer;
+
+    match next {
+        Doubling::Kontra if is_declarer => return Err("Only a defender can say Kontra"),
+        Doubling::Re if !is_declarer => return Err("Only the declarer can say Re"),
+        _ => {}
+    }
+
+    let (my_win_probability, _) = search.estimate_win(false);
+    let declarer_win_probability = if is_declarer {
+        my_win_probability
+    } else {
+        1.0 - my_win_probability
+    };
+
+    Ok(doubling_advice(
+        game_value,
+        declarer_win_probability,
+        current,
+        is_declarer,
+    ))
+}
+
+fn doubling_advice(
+    game_value: u16,
+    declarer_win_probability: f32,
+    current: Doubling,
+    is_declarer: bool,
+) -> DoublingAdvice {
+    let doubling = current.next().unwrap_or(current);
+    let expected_score = expected_declarer_score(game_value, declarer_win_probability, current);
+    let expected_score_doubled =
+        expected_declarer_score(game_value, declarer_win_probability, doubling);
+
+    let swing = if is_declarer {
+        expected_score_doubled - expected_score
+    } else {
+        expected_score - expected_score_doubled
+    };
+
+    DoublingAdvice {
+        doubling,
+        declarer_win_probability,
+        expected_score,
+        expected_score_doubled,
+        swing,
+        announce: swing > 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pimc::pimc_problem_builder::PimcProblemBuilder;
+
+    #[test]
+    fn test_expected_declarer_score() {
+        assert_eq!(expected_declarer_score(24, 1.0, Doubling::None), 24.0);
+        assert_eq!(expected_declarer_score(24, 0.0, Doubling::Kontra), -96.0);
+        // Break-even at 2/3 win probability
+        assert!(expected_declarer_score(24, 0.666, Doubling::None) < 0.0);
+        assert!(expected_declarer_score(24, 0.667, Doubling::None) > 0.0);
+    }
+
+    #[test]
+    fn test_kontra_and_re_decisions() {
+        let kontra = doubling_advice(48, 0.5, Doubling::None, false);
+        assert_eq!(kontra.doubling, Doubling::Kontra);
+        assert!(kontra.announce);
+        assert_eq!(kontra.swing, 24.0);
+
+        let no_kontra = doubling_advice(48, 0.9, Doubling::None, false);
+        assert!(!no_kontra.announce);
+
+        let re = doubling_advice(48, 0.9, Doubling::Kontra, true);
+        assert_eq!(re.doubling, Doubling::Re);
+        assert!(re.announce);
+
+        let no_re = doubling_advice(48, 0.5, Doubling::Kontra, true);
+        assert!(!no_re.announce);
+    }
+
+    #[test]
+    fn test_advice_follows_the_doubling_of_the_problem() {
+        let problem = |player: Player, doubling: Doubling| {
+            PimcProblemBuilder::new_farbspiel()
+                .cards(player, "SA SK S9")
+                .remaining_cards("ST SQ S8 C7 H7 D7")
+                .threshold(21)
+                .doubling(doubling)
+                .build()
+        };
+
+        let kontra = problem(Player::Left, Doubling::Kontra);
+        assert_eq!(
+            kontra.generate_concrete_problem().doubling(),
+            Doubling::Kontra
+        );
+        let search = PimcSearch::new(kontra, 10, None);
+        assert!(advise_doubling(&search, 24).is_err());
+
+        let search = PimcSearch::new(problem(Player::Declarer, Doubling::Kontra), 10, None);
+        assert_eq!(advise_doubling(&search, 24).unwrap().doubling, Doubling::Re);
+    }
+}
@@ -1,3 +1,27 @@
+use crate::consts::bitboard::{CLUBS, DIAMONDS, HEARTS, SPADES};
+use crate::skat::defs::{Game, Player};
+use crate::skat::rules::get_suit_for_card;
+
+/// Most card-count constraints of one player.
+pub const MAX_CARD_COUNTS: usize = 4;
+
+/// The player holds at least `min` and at most `max` of `cards`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CardCount {
+    pub cards: u32,
+    pub min: u8,
+    pub max: u8,
+}
+
+impl CardCount {
+    pub fn allows(&self, hand: u32) -> bool {
+        let count = (hand & self.cards).count_ones() as u8;
+        self.min <= count && count <= self.max
+    }
+}
+
+/// What is known about the hidden cards of a player (or of the Skat). Card
+/// constraints refer to the cards still in play.
 #[derive(Clone, Copy)]
 pub struct Facts {
     pub no_trump: bool,
@@ -5,6 +29,11 @@ pub struct Facts {
     pub no_spades: bool,
     pub no_hearts: bool,
     pub no_diamonds: bool,
+    /// Cards the player surely holds.
+    pub has_cards: u32,
+    /// Cards the player surely does not hold.
+    pub no_cards: u32,
+    pub counts: [Option<CardCount>; MAX_CARD_COUNTS],
 }
 
 impl Facts {
@@ -21,7 +50,10 @@ impl Default for Facts {
             no_clubs: false, 
             no_spades: false, 
             no_hearts: false, 
-            no_diamonds: false }
+            no_diamonds: false,
+            has_cards: 0,
+            no_cards: 0,
+            counts: [None; MAX_CARD_COUNTS] }
     }
 }
 
@@ -33,14 +65,148 @@ impl Facts {
             no_spades: false,
             no_hearts: false,
             no_diamonds: false,
+            ..Facts::default()
         }
     }
 
     pub fn zero_fact() -> Facts {
-        Facts{ no_trump: false, no_clubs: false, no_spades: false, no_hearts: false, no_diamonds: false }
+        Facts{ no_trump: false, no_clubs: false, no_spades: false, no_hearts: false, no_diamonds: false, ..Facts::default() }
     }
 
     pub fn one_fact(no_trump: bool, no_clubs: bool, no_spades: bool, no_hearts: bool, no_diamonds: bool) -> Facts {
-        Facts{ no_trump: no_trump, no_clubs: no_clubs, no_spades: no_spades, no_hearts: no_hearts, no_diamonds: no_diamonds }
+        Facts{ no_trump: no_trump, no_clubs: no_clubs, no_spades: no_spades, no_hearts: no_hearts, no_diamonds: no_diamonds, ..Facts::default() }
+    }
+
+    /// The player holds `cards` (and maybe more).
+    pub fn holds(mut self, cards: u32) -> Facts {
+        self.has_cards |= cards;
+        self.no_cards &= !cards;
+        self
+    }
+
+    /// The player holds exactly `cards`, e.g. the cards of an open hand.
+    pub fn holds_exactly(mut self, cards: u32) -> Facts {
+        self.has_cards = cards;
+        self.no_cards = !cards;
+        self
+    }
+
+    /// The player holds none of `cards`.
+    pub fn lacks(mut self, cards: u32) -> Facts {
+        self.no_cards |= cards;
+        self.has_cards &= !cards;
+        self
+    }
+
+    /// The player holds at least one of `cards`, e.g. after a signal.
+    pub fn holds_one_of(self, cards: u32) -> Result<Facts, String> {
+        self.holds_between(cards, 1, 32)
+    }
+
+    /// The player holds between `min` and `max` of `cards`. At most
+    /// `MAX_CARD_COUNTS` of these constraints are kept per player, more are
+    /// an error.
+    pub fn holds_between(mut self, cards: u32, min: u8, max: u8) -> Result<Facts, String> {
+        let slot = self
+            .counts
+            .iter_mut()
+            .find(|count| count.is_none())
+            .ok_or(format!(
+                "Too many card counts in facts, at most {} per player.",
+                MAX_CARD_COUNTS
+            ))?;
+        *slot = Some(CardCount { cards, min, max });
+        Ok(self)
+    }
+
+    /// True if a hand of the player fits the card constraints (the suit voids
+    /// depend on the game and are not checked).
+    pub fn allows(&self, hand: u32) -> bool {
+        hand & self.has_cards == self.has_cards
+            && hand & self.no_cards == 0
+            && self.counts.iter().flatten().all(|count| count.allows(hand))
+    }
+
+    /// Updates the card constraints after the player played `card`: the
+    /// counts refer to the rest of the hand.
+    pub fn card_played(&mut self, card: u32) {
+        self.has_cards &= !card;
+        for count in self.counts.iter_mut().flatten() {
+            if count.cards & card != 0 {
+                count.cards &= !card;
+                count.min = count.min.saturating_sub(1);
+                count.max = count.max.saturating_sub(1);
+            }
+        }
+    }
+}
+
+/// Facts of all players learned from the cards played: a player not
+/// following suit is void in it.
+pub struct FactsTracker {
+    pub declarer: Facts,
+    pub left: Facts,
+    pub right: Facts,
+}
+
+impl FactsTracker {
+    pub fn new() -> Self {
+        Self {
+            declarer: Facts::zero_fact(),
+            left: Facts::zero_fact(),
+            right: Facts::zero_fact(),
+        }
+    }
+
+    /// Records `played_card` of `player` to a trick of `trick_suit` (0 when
+    /// leading).
+    pub fn update_voids(
+        &mut self,
+        played_card: u32,
+        player: Player,
+        trick_suit: u32,
+        game_type: Game,
+    ) {
+        if trick_suit == 0 {
+            return;
+        } // Lead, no voids
+
+        let card_suit = get_suit_for_card(played_card, game_type);
+        if card_suit != trick_suit {
+            // Player is VOID in trick_suit
+            let facts = match player {
+                Player::Declarer => &mut self.declarer,
+                Player::Left => &mut self.left,
+                Player::Right => &mut self.right,
+            };
+
+            // Map trick_suit to Facts fields
+            let trump = game_type.get_trump();
+            if trick_suit == trump {
+                facts.no_trump = true;
+            } else if (trick_suit & CLUBS) != 0 {
+                facts.no_clubs = true;
+            } else if (trick_suit & SPADES) != 0 {
+                facts.no_spades = true;
+            } else if (trick_suit & HEARTS) != 0 {
+                facts.no_hearts = true;
+            } else if (trick_suit & DIAMONDS) != 0 {
+                facts.no_diamonds = true;
+            }
+        }
+    }
+
+    pub fn facts(&self, player: Player) -> Facts {
+        match player {
+            Player::Declarer => self.declarer,
+            Player::Left => self.left,
+            Player::Right => self.right,
+        }
+    }
+}
+
+impl Default for FactsTracker {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -0,0 +1,383 @@
+//! # Information-Set Monte Carlo Tree Search
+//!
+//! Single-observer ISMCTS (Cowling, Powley and Whitehouse): one UCT tree over
+//! the information sets of the searching player instead of one solve per
+//! sample. Every iteration determinizes a deal from the `PimcProblem`, walks
+//! down the tree with the cards legal in that deal, adds one node and plays
+//! the game out with the rollout policy.
+//!
+//! A card is only legal in some deals, so its UCB counts the iterations it
+//! was available in rather than the visits of its parent. Each node scores
+//! the game for the player who played its card (see `player_score`).
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use super::pimc_problem::PimcProblem;
+use super::pimc_search::{half_width, player_score, wilson_interval, MoveMetrics, Z_95};
+use crate::skat::context::GameContext;
+use crate::skat::counters::Counters;
+use crate::skat::defs::{Game, Player};
+use crate::skat::engine::SkatEngine;
+use crate::skat::position::Position;
+use crate::skat::tt::{ReplacementPolicy, TranspositionTable};
+use crate::traits::{Bitboard, Points};
+
+/// Entries of the transposition table of the double dummy rollouts, which
+/// is cleared for every deal.
+const ROLLOUT_TT_SIZE: usize = 1 << 16;
+
+/// How the games are played out below the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RolloutPolicy {
+    /// Random legal cards.
+    #[default]
+    Random,
+    /// Takes a trick as the last player if the own side wins it, else the
+    /// card with the fewest points; random leads.
+    Heuristic,
+    /// Solves the determinized deal double dummy.
+    DoubleDummy,
+}
+
+impl std::str::FromStr for RolloutPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "random" => Ok(RolloutPolicy::Random),
+            "heuristic" => Ok(RolloutPolicy::Heuristic),
+            "double-dummy" | "dd" => Ok(RolloutPolicy::DoubleDummy),
+            _ => Err(format!("Unknown rollout policy: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IsmctsConfig {
+    pub iterations: u32,
+    /// UCT exploration constant, on scores scaled to 0..1.
+    pub exploration: f32,
+    pub rollout: RolloutPolicy,
+}
+
+impl IsmctsConfig {
+    pub fn new(iterations: u32) -> Self {
+        IsmctsConfig {
+            iterations,
+            exploration: 0.7,
+            rollout: RolloutPolicy::Random,
+        }
+    }
+
+    pub fn with_rollout(mut self, rollout: RolloutPolicy) -> Self {
+        self.rollout = rollout;
+        self
+    }
+}
+
+struct Node {
+    /// Card leading here and the player who played it.
+    card: u32,
+    player: Player,
+    visits: u32,
+    /// Iterations in which the card was legal.
+    available: u32,
+    /// Sum of the scores (0..1) of `player`.
+    reward: f32,
+    children: Vec<usize>,
+}
+
+impl Node {
+    fn new(card: u32, player: Player) -> Self {
+        Node {
+            card,
+            player,
+            visits: 0,
+            available: 0,
+            reward: 0.0,
+            children: Vec::new(),
+        }
+    }
+
+    fn ucb(&self, exploration: f32) -> f32 {
+        let mean = self.reward / self.visits as f32;
+        mean + exploration * ((self.available.max(1) as f32).ln() / self.visits as f32).sqrt()
+    }
+}
+
+/// Results of the searching player's card at the root: wins, sum and sum of
+/// squares of the scores and the lowest score.
+#[derive(Clone, Copy)]
+struct RootStats {
+    wins: u32,
+    sum: f32,
+    sum_sq: f32,
+    min: f32,
+}
+
+pub struct IsmctsSearch {
+    pub uproblem: PimcProblem,
+    pub config: IsmctsConfig,
+    /// Seed of the determinizations and rollouts. Random unless set.
+    pub seed: u64,
+}
+
+impl IsmctsSearch {
+    pub fn new(uproblem: PimcProblem, config: IsmctsConfig) -> IsmctsSearch {
+        IsmctsSearch {
+            uproblem,
+            config,
+            seed: rand::random(),
+        }
+    }
+
+    /// Uses `seed` for the search: the same seed gives the same tree.
+    pub fn with_seed(mut self, seed: u64) -> IsmctsSearch {
+        self.seed = seed;
+        self
+    }
+
+    /// Metrics of every card of the searching player from the playouts
+    /// through it, sorted by visits (the card to play first). `samples` are
+    /// the visits; the intervals treat them as independent, which the tree
+    /// policy does not quite make them.
+    pub fn estimate_move_metrics(&self) -> Vec<(u32, MoveMetrics)> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut nodes = vec![Node::new(0, self.uproblem.my_player())];
+        let mut root_stats: Vec<Option<RootStats>> = vec![None];
+        let mut engine: Option<SkatEngine> = None;
+
+        for _ in 0..self.config.iterations {
+            let deal = self.uproblem.generate_concrete_problem_with_rng(&mut rng);
+            let mut position = deal.create_initial_position();
+            let mut path = vec![0];
+
+            while position.player_cards != 0 {
+                let node = *path.last().unwrap();
+                let legal = position.get_legal_moves();
+
+                let mut tried = 0;
+                for child in nodes[node].children.clone() {
+                    if legal & nodes[child].card != 0 {
+                        nodes[child].available += 1;
+                    }
+                    tried |= nodes[child].card;
+                }
+
+                let untried = legal & !tried;
+                let child = if untried != 0 {
+                    let card = random_card(untried, &mut rng);
+                    nodes.push(Node::new(card, position.player));
+                    root_stats.push(None);
+                    let child = nodes.len() - 1;
+                    nodes[node].children.push(child);
+                    nodes[child].available = 1;
+                    child
+                } else {
+                    let exploration = self.config.exploration;
+                    *nodes[node]
+                        .children
+                        .iter()
+                        .filter(|child| legal & nodes[**child].card != 0)
+                        .max_by(|a, b| {
+                            let (a, b) = (nodes[**a].ucb(exploration), nodes[**b].ucb(exploration));
+                            a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+                        })
+                        .unwrap()
+                };
+
+                position = position.make_move(nodes[child].card, &deal);
+                path.push(child);
+                if untried != 0 {
+                    break;
+                }
+            }
+
+            let declarer_points = self.rollout(position, &deal, &mut engine, &mut rng);
+            let game_type = deal.game_type();
+            for &node in &path[1..] {
+                let score = player_score(game_type, nodes[node].player, declarer_points);
+                nodes[node].visits += 1;
+                nodes[node].reward += score / 120.0;
+            }
+
+            if let Some(&first) = path.get(1) {
+                let score = player_score(game_type, self.uproblem.my_player(), declarer_points);
+                let stats = root_stats[first].get_or_insert(RootStats {
+                    wins: 0,
+                    sum: 0.0,
+                    sum_sq: 0.0,
+                    min: 120.0,
+                });
+                stats.wins += self.is_win(declarer_points) as u32;
+                stats.sum += score;
+                stats.sum_sq += score * score;
+                stats.min = stats.min.min(score);
+            }
+        }
+
+        let mut results: Vec<(u32, MoveMetrics)> = nodes[0]
+            .children
+            .iter()
+            .filter_map(|&child| {
+                let stats = root_stats[child]?;
+                let n = nodes[child].visits as f32;
+                let avg = stats.sum / n;
+                let std_dev = (stats.sum_sq / n - avg * avg).max(0.0).sqrt();
+                let win_prob = stats.wins as f32 / n;
+                let points_ci = half_width(std_dev, n, Z_95);
+                Some((
+                    nodes[child].card,
+                    MoveMetrics {
+                        win_prob,
+                        avg_points: avg,
+                        min_points: stats.min,
+                        std_dev,
+                        win_interval: wilson_interval(win_prob, n, Z_95),
+                        points_interval: (avg - points_ci, avg + points_ci),
+                        samples: nodes[child].visits,
+                        effective_samples: n,
+                        exact: false,
+                    },
+                ))
+            })
+            .collect();
+        results.sort_by_key(|(_, metrics)| std::cmp::Reverse(metrics.samples));
+        results
+    }
+
+    /// True if the declarer points mean a win for the searching player.
+    fn is_win(&self, declarer_points: u8) -> bool {
+        let declarer_wins = if self.uproblem.game_type().is_null() {
+            declarer_points == 0
+        } else {
+            declarer_points >= self.uproblem.threshold()
+        };
+        declarer_wins == (self.uproblem.my_player() == Player::Declarer)
+    }
+
+    /// Declarer points at the end of the game played out from `position`.
+    fn rollout<R: Rng>(
+        &self,
+        mut position: Position,
+        deal: &GameContext,
+        engine: &mut Option<SkatEngine>,
+        rng: &mut R,
+    ) -> u8 {
+        if self.config.rollout == RolloutPolicy::DoubleDummy && position.player_cards != 0 {
+            let engine = match engine {
+                Some(engine) => {
+                    engine.set_context(*deal);
+                    engine
+                }
+                None => {
+                    let tt = TranspositionTable::with_capacity(
+                        ROLLOUT_TT_SIZE,
+                        ReplacementPolicy::DepthPreferred,
+                    );
+                    engine.insert(SkatEngine::new(*deal, Some(tt)))
+                }
+            };
+            return engine.search(&position, &mut Counters::new(), 0, 120).1;
+        }
+
+        while position.player_cards != 0 {
+            let legal = position.get_legal_moves();
+            let card = match self.config.rollout {
+                RolloutPolicy::Heuristic => heuristic_card(
+                    legal,
+                    position.trick_cards,
+                    position.player,
+                    deal.game_type(),
+                    |card| position.predict_winner(card, deal).unwrap(),
+                    rng,
+                ),
+                _ => random_card(legal, rng),
+            };
+            position = position.make_move(card, deal);
+        }
+        position.declarer_points
+    }
+}
+
+/// Card of the heuristic rollout of `player`, see `RolloutPolicy::Heuristic`.
+/// `winner` is the player who takes the trick with a card as the last one.
+pub(crate) fn heuristic_card<R: Rng, F: Fn(u32) -> Player>(
+    legal: u32,
+    trick_cards: u32,
+    player: Player,
+    game_type: Game,
+    winner: F,
+    rng: &mut R,
+) -> u32 {
+    let (cards, n) = legal.__decompose();
+    let cards = &cards[0..n];
+
+    if trick_cards == 0 {
+        return random_card(legal, rng);
+    }
+
+    if trick_cards.count_ones() == 2 {
+        let taking = cards
+            .iter()
+            .filter(|card| same_side(game_type, winner(**card), player))
+            .max_by_key(|card| card.card_points());
+        if let Some(&card) = taking {
+            return card;
+        }
+    }
+
+    *cards.iter().min_by_key(|card| card.card_points()).unwrap()
+}
+
+/// True if the players play together. In Ramsch everybody plays alone.
+fn same_side(game_type: Game, a: Player, b: Player) -> bool {
+    a == b || (game_type != Game::Ramsch && (a == Player::Declarer) == (b == Player::Declarer))
+}
+
+fn random_card<R: Rng>(cards: u32, rng: &mut R) -> u32 {
+    let (cards, n) = cards.__decompose();
+    *cards[0..n].choose(rng).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IsmctsConfig, IsmctsSearch, RolloutPolicy};
+    use crate::pimc::pimc_problem_builder::PimcProblemBuilder;
+    use crate::skat::defs::Player;
+    use crate::traits::BitConverter;
+
+    #[test]
+    fn test_ismcts_plays_the_tops_first() {
+        // Leading the Seven gives away a trick and lets the defenders cash
+        // their Spades
+        let uproblem = PimcProblemBuilder::new_grand()
+            .cards(Player::Declarer, "HA HT H7")
+            .remaining_cards("HK HQ H9 SA ST S9")
+            .threshold(61)
+            .build();
+        let seven = "H7".__bit();
+
+        for rollout in [
+            RolloutPolicy::Random,
+            RolloutPolicy::Heuristic,
+            RolloutPolicy::DoubleDummy,
+        ] {
+            let config = IsmctsConfig::new(300).with_rollout(rollout);
+            let metrics = IsmctsSearch::new(uproblem, config)
+                .with_seed(7)
+                .estimate_move_metrics();
+
+            assert_eq!(metrics.len(), 3);
+            assert_eq!(metrics.iter().map(|(_, m)| m.samples).sum::<u32>(), 300);
+            assert_ne!(metrics[0].0, seven, "{:?}", rollout);
+            let worst = metrics
+                .iter()
+                .min_by(|a, b| a.1.avg_points.partial_cmp(&b.1.avg_points).unwrap())
+                .unwrap();
+            assert_eq!(worst.0, seven, "{:?}", rollout);
+        }
+    }
+}
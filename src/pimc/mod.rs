@@ -1,6 +1,11 @@
+pub mod alpha_mu;
 pub mod analysis;
 pub mod best_game;
+pub mod deal_space;
+pub mod doubling;
 pub mod facts;
+pub mod ismcts;
+pub mod opponent_model;
 pub mod pimc_problem;
 pub mod pimc_problem_builder;
 pub mod pimc_search;
@@ -0,0 +1,118 @@
+//! # Opponent Models
+//!
+//! The deals of a PIMC search are drawn uniformly among the deals consistent
+//! with the facts. What the other players did also makes some deals more
+//! likely than others: a bid of 48 hints at two or more Jacks, a Ten thrown
+//! on a trump trick at a void. An opponent model rates each deal with such a
+//! likelihood and `PimcSearch` weights the results of the deal with it
+//! (importance sampling).
+
+use super::facts::CardCount;
+use crate::skat::context::GameContext;
+use crate::skat::defs::Player;
+
+pub trait OpponentModel: Send + Sync {
+    /// Relative likelihood (not negative) of `deal` given the bidding and
+    /// the play so far. Only the ratios between deals matter.
+    fn likelihood(&self, deal: &GameContext) -> f32;
+}
+
+impl<F> OpponentModel for F
+where
+    F: Fn(&GameContext) -> f32 + Send + Sync,
+{
+    fn likelihood(&self, deal: &GameContext) -> f32 {
+        self(deal)
+    }
+}
+
+/// Models that hold together: the likelihoods multiply.
+impl OpponentModel for Vec<Box<dyn OpponentModel>> {
+    fn likelihood(&self, deal: &GameContext) -> f32 {
+        self.iter().map(|model| model.likelihood(deal)).product()
+    }
+}
+
+/// A fact that holds with some probability only, e.g. "the declarer holds at
+/// least two Jacks" after a bid of 48.
+#[derive(Clone, Copy, Debug)]
+pub struct ProbableFact {
+    pub player: Player,
+    pub count: CardCount,
+    pub probability: f32,
+}
+
+impl ProbableFact {
+    pub fn new(player: Player, count: CardCount, probability: f32) -> Self {
+        ProbableFact {
+            player,
+            count,
+            probability,
+        }
+    }
+
+    /// The player probably holds none of `cards`.
+    pub fn void(player: Player, cards: u32, probability: f32) -> Self {
+        Self::new(
+            player,
+            CardCount {
+                cards,
+                min: 0,
+                max: 0,
+            },
+            probability,
+        )
+    }
+}
+
+impl OpponentModel for ProbableFact {
+    fn likelihood(&self, deal: &GameContext) -> f32 {
+        let hand = match self.player {
+            Player::Declarer => deal.declarer_cards(),
+            Player::Left => deal.left_cards(),
+            Player::Right => deal.right_cards(),
+        };
+        if self.count.allows(hand) {
+            self.probability
+        } else {
+            1.0 - self.probability
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OpponentModel, ProbableFact};
+    use crate::consts::bitboard::JACKS;
+    use crate::pimc::facts::CardCount;
+    use crate::skat::builder::GameContextBuilder;
+    use crate::skat::context::GameContext;
+    use crate::skat::defs::{Game, Player};
+
+    #[test]
+    fn test_models_combine() {
+        let deal = GameContextBuilder::new(Game::Grand)
+            .cards(Player::Declarer, "CJ SJ CA")
+            .cards(Player::Left, "HJ SA HA")
+            .cards(Player::Right, "DJ DA CT")
+            .turn(Player::Declarer)
+            .build();
+
+        let two_jacks = CardCount {
+            cards: JACKS,
+            min: 2,
+            max: 4,
+        };
+        let bid = ProbableFact::new(Player::Declarer, two_jacks, 0.9);
+        assert_eq!(bid.likelihood(&deal), 0.9);
+        let void = ProbableFact::void(Player::Left, JACKS, 0.8);
+        assert!((void.likelihood(&deal) - 0.2).abs() < 1e-6);
+
+        let models: Vec<Box<dyn OpponentModel>> = vec![
+            Box::new(bid),
+            Box::new(void),
+            Box::new(|_: &GameContext| 0.5),
+        ];
+        assert!((models.likelihood(&deal) - 0.09).abs() < 1e-6);
+    }
+}
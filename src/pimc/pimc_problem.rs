@@ -2,29 +2,45 @@ use crate::consts::bitboard::{
     ACES, EIGHTS, JACKS, KINGS, NINES, NULL_CLUBS, NULL_DIAMONDS, NULL_HEARTS, NULL_SPADES, QUEENS,
     SEVENS, TENS,
 };
-use crate::skat::builder::GameContextBuilder;
+use crate::skat::builder::{cancel_cards_with_facts, GameContextBuilder};
 use crate::skat::context::GameContext;
 use crate::skat::defs::Game;
 use crate::skat::defs::Player;
+use crate::skat::scoring::Doubling;
 use crate::traits::{BitConverter, StringConverter};
 
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::deal_space::{DealSpace, NEXT, PREVIOUS};
 use super::facts::Facts;
 
-/// How PIMC samples are drawn: purely random, or filtered to plausible Null hands.
+/// Set once samples had to bend the facts, which is reported only once.
+static BENT_FACTS_REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// Weight of a deal failing the hand filter of the sampling mode.
+pub const IMPLAUSIBLE_WEIGHT: f32 = 0.01;
+
+/// How PIMC samples are weighted: all alike, or in favour of plausible hands.
+/// Deals failing the hand filter of a mode weigh `IMPLAUSIBLE_WEIGHT`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum SamplingMode {
     /// Every legal card distribution is equally likely (standard PIMC).
     #[default]
     Random,
-    /// Only distributions where BOTH opponents hold a "likely Null hand" are accepted.
+    /// Distributions where BOTH opponents hold a "likely Null hand".
     /// A likely Null hand has =2 gaps total and gaps in at most 2 suits.
     /// (Gap = a break in the consecutive sequence of a suit that could force a trick.)
     LikelyNull,
-    /// Filter out distributions where the declarer hand doesn't have a minimum EV
+    /// Distributions where the declarer hand has a minimum EV
     SmartGrand,
     SmartSuit,
 }
 
+/// Filter of a sampling mode on a hand.
+type HandCondition = fn(u32) -> bool;
+
 /// Count the number of Null gaps (Fehlstellen) in a single suit.
 ///
 /// Gaps are:
@@ -224,13 +240,19 @@ pub struct PimcProblem {
     // Facts
     facts_previous_player: Facts,
     facts_next_player: Facts,
+    // Known cards of the Skat, e.g. discarded by the declarer
+    facts_skat: Facts,
 
     // Score
     declarer_start_points: u8,
+    doubling: Doubling,
 
     // Asymmetric Information
     skat_cards: Option<u32>,
 
+    // Declarer hand laid open (Null Ouvert)
+    open_declarer_cards: Option<u32>,
+
     // Sampling mode
     pub sampling_mode: SamplingMode,
 
@@ -256,6 +278,11 @@ impl PimcProblem {
         if player != self.my_player {
             ret.infer_facts(played_card, player);
         }
+        if player == self.my_player.dec() {
+            ret.facts_previous_player.card_played(played_card);
+        } else if player == self.my_player.inc() {
+            ret.facts_next_player.card_played(played_card);
+        }
 
         // 3. Update table state + Check Trick Completion
         // Cards on table BEFORE this move:
@@ -684,6 +711,15 @@ impl PimcProblem {
     pub fn facts_next_player(&self) -> Facts {
         self.facts_next_player
     }
+
+    pub fn facts_skat(&self) -> Facts {
+        self.facts_skat
+    }
+
+    /// Kontra/Re said so far.
+    pub fn doubling(&self) -> Doubling {
+        self.doubling
+    }
 }
 
 // Setter
@@ -732,10 +768,18 @@ impl PimcProblem {
         self.facts_next_player = facts;
     }
 
+    pub fn set_facts_skat(&mut self, facts: Facts) {
+        self.facts_skat = facts;
+    }
+
     pub fn set_declarer_start_points(&mut self, points: u8) {
         self.declarer_start_points = points;
     }
 
+    pub fn set_doubling(&mut self, doubling: Doubling) {
+        self.doubling = doubling;
+    }
+
     pub fn set_active_suit(&mut self, active_suit: u32) {
         self.active_suit = active_suit;
     }
@@ -754,8 +798,11 @@ impl PimcProblem {
             threshold: 1u8,
             facts_previous_player: Facts::zero_fact(),
             facts_next_player: Facts::zero_fact(),
+            facts_skat: Facts::zero_fact(),
             declarer_start_points: 0,
+            doubling: Doubling::None,
             skat_cards: None,
+            open_declarer_cards: None,
             sampling_mode: SamplingMode::Random,
             declarer_played_cards: 0,
             all_played_cards: 0,
@@ -770,54 +817,115 @@ impl PimcProblem {
         self.skat_cards
     }
 
+    pub fn set_open_declarer_cards(&mut self, open_cards: u32) {
+        self.open_declarer_cards = Some(open_cards);
+    }
+
+    pub fn open_declarer_cards(&self) -> Option<u32> {
+        self.open_declarer_cards
+    }
+
     pub fn generate_concrete_problem(&self) -> GameContext {
+        self.generate_concrete_problem_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Like `generate_concrete_problem`, drawing the cards from `rng`: a
+    /// seeded generator gives reproducible samples.
+    ///
+    /// Every consistent deal is equally likely, the sampling mode weights the
+    /// deals instead (see `likelihood`).
+    pub fn generate_concrete_problem_with_rng<R: Rng>(&self, rng: &mut R) -> GameContext {
+        self.generate_concrete_problem_inner(rng.gen())
+    }
+
+    /// Likelihood of a deal under the sampling mode: 1 if it passes the hand
+    /// filter of the mode, `IMPLAUSIBLE_WEIGHT` if not.
+    pub fn likelihood(&self, deal: &GameContext) -> f32 {
+        match self.sampling_condition() {
+            Some((condition, check_opponents))
+                if !self.passes_condition(deal, condition, check_opponents) =>
+            {
+                IMPLAUSIBLE_WEIGHT
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Hand filter of the sampling mode and whether it applies to the
+    /// opponents (Left and Right) instead of the declarer.
+    fn sampling_condition(&self) -> Option<(HandCondition, bool)> {
         match self.sampling_mode {
-            SamplingMode::LikelyNull => {
-                self.generate_concrete_problem_rejection_sampled(is_likely_null_hand, true)
-            } // opponent check
-            SamplingMode::SmartGrand => {
-                self.generate_concrete_problem_rejection_sampled(is_playable_grand_hand, false)
-            } // declarer check
-            SamplingMode::SmartSuit => {
-                self.generate_concrete_problem_rejection_sampled(is_playable_suit_hand, false)
-            } // declarer check
-            SamplingMode::Random => self.generate_concrete_problem_inner(),
+            SamplingMode::LikelyNull => Some((is_likely_null_hand, true)), // opponent check
+            SamplingMode::SmartGrand => Some((is_playable_grand_hand, false)), // declarer check
+            SamplingMode::SmartSuit => Some((is_playable_suit_hand, false)), // declarer check
+            SamplingMode::Random => None,
         }
     }
 
-    /// Rejection-sampling version: retries until a condition is met.
-    /// If `check_opponents` is true, checks Left and Right hands.
-    /// If `check_opponents` is false, checks Declarer's hand.
-    fn generate_concrete_problem_rejection_sampled<F>(
-        &self,
-        condition: F,
-        check_opponents: bool,
-    ) -> GameContext
+    fn passes_condition<F>(&self, ctx: &GameContext, condition: F, check_opponents: bool) -> bool
     where
         F: Fn(u32) -> bool,
     {
-        for _ in 0..200 {
-            let ctx = self.generate_concrete_problem_inner();
-            let check_passed = if check_opponents {
-                condition(ctx.left_cards()) && condition(ctx.right_cards())
-            } else {
-                let full_hand = ctx.declarer_cards() | self.declarer_played_cards;
-                condition(full_hand)
-            };
-            if check_passed {
-                return ctx;
+        if check_opponents {
+            condition(ctx.left_cards()) && condition(ctx.right_cards())
+        } else {
+            let full_hand = ctx.declarer_cards() | self.declarer_played_cards;
+            condition(full_hand)
+        }
+    }
+
+    /// Draws a deal uniformly among the consistent deals, see `DealSpace`.
+    /// If the facts contradict each other (or no drawn deal fits the card
+    /// counts), the builder bends them; the first time this is reported.
+    fn generate_concrete_problem_inner(&self, seed: u64) -> GameContext {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let builder = match self.deal_space().sample(&mut rng) {
+            Some(deal) => self.context_builder().set_hands_for_other_players(
+                deal[NEXT],
+                deal[PREVIOUS],
+                self.previous_card,
+                self.next_card,
+                self.my_player,
+            ),
+            None => {
+                if !BENT_FACTS_REPORTED.swap(true, Ordering::Relaxed) {
+                    eprintln!("No deal fits the facts, the samples ignore some of them.");
+                }
+                self.context_builder()
+                    .seed(seed)
+                    .set_cards_for_other_players(
+                        self.calculate_distribution_pool(),
+                        self.previous_card,
+                        self.next_card,
+                        self.my_cards,
+                        self.my_player,
+                        self.next_player_facts(),
+                        self.previous_player_facts(),
+                    )
             }
+        };
+        let problem = builder.build();
+
+        if verify_card_distribution(&problem) {
+            return problem;
+        } else {
+            panic!("Something went wrong in randomly select cards with given facts.");
         }
-        // Fallback: return any valid deal if no suitable match found after 200 tries.
-        self.generate_concrete_problem_inner()
     }
 
-    fn generate_concrete_problem_inner(&self) -> GameContext {
+    /// Builder of the concrete problems with everything but the hands of the
+    /// two other players.
+    fn context_builder(&self) -> GameContextBuilder {
         let _ = self.validate();
 
-        let distribution_pool = self.calculate_distribution_pool();
+        let mut builder = GameContextBuilder::new(self.game_type);
+        if let Some(open_cards) = self.open_declarer_cards {
+            if self.game_type.is_ouvert() && self.my_player != Player::Declarer {
+                builder = builder.open_cards(Player::Declarer, open_cards);
+            }
+        }
 
-        let problem = GameContextBuilder::new(self.game_type)
+        builder
             .cards(Player::Declarer, "")
             .cards(Player::Left, "")
             .cards(Player::Right, "")
@@ -826,24 +934,70 @@ impl PimcProblem {
             .trick_from_uproblem(self.previous_card, self.next_card)
             .threshold(self.threshold)
             .set_cards_for_problem(self.my_cards, self.my_player)
-            .set_cards_for_other_players(
-                distribution_pool,
-                self.previous_card,
-                self.next_card,
-                self.my_cards,
-                self.my_player,
-                self.next_player_facts(),
-                self.previous_player_facts(),
-            )
             .declarer_start_points(self.declarer_start_points)
+            .doubling(self.doubling)
             .played_cards(self.all_played_cards)
-            .build();
+    }
 
-        if verify_card_distribution(&problem) {
-            return problem;
-        } else {
-            panic!("Something went wrong in randomly select cards with given facts.");
-        }
+    /// Places the hidden cards can lie in: the hands of the other two
+    /// players (as drawn by the builder) and the Skat, if any.
+    fn deal_space(&self) -> DealSpace {
+        let pool = self.calculate_distribution_pool();
+        let hidden = pool & !self.my_cards & !self.cards_on_table();
+        let hands = self.context_builder().hidden_hands(
+            pool,
+            self.previous_card,
+            self.next_card,
+            self.my_cards,
+            self.my_player,
+            self.next_player_facts(),
+            self.previous_player_facts(),
+        );
+        let skat_cards = cancel_cards_with_facts(hidden, self.facts_skat, self.game_type);
+        let skat_size = hidden
+            .count_ones()
+            .saturating_sub(hands.next_count + hands.previous_count);
+
+        DealSpace::new(
+            hidden,
+            [hands.next_pool, hands.previous_pool, skat_cards],
+            [hands.next_count, hands.previous_count, skat_size],
+            [
+                self.next_player_facts(),
+                self.previous_player_facts(),
+                self.facts_skat,
+            ],
+        )
+    }
+
+    /// Number of distributions of the hidden cards consistent with my cards,
+    /// the facts and the cards on the table; the cards left over lie in the
+    /// Skat. Card counts of the facts are not taken into account. 0 if the
+    /// facts cannot all hold (sampling then bends them).
+    pub fn count_consistent_deals(&self) -> u128 {
+        self.deal_space().count()
+    }
+
+    /// All consistent deals, each equally likely, or `None` if there are
+    /// none or more than `limit` (see `count_consistent_deals`).
+    pub fn enumerate_consistent_deals(&self, limit: u128) -> Option<Vec<GameContext>> {
+        let deals = self
+            .deal_space()
+            .enumerate(limit)?
+            .iter()
+            .map(|deal| {
+                self.context_builder()
+                    .set_hands_for_other_players(
+                        deal[NEXT],
+                        deal[PREVIOUS],
+                        self.previous_card,
+                        self.next_card,
+                        self.my_player,
+                    )
+                    .build()
+            })
+            .collect();
+        Some(deals)
     }
 
     fn calculate_distribution_pool(&self) -> u32 {
@@ -910,7 +1064,7 @@ fn verify_card_distribution(problem: &GameContext) -> bool {
 // Unit tests
 #[cfg(test)]
 mod tests {
-    use super::{PimcProblem, SamplingMode};
+    use super::{Doubling, PimcProblem, SamplingMode};
     use crate::{
         pimc::pimc_problem::Facts,
         skat::defs::{Game, Player},
@@ -930,9 +1084,12 @@ mod tests {
             threshold: 1,
             facts_previous_player: Facts::one_fact(true, false, false, false, false),
             facts_next_player: Facts::zero_fact(),
+            facts_skat: Facts::zero_fact(),
             declarer_start_points: 0,
+            doubling: Doubling::None,
 
             skat_cards: None,
+            open_declarer_cards: None,
             sampling_mode: SamplingMode::Random,
             declarer_played_cards: 0,
             all_played_cards: 0,
@@ -958,9 +1115,12 @@ mod tests {
             active_suit: 0u32,
             facts_previous_player: Facts::zero_fact(),
             facts_next_player: Facts::zero_fact(),
+            facts_skat: Facts::zero_fact(),
             declarer_start_points: 0,
+            doubling: Doubling::None,
 
             skat_cards: None,
+            open_declarer_cards: None,
             sampling_mode: SamplingMode::Random,
             declarer_played_cards: 0,
             all_played_cards: 0,
@@ -1003,9 +1163,12 @@ mod tests {
             active_suit: 0u32,
             facts_previous_player: Facts::zero_fact(),
             facts_next_player: Facts::one_fact(true, false, false, false, false), // No Trump for Left
+            facts_skat: Facts::zero_fact(),
             declarer_start_points: 0,
+            doubling: Doubling::None,
 
             skat_cards: None,
+            open_declarer_cards: None,
             sampling_mode: SamplingMode::Random,
             declarer_played_cards: 0,
             all_played_cards: 0,
@@ -1062,9 +1225,12 @@ mod tests {
             active_suit: 0u32,
             facts_previous_player: Facts::zero_fact(),
             facts_next_player: Facts::one_fact(false, true, false, false, false), // No Clubs for Left
+            facts_skat: Facts::zero_fact(),
             declarer_start_points: 0,
+            doubling: Doubling::None,
 
             skat_cards: None,
+            open_declarer_cards: None,
             sampling_mode: SamplingMode::Random,
             declarer_played_cards: 0,
             all_played_cards: 0,
@@ -1116,9 +1282,12 @@ mod tests {
             active_suit: 0u32,
             facts_previous_player: Facts::zero_fact(),
             facts_next_player: Facts::one_fact(false, false, true, false, false), // No Spades for Left
+            facts_skat: Facts::zero_fact(),
             declarer_start_points: 0,
+            doubling: Doubling::None,
 
             skat_cards: None,
+            open_declarer_cards: None,
             sampling_mode: SamplingMode::Random,
             declarer_played_cards: 0,
             all_played_cards: 0,
@@ -1173,9 +1342,12 @@ mod tests {
             active_suit: 0u32,
             facts_previous_player: Facts::zero_fact(),
             facts_next_player: Facts::one_fact(true, false, false, false, false), // No Trump for Left
+            facts_skat: Facts::zero_fact(),
             declarer_start_points: 0,
+            doubling: Doubling::None,
 
             skat_cards: None,
+            open_declarer_cards: None,
             sampling_mode: SamplingMode::Random,
             declarer_played_cards: 0,
             all_played_cards: 0,
@@ -1217,9 +1389,12 @@ mod tests {
             active_suit: 0u32,
             facts_previous_player: Facts::zero_fact(),
             facts_next_player: Facts::one_fact(false, true, false, false, false), // No Clubs for Left
+            facts_skat: Facts::zero_fact(),
             declarer_start_points: 0,
+            doubling: Doubling::None,
 
             skat_cards: None,
+            open_declarer_cards: None,
             sampling_mode: SamplingMode::Random,
             declarer_played_cards: 0,
             all_played_cards: 0,
@@ -1262,9 +1437,12 @@ mod tests {
             active_suit: 0u32,
             facts_previous_player: Facts::zero_fact(),
             facts_next_player: Facts::one_fact(false, false, true, false, false), // No Spades for Left
+            facts_skat: Facts::zero_fact(),
             declarer_start_points: 0,
+            doubling: Doubling::None,
 
             skat_cards: None,
+            open_declarer_cards: None,
             sampling_mode: SamplingMode::Random,
             declarer_played_cards: 0,
             all_played_cards: 0,
@@ -1306,9 +1484,12 @@ mod tests {
             active_suit: 0u32,
             facts_previous_player: Facts::zero_fact(),
             facts_next_player: Facts::one_fact(false, false, false, true, false), // No Hearts for Left
+            facts_skat: Facts::zero_fact(),
             declarer_start_points: 0,
+            doubling: Doubling::None,
 
             skat_cards: None,
+            open_declarer_cards: None,
             sampling_mode: SamplingMode::Random,
             declarer_played_cards: 0,
             all_played_cards: 0,
@@ -1350,9 +1531,12 @@ mod tests {
             active_suit: 0u32,
             facts_previous_player: Facts::zero_fact(),
             facts_next_player: Facts::one_fact(false, false, false, false, true), // No Diamonds for Left
+            facts_skat: Facts::zero_fact(),
             declarer_start_points: 0,
+            doubling: Doubling::None,
 
             skat_cards: None,
+            open_declarer_cards: None,
             sampling_mode: SamplingMode::Random,
             declarer_played_cards: 0,
             all_played_cards: 0,
@@ -1426,6 +1610,118 @@ mod tests {
             "Right should be inferred to have No Trump"
         );
     }
+
+    #[test]
+    fn test_null_ouvert_keeps_open_declarer_hand() {
+        // Left (Me): SA SK. Declarer lays CA CK open.
+        // Right and Skat share DA DK HA HK.
+        let mut up = PimcProblem::new();
+        up.set_game_type(Game::NullOuvert);
+        up.set_my_player(Player::Left);
+        up.set_my_cards("SA SK".__bit());
+        up.set_all_cards("CA CK SA SK DA DK HA HK".__bit());
+        up.set_open_declarer_cards("CA CK".__bit());
+
+        for _ in 0..20 {
+            let problem = up.generate_concrete_problem();
+            assert_eq!(problem.declarer_cards(), "CA CK".__bit());
+            assert_eq!(problem.right_cards() & "CA CK SA SK".__bit(), 0);
+            assert_eq!(problem.right_cards().count_ones(), 2);
+        }
+    }
+
+    #[test]
+    fn test_enumerate_consistent_deals() {
+        // Declarer: SA ST, Left and Right share CA CT HA HT.
+        let mut up = PimcProblem::new();
+        up.set_game_type(Game::Suit);
+        up.set_my_player(Player::Declarer);
+        up.set_my_cards("SA ST".__bit());
+        up.set_all_cards("SA ST CA CT HA HT".__bit());
+
+        assert_eq!(up.count_consistent_deals(), 6);
+        let deals = up.enumerate_consistent_deals(100).unwrap();
+        assert_eq!(deals.len(), 6);
+        for (i, deal) in deals.iter().enumerate() {
+            assert_eq!(deal.left_cards().count_ones(), 2);
+            assert_eq!(
+                deal.left_cards() | deal.right_cards(),
+                "CA CT HA HT".__bit()
+            );
+            assert!(deals[..i]
+                .iter()
+                .all(|d| d.left_cards() != deal.left_cards()));
+        }
+        assert!(up.enumerate_consistent_deals(5).is_none());
+
+        // Left has no trump: only one deal remains
+        up.set_facts_next_player(Facts::one_fact(true, false, false, false, false));
+        assert_eq!(up.count_consistent_deals(), 1);
+        let deals = up.enumerate_consistent_deals(100).unwrap();
+        assert_eq!(deals[0].left_cards(), "HA HT".__bit());
+
+        // Both have no trump: contradicting facts are left to sampling
+        up.set_facts_previous_player(Facts::one_fact(true, false, false, false, false));
+        assert_eq!(up.count_consistent_deals(), 0);
+        assert!(up.enumerate_consistent_deals(100).is_none());
+    }
+
+    #[test]
+    fn test_card_facts() {
+        // Left (Me): SA SK. Declarer discarded HA HK and holds DA or DK.
+        let mut up = PimcProblem::new();
+        up.set_game_type(Game::Grand);
+        up.set_my_player(Player::Left);
+        up.set_my_cards("SA SK".__bit());
+        up.set_all_cards("SA SK DA DK HA HK C7 C8".__bit());
+        up.set_facts_skat(Facts::zero_fact().holds("HA HK".__bit()));
+        up.set_facts_previous_player(Facts::zero_fact().holds_one_of("DA DK".__bit()).unwrap());
+
+        // Declarer holds two of DA DK C7 C8, but not C7 C8
+        assert_eq!(up.count_consistent_deals(), 6);
+        assert_eq!(up.enumerate_consistent_deals(100).unwrap().len(), 5);
+
+        for _ in 0..20 {
+            let problem = up.generate_concrete_problem();
+            let hands = problem.declarer_cards() | problem.right_cards();
+            assert_eq!(hands, "DA DK C7 C8".__bit());
+            assert_ne!(problem.declarer_cards() & "DA DK".__bit(), 0);
+        }
+
+        // Declarer leads DA: the other card may be anything
+        let mut facts = up.facts_previous_player();
+        facts.card_played("DA".__bit());
+        up.set_facts_previous_player(facts);
+        up.set_previous_card("DA".__bit());
+        let deals = up.enumerate_consistent_deals(100).unwrap();
+        assert_eq!(deals.len(), 3);
+        assert!(deals
+            .iter()
+            .all(|deal| deal.declarer_cards() & "DA".__bit() != 0));
+    }
+
+    #[test]
+    fn test_enumerate_deals_with_skat_and_trick() {
+        // Left (Me): SA SK. Declarer and Right hold two of DA DK HA HK C7 C8,
+        // the other two lie in the Skat.
+        let mut up = PimcProblem::new();
+        up.set_game_type(Game::Grand);
+        up.set_my_player(Player::Left);
+        up.set_my_cards("SA SK".__bit());
+        up.set_all_cards("SA SK DA DK HA HK C7 C8".__bit());
+        assert_eq!(up.count_consistent_deals(), 15 * 6);
+        assert_eq!(up.enumerate_consistent_deals(100).unwrap().len(), 90);
+
+        // Declarer (my previous player) led C7: Declarer holds one more card
+        up.set_previous_card("C7".__bit());
+        assert_eq!(up.count_consistent_deals(), 5 * 6);
+        for deal in up.enumerate_consistent_deals(100).unwrap() {
+            assert_eq!(deal.declarer_cards() & "C7".__bit(), "C7".__bit());
+            assert_eq!(deal.declarer_cards().count_ones(), 2);
+            assert_eq!(deal.right_cards().count_ones(), 2);
+            assert_eq!(deal.right_cards() & "C7 SA SK".__bit(), 0);
+        }
+    }
 }
 
 #[test]
@@ -2,6 +2,7 @@ use super::{facts::Facts, pimc_problem::PimcProblem, pimc_problem::SamplingMode}
 use crate::{
     consts::bitboard::ALLCARDS,
     skat::defs::{Game, Player},
+    skat::scoring::Doubling,
     traits::{BitConverter, Points},
 };
 
@@ -18,14 +19,17 @@ pub struct PimcProblemBuilder {
     active_suit: Option<u32>,
     threshold: Option<u8>,
     declarer_start_points: Option<u8>,
+    doubling: Option<Doubling>,
 
     // Facts
     facts_declarer: Option<Facts>,
     facts_left: Option<Facts>,
     facts_right: Option<Facts>,
+    facts_skat: Option<Facts>,
 
     // Asymmetric Info
     skat_cards: Option<u32>,
+    open_declarer_cards: Option<u32>,
 
     // Sampling Mode
     sampling_mode: Option<SamplingMode>,
@@ -107,6 +111,12 @@ impl PimcProblemBuilder {
         self
     }
 
+    /// Kontra/Re said so far.
+    pub fn doubling(mut self, doubling: Doubling) -> PimcProblemBuilder {
+        self.doubling = Some(doubling);
+        self
+    }
+
     pub fn threshold_half(mut self) -> PimcProblemBuilder {
         let all_cards = self.all_cards.expect("No all cards found.");
         self.threshold = Some((all_cards.points() as u8 / 2) + 1);
@@ -176,6 +186,13 @@ impl PimcProblemBuilder {
         self
     }
 
+    /// Facts about the Skat, e.g. the cards the declarer is known to have
+    /// discarded. The declarer's own Skat is set with `skat_cards`.
+    pub fn facts_skat(mut self, facts: Facts) -> PimcProblemBuilder {
+        self.facts_skat = Some(facts);
+        self
+    }
+
     // cards part of the game
     pub fn remaining_cards(mut self, cards: &str) -> PimcProblemBuilder {
         let remaining_cards_bit = cards.__bit();
@@ -231,6 +248,17 @@ impl PimcProblemBuilder {
         self
     }
 
+    /// Declarer cards known to everyone in Null Ouvert games.
+    pub fn open_declarer_cards(mut self, open_cards: &str) -> PimcProblemBuilder {
+        self.open_declarer_cards = Some(open_cards.__bit());
+        self
+    }
+
+    pub fn open_declarer_cards_val(mut self, open_cards: u32) -> PimcProblemBuilder {
+        self.open_declarer_cards = Some(open_cards);
+        self
+    }
+
     pub fn build(self) -> PimcProblem {
         self.validate();
 
@@ -273,6 +301,10 @@ impl PimcProblemBuilder {
             }
         }
 
+        if let Some(facts) = self.facts_skat {
+            uproblem.set_facts_skat(facts);
+        }
+
         if let Some(my_cards) = self.my_cards {
             uproblem.set_my_cards(my_cards);
         }
@@ -297,10 +329,18 @@ impl PimcProblemBuilder {
             uproblem.set_declarer_start_points(points);
         }
 
+        if let Some(doubling) = self.doubling {
+            uproblem.set_doubling(doubling);
+        }
+
         if let Some(skat) = self.skat_cards {
             uproblem.set_skat_cards(skat);
         }
 
+        if let Some(open_cards) = self.open_declarer_cards {
+            uproblem.set_open_declarer_cards(open_cards);
+        }
+
         if let Some(active_suit) = self.active_suit {
             uproblem.set_active_suit(active_suit);
         }
@@ -406,8 +446,11 @@ impl Default for PimcProblemBuilder {
             facts_declarer: Some(Facts::zero_fact()),
             facts_left: Some(Facts::zero_fact()),
             facts_right: Some(Facts::zero_fact()),
+            facts_skat: Some(Facts::zero_fact()),
             declarer_start_points: Some(0),
+            doubling: None,
             skat_cards: None,
+            open_declarer_cards: None,
             sampling_mode: None,
             declarer_played_cards: None,
             all_played_cards: None,
@@ -1,30 +1,111 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::sync::Mutex;
+use std::ops::Range;
+use std::sync::Arc;
 
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use rayon::prelude::*;
 
+use super::alpha_mu::{best_share, AlphaMu, MAX_WORLDS};
+use super::opponent_model::OpponentModel;
 use super::pimc_problem::PimcProblem;
-use crate::extensions::solver::{solve_all_cards, solve_win};
-use crate::skat::defs::Game;
-use crate::skat::defs::Player;
+use crate::extensions::solver::{solve_all_cards, solve_win, SolveAllCardsRet};
+use crate::skat::context::GameContext;
+use crate::skat::defs::{Game, Player};
 use crate::skat::engine::SkatEngine;
 use crate::traits::StringConverter;
 
+/// Standard errors of a 95 % confidence interval.
+pub const Z_95: f32 = 1.96;
+
+/// Default of `PimcSearch::exact_limit`.
+pub const EXACT_LIMIT: u32 = 100;
+
+thread_local! {
+    /// Engine of the PIMC solves on this thread, kept for all searches.
+    static ENGINE: RefCell<Option<SkatEngine>> = const { RefCell::new(None) };
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct MoveMetrics {
     pub win_prob: f32,
     pub avg_points: f32,
     pub min_points: f32,
     pub std_dev: f32,
+    /// Confidence intervals (low, high) of `win_prob` (Wilson) and of
+    /// `avg_points`, see `AdaptiveSampling::z`.
+    pub win_interval: (f32, f32),
+    pub points_interval: (f32, f32),
+    /// Samples the metrics are based on.
+    pub samples: u32,
+    /// Number of equally weighted samples worth as much as the weighted
+    /// samples (`samples` if all weigh alike); the intervals rest on it.
+    pub effective_samples: f32,
+    /// True if the samples are all consistent deals: the metrics are exact
+    /// expectations and the intervals collapse to them.
+    pub exact: bool,
+}
+
+/// Sequential sampling of the card estimates. After the first `sample_size`
+/// samples, batches are added until the best card is significantly ahead of
+/// every other card or `max_samples` are used: obvious decisions finish fast,
+/// close ones get more samples.
+///
+/// The lead is tested on the differences of the scores in each sample (all
+/// cards are solved on the same deals). Cards scoring alike in every sample
+/// count as decided.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveSampling {
+    pub max_samples: u32,
+    /// Samples added before the next test.
+    pub batch_size: u32,
+    /// Width of the confidence intervals in standard errors.
+    pub z: f32,
+}
+
+impl AdaptiveSampling {
+    pub fn up_to(max_samples: u32) -> Self {
+        AdaptiveSampling {
+            max_samples,
+            batch_size: 10,
+            z: Z_95,
+        }
+    }
+}
+
+/// How `estimate_probability_of_all_cards` rates the cards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Estimator {
+    /// Every sample is solved on its own.
+    #[default]
+    Pimc,
+    /// αμ search of the next `max_moves` moves of the searching player over
+    /// at most `MAX_WORLDS` samples, see `alpha_mu`.
+    AlphaMu { max_moves: u8 },
 }
 
 pub struct PimcSearch {
     pub uproblem: PimcProblem,
+    /// Number of samples, the first batch if `adaptive` is set.
     pub sample_size: u32,
     pub log_file: Option<String>,
     pub verbose_progress: bool,
+    /// Seed of the samples, see `solve_samples`. Random unless set.
+    pub seed: u64,
+    /// Sequential sampling of the card estimates, fixed sample size if `None`.
+    pub adaptive: Option<AdaptiveSampling>,
+    /// With at most this many consistent deals left, every deal is solved
+    /// once instead of drawing samples (0 always samples), see
+    /// `PimcProblem::enumerate_consistent_deals`.
+    pub exact_limit: u32,
+    /// Likelihood of the deals besides the sampling mode of `uproblem`; the
+    /// results of every deal are weighted with it.
+    pub opponent_model: Option<Arc<dyn OpponentModel>>,
+    pub estimator: Estimator,
 }
 
 impl PimcSearch {
@@ -34,23 +115,81 @@ impl PimcSearch {
             sample_size,
             log_file,
             verbose_progress: false,
+            seed: rand::random(),
+            adaptive: None,
+            exact_limit: EXACT_LIMIT,
+            opponent_model: None,
+            estimator: Estimator::Pimc,
         }
     }
 
+    /// Uses `seed` for the samples: the same seed gives the same samples.
+    pub fn with_seed(mut self, seed: u64) -> PimcSearch {
+        self.seed = seed;
+        self
+    }
+
+    /// Samples the card estimates sequentially (or with the fixed
+    /// `sample_size` if `None`).
+    pub fn with_adaptive(mut self, adaptive: Option<AdaptiveSampling>) -> PimcSearch {
+        self.adaptive = adaptive;
+        self
+    }
+
+    /// Enumerates the consistent deals if there are at most `exact_limit`.
+    pub fn with_exact_limit(mut self, exact_limit: u32) -> PimcSearch {
+        self.exact_limit = exact_limit;
+        self
+    }
+
+    pub fn with_estimator(mut self, estimator: Estimator) -> PimcSearch {
+        self.estimator = estimator;
+        self
+    }
+
+    /// Weights the deals with the likelihood of `model`.
+    pub fn with_opponent_model<M: OpponentModel + 'static>(mut self, model: M) -> PimcSearch {
+        self.opponent_model = Some(Arc::new(model));
+        self
+    }
+
+    /// Weight of a deal: its likelihood under the sampling mode and the
+    /// opponent model.
+    fn weight(&self, context: &GameContext) -> f32 {
+        let model = self
+            .opponent_model
+            .as_ref()
+            .map_or(1.0, |model| model.likelihood(context));
+        self.uproblem.likelihood(context) * model.max(0.0)
+    }
+
+    fn weights<R>(&self, samples: &[(GameContext, R)]) -> Vec<f32> {
+        samples
+            .iter()
+            .map(|(context, _)| self.weight(context))
+            .collect()
+    }
+
+    /// Width of the confidence intervals in standard errors.
+    fn z(&self) -> f32 {
+        self.adaptive.map_or(Z_95, |adaptive| adaptive.z)
+    }
+
+    /// Weighted probability that the searching player wins, and the number of
+    /// samples won.
     pub fn estimate_win(&self, info: bool) -> (f32, u32) {
         let mut sum: f32 = 0.0;
+        let mut wins = 0;
         let my_player = self.uproblem.my_player();
 
-        for i in 0..self.sample_size {
-            if self.verbose_progress {
-                print!(".");
-                std::io::stdout().flush().unwrap();
-            }
+        let samples = match self.exact_deals() {
+            Some(deals) => self.solve_deals(&deals, solve_win),
+            None => self.solve_samples(0..self.sample_size, solve_win),
+        };
 
-            let concrete_problem = self.uproblem.generate_concrete_problem();
-            let mut solver = SkatEngine::new(concrete_problem, None);
-            let search_result = solve_win(&mut solver);
+        let weights = self.weights(&samples);
 
+        for (i, (context, search_result)) in samples.iter().enumerate() {
             let declarer_wins = search_result.declarer_wins;
             let i_win = if my_player == Player::Declarer {
                 declarer_wins
@@ -60,12 +199,9 @@ impl PimcSearch {
 
             if info {
                 println!("Game {}", i);
-                println!(
-                    "Declarer cards: {}",
-                    solver.context.declarer_cards().__str()
-                );
-                println!("Left cards    : {}", solver.context.left_cards().__str());
-                println!("Right cards   : {}", solver.context.right_cards().__str());
+                println!("Declarer cards: {}", context.declarer_cards().__str());
+                println!("Left cards    : {}", context.left_cards().__str());
+                println!("Right cards   : {}", context.right_cards().__str());
                 println!(
                     "Best card: {} DeclWin: {} MyWin: {}",
                     search_result.best_card.__str(),
@@ -76,7 +212,8 @@ impl PimcSearch {
             }
 
             if i_win {
-                sum += 1.0;
+                sum += weights[i];
+                wins += 1;
             }
 
             if let Some(path) = &self.log_file {
@@ -87,21 +224,16 @@ impl PimcSearch {
                     .unwrap();
 
                 writeln!(file, "Sample {}:", i).unwrap();
-                writeln!(file, "Game Type: {:?}", solver.context.game_type()).unwrap();
-                writeln!(
-                    file,
-                    "Declarer : {}",
-                    solver.context.declarer_cards().__str()
-                )
-                .unwrap();
-                writeln!(file, "Left     : {}", solver.context.left_cards().__str()).unwrap();
-                writeln!(file, "Right    : {}", solver.context.right_cards().__str()).unwrap();
+                writeln!(file, "Game Type: {:?}", context.game_type()).unwrap();
+                writeln!(file, "Declarer : {}", context.declarer_cards().__str()).unwrap();
+                writeln!(file, "Left     : {}", context.left_cards().__str()).unwrap();
+                writeln!(file, "Right    : {}", context.right_cards().__str()).unwrap();
 
                 let skat = crate::skat::defs::ALLCARDS
-                    ^ solver.context.declarer_cards()
-                    ^ solver.context.left_cards()
-                    ^ solver.context.right_cards()
-                    ^ solver.context.trick_cards();
+                    ^ context.declarer_cards()
+                    ^ context.left_cards()
+                    ^ context.right_cards()
+                    ^ context.trick_cards();
 
                 writeln!(file, "Skat     : {}", skat.__str()).unwrap();
 
@@ -109,57 +241,44 @@ impl PimcSearch {
                 writeln!(file, "--------------------------------------------------").unwrap();
             }
         }
-        (sum / self.sample_size as f32, sum as u32)
+        (weighted_mean(sum, weights.iter().sum()), wins)
     }
 
+    /// Win probability of every card of the searching player, sorted
+    /// descending, by the `estimator`.
     pub fn estimate_probability_of_all_cards(&self, info: bool) -> Vec<(u32, f32)> {
-        let my_player = self.uproblem.my_player();
+        self.estimate_weighted_probability_of_all_cards(info).0
+    }
+
+    /// Like `estimate_probability_of_all_cards`, with the effective number
+    /// of samples behind the probabilities (see `MoveMetrics`).
+    pub fn estimate_weighted_probability_of_all_cards(&self, info: bool) -> (Vec<(u32, f32)>, f32) {
+        if let Estimator::AlphaMu { max_moves } = self.estimator {
+            return self.estimate_alpha_mu(max_moves, info);
+        }
         let threshold = self.uproblem.threshold();
-        let game_type = self.uproblem.game_type();
 
-        // Shared accumulator: card → total win-count across all samples.
-        let global_dict: Mutex<HashMap<u32, u32>> = Mutex::new(HashMap::new());
+        let (samples, _) = self.solve_card_samples(threshold - 1, threshold, |value| {
+            self.is_win(value) as u8 as f32
+        });
+
+        let weights = self.weights(&samples);
 
-        (0..self.sample_size).into_par_iter().for_each(|i| {
-            let concrete_problem = self.uproblem.generate_concrete_problem();
-            let mut solver = SkatEngine::new(concrete_problem, None);
-            let search_result = solve_all_cards(&mut solver, threshold - 1, threshold);
+        // card → (weighted wins, total weight) across all samples.
+        let mut global: BTreeMap<u32, (f32, f32)> = BTreeMap::new();
 
-            let mut local_dict: HashMap<u32, u8> = HashMap::new();
+        for (i, (context, search_result)) in samples.iter().enumerate() {
             for line in search_result.results.iter() {
                 let card = line.0;
                 let value = line.2;
 
-                let declarer_wins = if game_type == Game::Null {
-                    value == 0
-                } else {
-                    value >= threshold
-                };
-                let winvalue: u8 = if my_player == Player::Declarer {
-                    if declarer_wins {
-                        1
-                    } else {
-                        0
-                    }
-                } else {
-                    if !declarer_wins {
-                        1
-                    } else {
-                        0
-                    }
-                };
-                local_dict.insert(card, winvalue);
-            }
-
-            // Merge into global accumulator.
-            {
-                let mut global = global_dict.lock().unwrap();
-                for (&card, &wv) in local_dict.iter() {
-                    *global.entry(card).or_insert(0) += wv as u32;
+                let entry = global.entry(card).or_insert((0.0, 0.0));
+                if self.is_win(value) {
+                    entry.0 += weights[i];
                 }
+                entry.1 += weights[i];
             }
 
-            // Optional per-sample log file (serialised via re-opening the file).
             if let Some(path) = &self.log_file {
                 let mut file = OpenOptions::new()
                     .create(true)
@@ -167,35 +286,57 @@ impl PimcSearch {
                     .open(path)
                     .unwrap();
                 writeln!(file, "Sample {}:", i).unwrap();
-                writeln!(
-                    file,
-                    "Declarer: {}",
-                    solver.context.declarer_cards().__str()
-                )
-                .unwrap();
-                writeln!(file, "Left    : {}", solver.context.left_cards().__str()).unwrap();
-                writeln!(file, "Right   : {}", solver.context.right_cards().__str()).unwrap();
+                writeln!(file, "Declarer: {}", context.declarer_cards().__str()).unwrap();
+                writeln!(file, "Left    : {}", context.left_cards().__str()).unwrap();
+                writeln!(file, "Right   : {}", context.right_cards().__str()).unwrap();
                 writeln!(file, "--------------------------------------------------").unwrap();
             }
 
             if info {
-                println!(
-                    "Sample {}: Declarer={} Left={} Right={}",
-                    i,
-                    solver.context.declarer_cards().__str(),
-                    solver.context.left_cards().__str(),
-                    solver.context.right_cards().__str(),
-                );
+                print_sample(i, context);
             }
-        });
+        }
 
-        let global = global_dict.into_inner().unwrap();
         let mut sorted: Vec<(u32, f32)> = global
             .iter()
-            .map(|(&card, &wins)| (card, wins as f32 / self.sample_size as f32))
+            .map(|(&card, &(wins, total))| (card, weighted_mean(wins, total)))
             .collect();
         sorted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        sorted
+        (sorted, effective_samples(&weights))
+    }
+
+    /// Win probability of every card under the αμ search: the weighted share
+    /// of the samples won with the best outcome of the card's Pareto front.
+    fn estimate_alpha_mu(&self, max_moves: u8, info: bool) -> (Vec<(u32, f32)>, f32) {
+        let threshold = self.uproblem.threshold();
+        let deals = match self.exact_deals() {
+            Some(deals) if deals.len() <= MAX_WORLDS => deals,
+            _ => (0..self.sample_size.min(MAX_WORLDS as u32))
+                .map(|i| self.draw_sample(i))
+                .collect(),
+        };
+        let weights: Vec<f32> = deals.iter().map(|deal| self.weight(deal)).collect();
+
+        if info {
+            for (i, deal) in deals.iter().enumerate() {
+                print_sample(i, deal);
+            }
+        }
+
+        let mut search = AlphaMu::new(
+            &deals,
+            self.uproblem.my_player(),
+            threshold - 1,
+            threshold,
+            |value| self.is_win(value),
+        );
+        let mut sorted: Vec<(u32, f32)> = search
+            .search(max_moves)
+            .iter()
+            .map(|(card, front)| (*card, best_share(front, &weights)))
+            .collect();
+        sorted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        (sorted, effective_samples(&weights))
     }
 
     /// Like `estimate_probability_of_all_cards` but returns the **average declarer point value**
@@ -208,175 +349,106 @@ impl PimcSearch {
     ///
     /// Returns `Vec<(card, avg_score)>` sorted descending by avg_score.
     pub fn estimate_avg_points_of_all_cards(&self, info: bool) -> Vec<(u32, f32)> {
-        let my_player = self.uproblem.my_player();
-        let game_type = self.uproblem.game_type();
+        // Full-range solve to get exact point values (0–120).
+        let (samples, _) = self.solve_card_samples(0, 120, |value| self.player_score(value));
 
-        // Shared accumulator: card → (sum_of_scores, count).
-        let global: Mutex<HashMap<u32, (f32, u32)>> = Mutex::new(HashMap::new());
+        let weights = self.weights(&samples);
 
-        (0..self.sample_size).into_par_iter().for_each(|i| {
-            let concrete_problem = self.uproblem.generate_concrete_problem();
-            let mut solver = SkatEngine::new(concrete_problem, None);
-            // Full-range solve to get exact point values (0–120).
-            let search_result = solve_all_cards(&mut solver, 0, 120);
+        // card → (weighted sum of scores, total weight).
+        let mut global: BTreeMap<u32, (f32, f32)> = BTreeMap::new();
 
-            let mut local: Vec<(u32, f32)> = Vec::new();
+        for (i, (context, search_result)) in samples.iter().enumerate() {
             for line in search_result.results.iter() {
                 let card = line.0;
-                let decl_points = line.2; // u8, 0–120
-
-                let player_score: f32 = if game_type == Game::Null {
-                    if decl_points == 0 {
-                        if my_player == Player::Declarer {
-                            120.0
-                        } else {
-                            0.0
-                        }
-                    } else {
-                        if my_player == Player::Declarer {
-                            0.0
-                        } else {
-                            120.0
-                        }
-                    }
-                } else if my_player == Player::Declarer {
-                    decl_points as f32
-                } else {
-                    120.0 - decl_points as f32
-                };
-                local.push((card, player_score));
-            }
+                let player_score = self.player_score(line.2);
 
-            // Merge into global accumulator.
-            {
-                let mut g = global.lock().unwrap();
-                for (card, score) in local {
-                    let entry = g.entry(card).or_insert((0.0, 0));
-                    entry.0 += score;
-                    entry.1 += 1;
-                }
+                let entry = global.entry(card).or_insert((0.0, 0.0));
+                entry.0 += weights[i] * player_score;
+                entry.1 += weights[i];
             }
 
             if info {
-                println!(
-                    "Sample {}: Declarer={} Left={} Right={}",
-                    i,
-                    solver.context.declarer_cards().__str(),
-                    solver.context.left_cards().__str(),
-                    solver.context.right_cards().__str(),
-                );
+                print_sample(i, context);
             }
-        });
+        }
 
         // Compute averages and sort descending.
-        let g = global.into_inner().unwrap();
-        let mut entries: Vec<(u32, f32)> = g
+        let mut entries: Vec<(u32, f32)> = global
             .iter()
-            .map(|(&card, &(sum, cnt))| (card, sum / cnt.max(1) as f32))
+            .map(|(&card, &(sum, total))| (card, weighted_mean(sum, total)))
             .collect();
         entries.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         entries
     }
 
     /// Evaluates `win_prob`, `avg_points`, and `min_points` for each card in a single pass.
-    /// Used by PointsPlayout hybrid/minimum strategies.
+    /// Used by PointsPlayout hybrid/minimum strategies. Adaptive sampling
+    /// tests the lead of the best `avg_points`.
     pub fn estimate_move_metrics(&self, info: bool) -> Vec<(u32, MoveMetrics)> {
-        let my_player = self.uproblem.my_player();
-        let game_type = self.uproblem.game_type();
-        let threshold = self.uproblem.threshold();
-
-        // Accumulator: card -> (wins, points_sum, points_sum_sq, points_min, count)
-        let global: Mutex<HashMap<u32, (u32, f32, f32, f32, u32)>> = Mutex::new(HashMap::new());
-
-        (0..self.sample_size).into_par_iter().for_each(|i| {
-            let concrete_problem = self.uproblem.generate_concrete_problem();
-            let mut solver = SkatEngine::new(concrete_problem, None);
-            let search_result = solve_all_cards(&mut solver, 0, 120);
-
-            let mut local: Vec<(u32, bool, f32)> = Vec::new();
+        let (samples, exact) = self.solve_card_samples(0, 120, |value| self.player_score(value));
+        let z = self.z();
+        let weights = self.weights(&samples);
+        let total: f32 = weights.iter().sum();
+        let effective = effective_samples(&weights);
+
+        // Accumulator: card -> (weighted wins, points_sum, points_sum_sq, points_min, count),
+        // sums weighted
+        let mut global: BTreeMap<u32, (f32, f32, f32, f32, u32)> = BTreeMap::new();
+
+        for (i, (context, search_result)) in samples.iter().enumerate() {
+            let weight = weights[i];
             for line in search_result.results.iter() {
                 let card = line.0;
                 let decl_points = line.2; // u8, 0-120
 
-                // Win Prob Logic
-                let declarer_wins = if game_type == Game::Null {
-                    decl_points == 0
-                } else {
-                    decl_points >= threshold
-                };
-                let win = if my_player == Player::Declarer {
-                    declarer_wins
-                } else {
-                    !declarer_wins
-                };
+                let win = self.is_win(decl_points);
+                let player_score = self.player_score(decl_points);
 
-                // Points Logic
-                let player_score: f32 = if game_type == Game::Null {
-                    if decl_points == 0 {
-                        if my_player == Player::Declarer {
-                            120.0
-                        } else {
-                            0.0
-                        }
-                    } else {
-                        if my_player == Player::Declarer {
-                            0.0
-                        } else {
-                            120.0
-                        }
-                    }
-                } else if my_player == Player::Declarer {
-                    decl_points as f32
-                } else {
-                    120.0 - decl_points as f32
-                };
-
-                local.push((card, win, player_score));
-            }
-
-            // Merge into global
-            {
-                let mut g = global.lock().unwrap();
-                for (card, win, score) in local {
-                    let entry = g.entry(card).or_insert((0, 0.0, 0.0, 150.0, 0));
-                    if win {
-                        entry.0 += 1;
-                    }
-                    entry.1 += score;
-                    entry.2 += score * score;
-                    if score < entry.3 {
-                        entry.3 = score;
-                    }
-                    entry.4 += 1;
+                let entry = global.entry(card).or_insert((0.0, 0.0, 0.0, 150.0, 0));
+                if win {
+                    entry.0 += weight;
+                }
+                entry.1 += weight * player_score;
+                entry.2 += weight * player_score * player_score;
+                // Deals ruled out by the weights cannot be the worst case
+                if player_score < entry.3 && weight > 0.0 {
+                    entry.3 = player_score;
                 }
+                entry.4 += 1;
             }
 
             if info {
-                println!(
-                    "Sample {}: Declarer={} Left={} Right={}",
-                    i,
-                    solver.context.declarer_cards().__str(),
-                    solver.context.left_cards().__str(),
-                    solver.context.right_cards().__str(),
-                );
+                print_sample(i, context);
             }
-        });
+        }
 
-        let g = global.into_inner().unwrap();
-        let mut results: Vec<(u32, MoveMetrics)> = g
+        let mut results: Vec<(u32, MoveMetrics)> = global
             .into_iter()
             .map(|(card, (wins, sum, sum_sq, min, count))| {
-                let cnt = count.max(1) as f32;
-                let avg = sum / cnt;
-                let variance = (sum_sq / cnt) - avg * avg;
+                let avg = weighted_mean(sum, total);
+                let variance = weighted_mean(sum_sq, total) - avg * avg;
                 let std_dev = variance.max(0.0).sqrt();
+                let win_prob = weighted_mean(wins, total);
+                let (win_interval, points_ci) = if exact {
+                    ((win_prob, win_prob), 0.0)
+                } else {
+                    (
+                        wilson_interval(win_prob, effective, z),
+                        half_width(std_dev, effective, z),
+                    )
+                };
                 (
                     card,
                     MoveMetrics {
-                        win_prob: wins as f32 / cnt,
+                        win_prob,
                         avg_points: avg,
-                        min_points: if count == 0 { 0.0 } else { min },
+                        min_points: if total == 0.0 { 0.0 } else { min },
                         std_dev,
+                        win_interval,
+                        points_interval: (avg - points_ci, avg + points_ci),
+                        samples: count,
+                        effective_samples: effective,
+                        exact,
                     },
                 )
             })
@@ -390,12 +462,273 @@ impl PimcSearch {
         });
         results
     }
+
+    /// True if the declarer points mean a win for the searching player.
+    fn is_win(&self, declarer_points: u8) -> bool {
+        let declarer_wins = if self.uproblem.game_type().is_null() {
+            declarer_points == 0
+        } else {
+            declarer_points >= self.uproblem.threshold()
+        };
+        declarer_wins == (self.uproblem.my_player() == Player::Declarer)
+    }
+
+    /// Score (0–120) of the declarer points for the searching player, see
+    /// `player_score`.
+    fn player_score(&self, declarer_points: u8) -> f32 {
+        player_score(
+            self.uproblem.game_type(),
+            self.uproblem.my_player(),
+            declarer_points,
+        )
+    }
+
+    /// Solves all cards in the window `alpha`..`beta` on the samples, with
+    /// sequential sampling if `adaptive` is set; `score` rates the declarer
+    /// points of a card for the test of the lead. True if the samples are
+    /// all consistent deals (see `exact_limit`).
+    fn solve_card_samples<S>(
+        &self,
+        alpha: u8,
+        beta: u8,
+        score: S,
+    ) -> (Vec<(GameContext, SolveAllCardsRet)>, bool)
+    where
+        S: Fn(u8) -> f32,
+    {
+        let solve = |engine: &mut SkatEngine| solve_all_cards(engine, alpha, beta);
+        if let Some(deals) = self.exact_deals() {
+            return (self.solve_deals(&deals, solve), true);
+        }
+
+        let mut samples = self.solve_samples(0..self.sample_size, solve);
+
+        if let Some(adaptive) = self.adaptive {
+            while (samples.len() as u32) < adaptive.max_samples
+                && !lead_is_significant(
+                    &card_scores(&samples, &score),
+                    &self.weights(&samples),
+                    adaptive.z,
+                )
+            {
+                let start = samples.len() as u32;
+                let end = (start + adaptive.batch_size.max(1)).min(adaptive.max_samples);
+                samples.extend(self.solve_samples(start..end, solve));
+            }
+        }
+        (samples, false)
+    }
+
+    /// All consistent deals if there are at most `exact_limit`.
+    fn exact_deals(&self) -> Option<Vec<GameContext>> {
+        self.uproblem
+            .enumerate_consistent_deals(self.exact_limit as u128)
+    }
+
+    /// Draws and solves the samples of `range` in parallel, returning the
+    /// deals and the results of `solve` in sample order.
+    ///
+    /// Sample `i` is drawn from its own generator seeded with `seed + i`, so
+    /// the results do not depend on the thread schedule.
+    fn solve_samples<R, F>(&self, range: Range<u32>, solve: F) -> Vec<(GameContext, R)>
+    where
+        R: Send,
+        F: Fn(&mut SkatEngine) -> R + Sync,
+    {
+        self.solve_each(range, |i| self.draw_sample(i), solve)
+    }
+
+    /// Sample `i`, drawn from its own generator seeded with `seed + i`.
+    fn draw_sample(&self, i: u32) -> GameContext {
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(i as u64));
+        self.uproblem.generate_concrete_problem_with_rng(&mut rng)
+    }
+
+    /// Solves the given deals in parallel, in order.
+    fn solve_deals<R, F>(&self, deals: &[GameContext], solve: F) -> Vec<(GameContext, R)>
+    where
+        R: Send,
+        F: Fn(&mut SkatEngine) -> R + Sync,
+    {
+        self.solve_each(0..deals.len() as u32, |i| deals[i as usize], solve)
+    }
+
+    /// Solves the deals `deal(i)` of `range` in parallel. Every thread reuses
+    /// its engine (see `ENGINE`), and with it one transposition table, for
+    /// its deals.
+    fn solve_each<R, D, F>(&self, range: Range<u32>, deal: D, solve: F) -> Vec<(GameContext, R)>
+    where
+        R: Send,
+        D: Fn(u32) -> GameContext + Sync,
+        F: Fn(&mut SkatEngine) -> R + Sync,
+    {
+        range
+            .into_par_iter()
+            .map(|i| {
+                if self.verbose_progress {
+                    print!(".");
+                    std::io::stdout().flush().unwrap();
+                }
+
+                let context = deal(i);
+                let result = ENGINE.with(|engine| match engine.try_borrow_mut() {
+                    Ok(mut engine) => match engine.as_mut() {
+                        Some(engine) => {
+                            engine.set_context(context);
+                            solve(engine)
+                        }
+                        None => solve(engine.insert(SkatEngine::new(context, None))),
+                    },
+                    // A solve nested in another one of the thread
+                    Err(_) => solve(&mut SkatEngine::new(context, None)),
+                });
+                (context, result)
+            })
+            .collect()
+    }
+}
+
+/// Score (0–120) of the declarer points at the end of the game for `player`,
+/// higher is better.
+///
+/// - Defender: the points are inverted to `120 - decl_points`.
+/// - Null games: a win scores 120, a loss 0.
+/// - Ramsch: paranoid view, the declarer seat collects as few points as
+///   possible.
+pub fn player_score(game_type: Game, player: Player, declarer_points: u8) -> f32 {
+    let is_declarer = player == Player::Declarer;
+
+    if game_type.is_null() {
+        if (declarer_points == 0) == is_declarer {
+            120.0
+        } else {
+            0.0
+        }
+    } else if (game_type == Game::Ramsch) == is_declarer {
+        120.0 - declarer_points as f32
+    } else {
+        declarer_points as f32
+    }
+}
+
+/// Scores of every card in sample order.
+fn card_scores<S>(samples: &[(GameContext, SolveAllCardsRet)], score: S) -> BTreeMap<u32, Vec<f32>>
+where
+    S: Fn(u8) -> f32,
+{
+    let mut scores: BTreeMap<u32, Vec<f32>> = BTreeMap::new();
+    for (_, result) in samples {
+        for &(card, _, value) in &result.results {
+            scores.entry(card).or_default().push(score(value));
+        }
+    }
+    scores
+}
+
+/// True if the card with the best weighted mean score is ahead of every
+/// other card by more than `z` standard errors of the differences per sample,
+/// or scores alike in every sample.
+fn lead_is_significant(scores: &BTreeMap<u32, Vec<f32>>, weights: &[f32], z: f32) -> bool {
+    let total: f32 = weights.iter().sum();
+    let mean = |values: &[f32]| {
+        let sum = values.iter().zip(weights).map(|(v, w)| v * w).sum::<f32>();
+        weighted_mean(sum, total)
+    };
+    let best = match scores
+        .values()
+        .max_by(|a, b| mean(a).partial_cmp(&mean(b)).unwrap_or(Ordering::Equal))
+    {
+        Some(best) => best,
+        None => return false,
+    };
+    let effective = effective_samples(weights);
+
+    scores
+        .values()
+        .filter(|other| !std::ptr::eq(*other, best))
+        .all(|other| {
+            let diffs: Vec<f32> = best.iter().zip(other).map(|(b, o)| b - o).collect();
+            let lead = mean(&diffs);
+            let squares: Vec<f32> = diffs.iter().map(|d| (d - lead) * (d - lead)).collect();
+            let variance = mean(&squares);
+
+            diffs.iter().all(|d| *d == 0.0) || lead > half_width(variance.sqrt(), effective, z)
+        })
+}
+
+/// Sum of weighted values divided by the total weight, 0 without weight.
+fn weighted_mean(sum: f32, total: f32) -> f32 {
+    if total > 0.0 {
+        sum / total
+    } else {
+        0.0
+    }
+}
+
+/// Kish's effective sample size of the weights: the number of equally
+/// weighted samples giving the same variance of a mean.
+fn effective_samples(weights: &[f32]) -> f32 {
+    let total: f32 = weights.iter().sum();
+    let squares: f32 = weights.iter().map(|w| w * w).sum();
+    if squares > 0.0 {
+        total * total / squares
+    } else {
+        0.0
+    }
+}
+
+/// Half width of the confidence interval of a mean of `n` values with the
+/// standard deviation `std_dev` (of the values, not corrected). Infinite for
+/// less than two values.
+pub(crate) fn half_width(std_dev: f32, n: f32, z: f32) -> f32 {
+    if n < 2.0 {
+        return f32::INFINITY;
+    }
+    z * std_dev / (n - 1.0).sqrt()
+}
+
+/// Wilson score interval of the win probability `p` in `n` trials, which
+/// unlike the normal approximation does not collapse at 0 or 1.
+pub(crate) fn wilson_interval(p: f32, n: f32, z: f32) -> (f32, f32) {
+    if n <= 0.0 {
+        return (0.0, 1.0);
+    }
+    let z2 = z * z;
+    let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let half = z / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+    ((center - half).max(0.0), (center + half).min(1.0))
+}
+
+fn print_sample(i: usize, context: &GameContext) {
+    println!(
+        "Sample {}: Declarer={} Left={} Right={}",
+        i,
+        context.declarer_cards().__str(),
+        context.left_cards().__str(),
+        context.right_cards().__str(),
+    );
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{pimc::pimc_problem_builder::PimcProblemBuilder, skat::defs::Player};
 
+    /// Clubs game with six hidden cards, so only 20 deals are consistent.
+    fn few_deals_game() -> PimcProblemBuilder {
+        PimcProblemBuilder::new_farbspiel()
+            .cards(Player::Declarer, "CJ SJ HA")
+            .remaining_cards("HJ CA CT H7 SA ST")
+            .threshold(30)
+    }
+
+    /// Clubs game with ten hidden cards, too many deals to enumerate.
+    fn sampled_game() -> PimcProblemBuilder {
+        PimcProblemBuilder::new_farbspiel()
+            .cards(Player::Declarer, "CJ SJ HA HK S7")
+            .remaining_cards("HJ DJ CA CT H7 H8 SA ST D7 D8")
+            .threshold(30)
+    }
+
     #[test]
     fn test_uproblem_three_cards() {
         let uproblem = PimcProblemBuilder::new_farbspiel()
@@ -492,4 +825,200 @@ mod tests {
             "Defender should have high win prob (Declarer leads to loss)"
         );
     }
+
+    #[test]
+    fn test_samples_do_not_depend_on_threads() {
+        let uproblem = sampled_game().build();
+
+        let mut search = super::PimcSearch::new(uproblem, 24, None);
+        search.seed = 16;
+
+        let metrics = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(|| search.estimate_move_metrics(false))
+                .iter()
+                .map(|(card, m)| (*card, m.win_prob, m.avg_points, m.min_points))
+                .collect::<Vec<_>>()
+        };
+
+        let sequential = metrics(1);
+        assert_eq!(sequential, metrics(4));
+        assert_eq!(sequential.len(), 5);
+    }
+
+    #[test]
+    fn test_lead_is_significant() {
+        use super::{lead_is_significant, Z_95};
+        use std::collections::BTreeMap;
+
+        let scores = |best: Vec<f32>, other: Vec<f32>| {
+            let mut scores = BTreeMap::new();
+            scores.insert(1, best);
+            scores.insert(2, other);
+            scores
+        };
+
+        // Clear lead in every sample
+        let clear = scores(vec![60.0, 70.0, 65.0, 80.0], vec![30.0, 45.0, 20.0, 40.0]);
+        let weights = [1.0; 4];
+        assert!(lead_is_significant(&clear, &weights, Z_95));
+        // Ahead on average, but not in every sample
+        let close = scores(vec![60.0, 0.0, 65.0, 10.0], vec![30.0, 45.0, 20.0, 40.0]);
+        assert!(!lead_is_significant(&close, &weights, Z_95));
+        // Unless the samples against the lead are ruled out
+        assert!(lead_is_significant(&close, &[1.0, 0.0, 1.0, 0.0], Z_95));
+        // Equivalent cards
+        let alike = scores(vec![60.0, 0.0, 65.0], vec![60.0, 0.0, 65.0]);
+        assert!(lead_is_significant(&alike, &weights[..3], Z_95));
+    }
+
+    #[test]
+    fn test_wilson_interval() {
+        use super::{wilson_interval, Z_95};
+
+        let (low, high) = wilson_interval(1.0, 20.0, Z_95);
+        assert!(low > 0.8 && low < 1.0);
+        assert_eq!(high, 1.0);
+
+        let (low, high) = wilson_interval(0.5, 20.0, Z_95);
+        assert!(low < 0.5 && high > 0.5);
+        assert!((0.5 - low - (high - 0.5)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_adaptive_sampling() {
+        use super::AdaptiveSampling;
+
+        let uproblem = sampled_game().build();
+
+        let adaptive = super::PimcSearch::new(uproblem, 10, None)
+            .with_seed(18)
+            .with_adaptive(Some(AdaptiveSampling::up_to(60)));
+        let metrics = adaptive.estimate_move_metrics(false);
+        let used = metrics[0].1.samples;
+        assert!((10..=60).contains(&used));
+        assert!(metrics.iter().all(|(_, m)| m.samples == used));
+        for (_, m) in &metrics {
+            assert!(m.points_interval.0 <= m.avg_points && m.avg_points <= m.points_interval.1);
+            assert!(m.win_interval.0 <= m.win_prob && m.win_prob <= m.win_interval.1);
+        }
+
+        // The adaptive samples are the first samples of a fixed size search
+        let fixed = super::PimcSearch::new(uproblem, used, None).with_seed(18);
+        let avg = |metrics: Vec<(u32, super::MoveMetrics)>| {
+            metrics
+                .iter()
+                .map(|(card, m)| (*card, m.avg_points))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(avg(metrics), avg(fixed.estimate_move_metrics(false)));
+    }
+
+    #[test]
+    fn test_exact_deals() {
+        let uproblem = few_deals_game().build();
+
+        // 20 deals: every deal is solved once, whatever the seed
+        let metrics = |seed: u64| {
+            super::PimcSearch::new(uproblem, 8, None)
+                .with_seed(seed)
+                .estimate_move_metrics(false)
+        };
+        let exact = metrics(1);
+        assert_eq!(exact.len(), 3);
+        for (_, m) in &exact {
+            assert!(m.exact);
+            assert_eq!(m.samples, 20);
+            assert_eq!(m.points_interval, (m.avg_points, m.avg_points));
+            assert_eq!(m.win_interval, (m.win_prob, m.win_prob));
+        }
+        let values = |metrics: &Vec<(u32, super::MoveMetrics)>| {
+            metrics
+                .iter()
+                .map(|(card, m)| (*card, m.win_prob, m.avg_points))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(&exact), values(&metrics(2)));
+
+        // Below the number of deals the samples are drawn
+        let sampled = super::PimcSearch::new(uproblem, 8, None)
+            .with_seed(1)
+            .with_exact_limit(19)
+            .estimate_move_metrics(false);
+        assert!(sampled.iter().all(|(_, m)| !m.exact && m.samples == 8));
+    }
+
+    #[test]
+    fn test_opponent_model_weights() {
+        use crate::pimc::facts::Facts;
+        use crate::pimc::opponent_model::ProbableFact;
+        use crate::traits::BitConverter;
+
+        let hj = "HJ".__bit();
+
+        // A certain fact weighs the deals against it with 0, which is
+        // the same as excluding them
+        let weighted = super::PimcSearch::new(few_deals_game().build(), 8, None)
+            .with_opponent_model(ProbableFact::void(Player::Left, hj, 1.0))
+            .estimate_move_metrics(false);
+        let excluded = super::PimcSearch::new(
+            few_deals_game()
+                .facts(Player::Left, Facts::new().lacks(hj))
+                .build(),
+            8,
+            None,
+        )
+        .estimate_move_metrics(false);
+
+        assert_eq!(weighted.len(), excluded.len());
+        for ((card, w), (other, e)) in weighted.iter().zip(&excluded) {
+            assert_eq!(card, other);
+            assert_eq!(w.samples, 20);
+            assert_eq!(e.samples, 10);
+            assert!((w.effective_samples - 10.0).abs() < 1e-4);
+            assert!((w.win_prob - e.win_prob).abs() < 1e-4);
+            assert!((w.avg_points - e.avg_points).abs() < 1e-3);
+            assert_eq!(w.min_points, e.min_points);
+        }
+    }
+
+    #[test]
+    fn test_alpha_mu() {
+        use super::Estimator;
+        use crate::traits::BitConverter;
+
+        let uproblem = PimcProblemBuilder::new_farbspiel()
+            .cards(Player::Declarer, "HA ST SQ DJ")
+            .remaining_cards("S8 HT HQ CT SK CJ DA DT")
+            .threshold(48)
+            .build();
+        let probabilities = |estimator: Estimator| {
+            let mut cards = super::PimcSearch::new(uproblem, 32, None)
+                .with_seed(1)
+                .with_exact_limit(0)
+                .with_estimator(estimator)
+                .estimate_probability_of_all_cards(false);
+            cards.sort_by_key(|(card, _)| *card);
+            cards
+        };
+
+        // With one move of the searching player αμ is PIMC
+        let pimc = probabilities(Estimator::Pimc);
+        assert_eq!(probabilities(Estimator::AlphaMu { max_moves: 1 }), pimc);
+
+        // With more it cannot promise more, as one card must do in all deals.
+        // Leading DJ, PIMC counts on guessing the rest of the tricks right.
+        let alpha_mu = probabilities(Estimator::AlphaMu { max_moves: 3 });
+        assert_eq!(alpha_mu.len(), pimc.len());
+        for ((card, p), (other, q)) in alpha_mu.iter().zip(&pimc) {
+            assert_eq!(card, other);
+            assert!(p <= q);
+        }
+        let dj = "DJ".__bit();
+        let value = |cards: &Vec<(u32, f32)>| cards.iter().find(|(card, _)| *card == dj).unwrap().1;
+        assert!(value(&alpha_mu) < value(&pimc));
+    }
 }
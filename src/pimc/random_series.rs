@@ -1,10 +1,10 @@
-use crate::consts::bitboard::{ALLCARDS, JACKOFCLUBS, JACKOFDIAMONDS, JACKOFHEARTS, JACKOFSPADES};
+use crate::consts::bitboard::ALLCARDS;
 use crate::traits::{Points, StringConverter};
 use crate::skat::counters::Counters;
 use crate::skat::defs::{Game, Player};
 use crate::skat::context::GameContext;
 use crate::extensions::skat_solving::{AccelerationMode, solve_with_skat}; 
-use crate::extensions::all_games::{calc_all_games, AllGames, CalculationError};
+use crate::extensions::all_games::{calc_all_games, AllGames};
 
 use crate::skat::engine::SkatEngine;
 
@@ -215,6 +215,7 @@ pub fn allgames(number_of_samples: usize) -> std::io::Result<()> {
                 println!("            {:4} | {:4} | {:4} | {:4} | {:4} | {:4} ","Eich","Grue","Herz","Sche","Grnd","Null");
                 println!(" Mit Skat : {:4} | {:4} | {:4} | {:4} | {:4} | {:4} ",values.eichel_farbe, values.gruen_farbe, values.herz_farbe, values.schell_farbe, values.grand, values.null);
                 println!("     Hand : {:4} | {:4} | {:4} | {:4} | {:4} | {:4} ",values.eichel_hand, values.gruen_hand, values.herz_hand, values.schell_hand, values.grand_hand, values.null_hand);
+                print_scores(&values, declarer_cards | skat);
             },
             Err(_) => todo!(),
         }
@@ -223,252 +224,21 @@ pub fn allgames(number_of_samples: usize) -> std::io::Result<()> {
     Ok(())
 }
 
-pub fn allgames_battle(number_of_samples: usize) -> std::io::Result<()> {
-    let distros = get_random_card_distros(number_of_samples);
-
-    let mut i=0;
-    let mut results: Vec<(Ply, Option<WonGame>)> = Vec::new();
-
-    for (player_a_cards, player_b_cards, player_c_cards) in distros {
-
-        i = i + 1;
-
-        println!("GAME {i} -----+-+-+-+-+-++---------------------- ");
-        
-        let start_pos_a = if i % 3 == 0 { Player::Declarer } else if i % 3 == 1 { Player::Left }     else { Player::Right };
-        let start_pos_b = if i % 3 == 0 { Player::Right }    else if i % 3 == 1 { Player::Declarer } else { Player::Left };
-        let start_pos_c = if i % 3 == 0 { Player::Left }     else if i % 3 == 1 { Player::Right }    else { Player::Declarer };
-
-        let skat = ALLCARDS ^ player_a_cards ^ player_b_cards ^ player_c_cards;        
-        
-        let res1 = calc_all_games(player_b_cards, player_c_cards, player_a_cards, start_pos_a);
-        let res2 = calc_all_games(player_c_cards, player_a_cards, player_b_cards, start_pos_b);
-        let res3 = calc_all_games(player_a_cards, player_b_cards, player_c_cards, start_pos_c);
-
-        let mut player_won: Ply = Ply::NA;        
-        let mut won_game: Option<WonGame> = None;
-        let mut best_points: u32 = 0;
-
-        println!("\nPlayer A ---------------- {} ", start_pos_a);
-        let player_a = print_scorecard(player_a_cards, player_b_cards, player_c_cards, skat, res1);
-        match player_a {
-            Some(best) => if best.points > best_points {player_won = Ply::A; won_game = Some(best); best_points = best.points;},
-            None => {},
-        }
-
-        println!("\nPlayer B ---------------- {} ", start_pos_b);
-        let player_b = print_scorecard(player_b_cards, player_c_cards, player_a_cards, skat, res2);
-        match player_b {
-            Some(best) => if best.points > best_points {player_won = Ply::B; won_game = Some(best); best_points = best.points;},
-            None => {},
-        }
-
-        println!("\nPlayer C ---------------- {} ", start_pos_c);
-        let player_c = print_scorecard(player_c_cards, player_a_cards, player_b_cards, skat, res3);
-        match player_c {
-            Some(best) => if best.points > best_points {player_won = Ply::C; won_game = Some(best); /* best_points = best.points; */},
-            None => {},
-        }
-
-        match won_game {
-            Some(game) => println!("{}: {} | {} - Hand: {}",player_won, game.points, game.game, game.hand),
-            None => println!("EINGEMISCHT"),
-        }
-
-        results.push((player_won, won_game));
-        
-    }
-
-    // Show all results
-
-
-    println!("");
-    println!(" ------------- FULL RESULTS ----------------- ");
-    println!("");
-
-    for (ply, game) in results {        
-        match game {
-            Some(game) => { 
-                let hand_string = if game.hand { "Hand" } else { "" };
-                println!("{}: {:4} | {:8} {}",ply, game.points, game.game, hand_string)
-            },
-            None => println!("EINGEMISCHT"),
-        }
-    }
-
-    Ok(())
-}
-
-fn print_scorecard(declarer_cards: u32, left_cards: u32, right_cards: u32, skat: u32, res: Result<AllGames, CalculationError>) 
--> Option<WonGame> {
-    match res {
-        Ok(values) => {
-            println!("Declarer  : {}", declarer_cards.__str());
-            println!("Left      : {}", left_cards.__str());
-            println!("Right     : {}", right_cards.__str());
-            println!("Skat      : {}", skat.__str());
-            println!("            {:4} | {:4} | {:4} | {:4} | {:4} | {:4} ","Eich","Grue","Herz","Sche","Grnd","Null");
-            println!(" Mit Skat : {:4} | {:4} | {:4} | {:4} | {:4} | {:4} ",values.eichel_farbe, values.gruen_farbe, values.herz_farbe, values.schell_farbe, values.grand, values.null);
-            println!("     Hand : {:4} | {:4} | {:4} | {:4} | {:4} | {:4} ",values.eichel_hand, values.gruen_hand, values.herz_hand, values.schell_hand, values.grand_hand, values.null_hand);
-
-            let wongames = get_wongames(values, declarer_cards);
-            println!(" WonGames: {:4}", wongames.len());
-
-            for game in &wongames {
-                println!(" {:}, {:}, {:}, {:} ",game.points, game.game, game.value, game.hand);
-            }
-
-            let best: Option<&WonGame> = max_won_game_ref(&wongames);
-
-            match best {
-                Some(best_won_game) => {
-                    println!("Reizwert: {:4} | Spiel: {:} | Wert: {:}", best_won_game.points, best_won_game.game, best_won_game.value);
-                },
-                None => {
-                    println!("Weg");
-                }
-            }
-
-            println!("end---");
-
-            return best.copied();
-        },
-        Err(_) => todo!(),        
-    }
-}
-
-fn max_won_game_ref(games: &[WonGame]) -> Option<&WonGame> {
-    games.iter().max_by_key(|wg| wg.points)
-}
-
-#[derive(Clone, Copy)]
-pub enum Ply {
-    A, B, C, NA
-}
-
-impl std::fmt::Display for Ply {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {            
-            Ply::A => write!(f, " [A] "),
-            Ply::B => write!(f, " [B] "),
-            Ply::C => write!(f, " [C] "),
-            Ply::NA => write!(f, " [NA] "),            
-        }
-    }
-}
-
-#[derive(Clone, Copy)]
-pub struct WonGame {
-    value: u8,
-    points: u32,
-    game: WonGameType,
-    hand: bool
-}
-
-#[derive(Clone, Copy)]
-pub enum WonGameType {
-    Eichel,
-    Gruen,
-    Herz,
-    Schell,
-    Grand,
-    Null,
-    NA
-}
-
-impl std::fmt::Display for WonGameType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            WonGameType::Eichel => write!(f, "Eichel"),
-            WonGameType::Gruen => write!(f, "Gruen"),
-            WonGameType::Herz => write!(f, "Herz"),
-            WonGameType::Schell => write!(f, "Schell"),
-            WonGameType::Grand => write!(f, "Grand"),
-            WonGameType::Null => write!(f, "Null"),
-            WonGameType::NA => write!(f, "N/A"),
-        }
-    }
-}
-
-fn get_wongames(values: AllGames, cards: u32) -> Vec<WonGame> {
-
-    let mut ret: Vec<WonGame> = Vec::new();
-
-    let games = [
-        (values.eichel_farbe, 12, WonGameType::Eichel, false),
-        (values.gruen_farbe, 11, WonGameType::Gruen, false),
-        (values.herz_farbe, 10, WonGameType::Herz, false),
-        (values.schell_farbe, 9,  WonGameType::Schell, false),
-        (values.grand, 24,  WonGameType::Grand, false),
-        (values.eichel_hand, 12, WonGameType::Eichel, true),
-        (values.gruen_hand, 11, WonGameType::Gruen, true),
-        (values.herz_hand, 10, WonGameType::Herz, true),
-        (values.schell_hand, 9,  WonGameType::Schell, true),
-        (values.grand_hand, 24,  WonGameType::Grand, true),        
-    ];
-    
-    for &(value, multiplier, game, hand) in &games {
-        // nur weiter, wenn > 60
-        if value <= 60 {
-            continue;
-        }
-    
-        // Basis‑Faktor
-        let mut factor = get_factor(cards) + 1;
-    
-        // Schneider‑Bonus
-        if value > 89 {
-            factor += 1;
-        }
-
-        // Hand-Bonus
-        if hand {
-            factor += 1;
-        }
-    
-        // Punkte berechnen und pushen
-        let points = factor * multiplier;
-        ret.push(WonGame { 
-            value, 
-            points, 
-            game, 
-            hand 
-        });
-    }
-
-    if values.null == 0 {
-        ret.push(WonGame { value: 0, points: 23, game: WonGameType::Null, hand: false });
-    }
-
-    if values.null_hand == 0 {
-        ret.push(WonGame { value: 0, points: 35, game: WonGameType::Null, hand: true });
+/// Prints the settled scores (won: game value, lost: minus twice the value).
+fn print_scores(values: &AllGames, declarer_cards_with_skat: u32) {
+    let scores = values.scores(declarer_cards_with_skat, 0);
+    let row = |hand: bool| -> Vec<i32> {
+        scores
+            .iter()
+            .filter(|score| score.hand == hand)
+            .map(|score| score.settlement.score)
+            .collect()
+    };
+    for (label, r) in [("Score Skat", row(false)), ("Score Hand", row(true))] {
+        println!("{:>10}: {:4} | {:4} | {:4} | {:4} | {:4} | {:4} ", label, r[0], r[1], r[2], r[3], r[4], r[5]);
     }
-
-    ret
-}
-
-const JACK_MASKS: [u32; 4] = [
-    JACKOFCLUBS,
-    JACKOFSPADES,
-    JACKOFHEARTS,
-    JACKOFDIAMONDS,
-];
-
-    
-fn get_factor(cards: u32) -> u32 {
-    // Ist der Clubs‑Bube gesetzt?
-    let first_present = cards & JACKOFCLUBS != 0;
-
-    // Zähle, wie viele Jacks von links anfangen,
-    // bei denen (gesetzt? == first_present)
-    // true liefern, und stoppe beim ersten Wechsel.
-    JACK_MASKS
-        .iter()
-        .take_while(|&&mask| (cards & mask != 0) == first_present)
-        .count() as u32
 }
 
-
 fn get_random_card_distros(number_of_distros: usize) -> Vec<(u32, u32, u32)> {
     let mut rand = StdRng::seed_from_u64(223);
     let mut ret = Vec::<(u32, u32, u32)>::new();
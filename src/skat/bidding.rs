@@ -0,0 +1,471 @@
+//! # Bidding
+//!
+//! The auction ("Reizen") before the card play: the ladder of legal bid
+//! values, the seat roles and the hold/pass protocol. Middlehand bids to
+//! Forehand first, the survivor then listens to Rearhand. If both of them
+//! pass without a bid, Forehand may still open at 18 or pass the game in.
+//!
+//! `recommend_max_bid` turns win probabilities per contract into a bidding
+//! limit; the probabilities themselves come from `pimc::analysis::analyze_bid`.
+
+use crate::skat::defs::Game;
+use crate::skat::scoring::{base_game_value, Contract};
+
+// -----------------------------------------------------------------------------
+// BID LADDER
+// -----------------------------------------------------------------------------
+
+pub const MIN_BID: u16 = 18;
+
+/// Highest reachable game value: Grand with 4 matadors, Ouvert (11 x 24).
+pub const MAX_BID: u16 = 264;
+
+/// All legal bid values in ascending order (18, 20, 22, 23, 24, 27, ...).
+///
+/// A value is legal if some game can be worth exactly that much: a multiple
+/// of a Suit or Grand base value, or one of the fixed Null values.
+pub fn bid_values() -> Vec<u16> {
+    let mut values = Vec::new();
+
+    // Suit games: up to 11 matadors, game, Hand, Schneider, announced,
+    // Schwarz, announced, Ouvert
+    for base in [9u16, 10, 11, 12].iter() {
+        for multiplier in 2..=18 {
+            values.push(base * multiplier);
+        }
+    }
+    // Grand: up to 4 matadors plus the same levels
+    for multiplier in 2..=11 {
+        values.push(24 * multiplier);
+    }
+    for null in [
+        Game::Null,
+        Game::NullHand,
+        Game::NullOuvert,
+        Game::NullOuvertHand,
+    ]
+    .iter()
+    {
+        values.extend(null.null_value());
+    }
+
+    values.retain(|v| *v >= MIN_BID);
+    values.sort_unstable();
+    values.dedup();
+    values
+}
+
+pub fn is_valid_bid(value: u16) -> bool {
+    bid_values().contains(&value)
+}
+
+/// The next legal bid above `current` (18 if nothing has been bid yet).
+pub fn next_bid(current: u16) -> Option<u16> {
+    bid_values().into_iter().find(|v| *v > current)
+}
+
+/// The highest legal bid not exceeding `value`.
+pub fn max_bid_for_value(value: u16) -> Option<u16> {
+    bid_values().into_iter().take_while(|v| *v <= value).last()
+}
+
+// -----------------------------------------------------------------------------
+// AUCTION
+// -----------------------------------------------------------------------------
+
+/// Seat roles relative to the dealer: Forehand (Vorhand) is served first and
+/// leads the first trick, Middlehand (Mittelhand) and Rearhand (Hinterhand)
+/// follow clockwise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Seat {
+    Forehand,
+    Middlehand,
+    Rearhand,
+}
+
+impl Seat {
+    pub fn inc(&self) -> Seat {
+        match self {
+            Seat::Forehand => Seat::Middlehand,
+            Seat::Middlehand => Seat::Rearhand,
+            Seat::Rearhand => Seat::Forehand,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        match self {
+            Seat::Forehand => 0,
+            Seat::Middlehand => 1,
+            Seat::Rearhand => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuctionAction {
+    /// Name a value (only the bidding seat).
+    Bid(u16),
+    /// Accept the last bid (only the listening seat).
+    Hold,
+    Pass,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuctionOutcome {
+    Declarer {
+        seat: Seat,
+        bid: u16,
+    },
+    /// All three players passed.
+    PassedIn,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AuctionPhase {
+    /// `bidder` names values to `listener`, who holds or passes.
+    Bidding {
+        bidder: Seat,
+        listener: Seat,
+    },
+    /// `bidder` has just named a value, `listener` has to answer.
+    Answering {
+        bidder: Seat,
+        listener: Seat,
+    },
+    /// Middlehand and Rearhand passed without a bid: Forehand may open.
+    ForehandOpens,
+    Finished(AuctionOutcome),
+}
+
+/// State machine of one auction. Feed actions with `apply` until
+/// `outcome` returns a result.
+#[derive(Clone, Copy, Debug)]
+pub struct Auction {
+    bid: u16,
+    phase: AuctionPhase,
+}
+
+impl Default for Auction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Auction {
+    pub fn new() -> Auction {
+        Auction {
+            bid: 0,
+            phase: AuctionPhase::Bidding {
+                bidder: Seat::Middlehand,
+                listener: Seat::Forehand,
+            },
+        }
+    }
+
+    /// Highest value bid and held so far (0 before the first bid).
+    pub fn current_bid(&self) -> u16 {
+        self.bid
+    }
+
+    /// The seat whose action is expected next, `None` once finished.
+    pub fn to_act(&self) -> Option<Seat> {
+        match self.phase {
+            AuctionPhase::Bidding { bidder, .. } => Some(bidder),
+            AuctionPhase::Answering { listener, .. } => Some(listener),
+            AuctionPhase::ForehandOpens => Some(Seat::Forehand),
+            AuctionPhase::Finished(_) => None,
+        }
+    }
+
+    /// True if the seat to act names values, false if it answers a bid.
+    pub fn is_bidding(&self) -> bool {
+        matches!(
+            self.phase,
+            AuctionPhase::Bidding { .. } | AuctionPhase::ForehandOpens
+        )
+    }
+
+    pub fn outcome(&self) -> Option<AuctionOutcome> {
+        match self.phase {
+            AuctionPhase::Finished(outcome) => Some(outcome),
+            _ => None,
+        }
+    }
+
+    pub fn apply(&mut self, seat: Seat, action: AuctionAction) -> Result<(), &'static str> {
+        if self.to_act() != Some(seat) {
+            return Err("Seat is not on turn");
+        }
+
+        self.phase = match (self.phase, action) {
+            (AuctionPhase::Bidding { bidder, listener }, AuctionAction::Bid(value)) => {
+                self.raise(value)?;
+                AuctionPhase::Answering { bidder, listener }
+            }
+            (AuctionPhase::Bidding { listener, .. }, AuctionAction::Pass) => {
+                self.next_round(listener)
+            }
+            (AuctionPhase::Answering { bidder, listener }, AuctionAction::Hold) => {
+                AuctionPhase::Bidding { bidder, listener }
+            }
+            (AuctionPhase::Answering { bidder, .. }, AuctionAction::Pass) => {
+                self.next_round(bidder)
+            }
+            (AuctionPhase::ForehandOpens, AuctionAction::Bid(value)) => {
+                self.raise(value)?;
+                AuctionPhase::Finished(AuctionOutcome::Declarer {
+                    seat: Seat::Forehand,
+                    bid: value,
+                })
+            }
+            (AuctionPhase::ForehandOpens, AuctionAction::Pass) => {
+                AuctionPhase::Finished(AuctionOutcome::PassedIn)
+            }
+            (AuctionPhase::Finished(_), _) => return Err("Auction is finished"),
+            (_, AuctionAction::Hold) => return Err("Nothing to hold"),
+            (AuctionPhase::Answering { .. }, AuctionAction::Bid(_)) => {
+                return Err("Listener can only hold or pass")
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Runs a complete auction in which every seat bids or holds up to its
+    /// limit (0 = pass immediately).
+    pub fn run(limits: [u16; 3]) -> AuctionOutcome {
+        let mut auction = Auction::new();
+
+        while let Some(seat) = auction.to_act() {
+            let limit = limits[seat.index()];
+            let action = if auction.is_bidding() {
+                match next_bid(auction.bid) {
+                    Some(value) if value <= limit => AuctionAction::Bid(value),
+                    _ => AuctionAction::Pass,
+                }
+            } else if auction.bid <= limit {
+                AuctionAction::Hold
+            } else {
+                AuctionAction::Pass
+            };
+            auction
+                .apply(seat, action)
+                .expect("Generated auction action is legal");
+        }
+
+        auction.outcome().unwrap()
+    }
+
+    fn raise(&mut self, value: u16) -> Result<(), &'static str> {
+        if value <= self.bid || !is_valid_bid(value) {
+            return Err("Invalid bid value");
+        }
+        self.bid = value;
+        Ok(())
+    }
+
+    /// Starts the next round after one seat passed; `winner` is the seat that
+    /// stayed in.
+    fn next_round(&self, winner: Seat) -> AuctionPhase {
+        match winner {
+            // First round: Middlehand against Forehand, Rearhand still to bid
+            _ if !self.rearhand_started() => AuctionPhase::Bidding {
+                bidder: Seat::Rearhand,
+                listener: winner,
+            },
+            _ if self.bid == 0 => AuctionPhase::ForehandOpens,
+            _ => AuctionPhase::Finished(AuctionOutcome::Declarer {
+                seat: winner,
+                bid: self.bid,
+            }),
+        }
+    }
+
+    fn rearhand_started(&self) -> bool {
+        match self.phase {
+            AuctionPhase::Bidding { bidder, listener }
+            | AuctionPhase::Answering { bidder, listener } => {
+                bidder == Seat::Rearhand || listener == Seat::Rearhand
+            }
+            _ => true,
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// BID ADVICE
+// -----------------------------------------------------------------------------
+
+/// Win probability of one contract for a 10-card hand.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContractEstimate {
+    pub contract: Contract,
+    pub win_probability: f32,
+    /// Game value if won without extra levels (matadors from the 10 cards).
+    pub game_value: u16,
+}
+
+impl ContractEstimate {
+    pub fn new(contract: Contract, hand: u32, win_probability: f32) -> ContractEstimate {
+        ContractEstimate {
+            contract,
+            win_probability,
+            game_value: base_game_value(&contract, hand),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BidAdvice {
+    /// Highest value to bid or hold, 0 if the hand should pass.
+    pub max_bid: u16,
+    /// The contract backing `max_bid`.
+    pub contract: Option<ContractEstimate>,
+    pub estimates: Vec<ContractEstimate>,
+}
+
+/// Bids up to the most valuable contract that is won with at least
+/// `min_probability`. Ties in value go to the safer contract.
+pub fn recommend_max_bid(estimates: Vec<ContractEstimate>, min_probability: f32) -> BidAdvice {
+    let contract = estimates
+        .iter()
+        .filter(|e| e.win_probability >= min_probability)
+        .max_by(|a, b| {
+            a.game_value.cmp(&b.game_value).then_with(|| {
+                a.win_probability
+                    .partial_cmp(&b.win_probability)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+        })
+        .copied();
+
+    let max_bid = contract
+        .and_then(|c| max_bid_for_value(c.game_value))
+        .unwrap_or(0);
+
+    BidAdvice {
+        max_bid,
+        contract,
+        estimates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::bitboard::*;
+
+    #[test]
+    fn test_bid_ladder() {
+        let values = bid_values();
+        assert_eq!(
+            values[0..12],
+            [18, 20, 22, 23, 24, 27, 30, 33, 35, 36, 40, 44]
+        );
+        assert_eq!(*values.last().unwrap(), MAX_BID);
+        assert_eq!(next_bid(0), Some(18));
+        assert_eq!(next_bid(24), Some(27));
+        assert_eq!(next_bid(MAX_BID), None);
+        assert!(!is_valid_bid(19));
+        assert!(is_valid_bid(59));
+    }
+
+    #[test]
+    fn test_auction_protocol() {
+        let mut auction = Auction::new();
+        assert_eq!(auction.to_act(), Some(Seat::Middlehand));
+        auction
+            .apply(Seat::Middlehand, AuctionAction::Bid(18))
+            .unwrap();
+        auction.apply(Seat::Forehand, AuctionAction::Hold).unwrap();
+        auction
+            .apply(Seat::Middlehand, AuctionAction::Bid(20))
+            .unwrap();
+        auction.apply(Seat::Forehand, AuctionAction::Pass).unwrap();
+
+        // Rearhand continues against Middlehand
+        assert_eq!(auction.to_act(), Some(Seat::Rearhand));
+        assert!(auction
+            .apply(Seat::Rearhand, AuctionAction::Bid(20))
+            .is_err());
+        auction
+            .apply(Seat::Rearhand, AuctionAction::Bid(22))
+            .unwrap();
+        assert!(auction.apply(Seat::Rearhand, AuctionAction::Hold).is_err());
+        auction
+            .apply(Seat::Middlehand, AuctionAction::Pass)
+            .unwrap();
+
+        assert_eq!(
+            auction.outcome(),
+            Some(AuctionOutcome::Declarer {
+                seat: Seat::Rearhand,
+                bid: 22
+            })
+        );
+        assert!(auction.apply(Seat::Forehand, AuctionAction::Pass).is_err());
+    }
+
+    #[test]
+    fn test_forehand_opens_or_passes_in() {
+        assert_eq!(Auction::run([0, 0, 0]), AuctionOutcome::PassedIn);
+        assert_eq!(
+            Auction::run([18, 0, 0]),
+            AuctionOutcome::Declarer {
+                seat: Seat::Forehand,
+                bid: 18
+            }
+        );
+    }
+
+    #[test]
+    fn test_run_auction_with_limits() {
+        // Forehand holds up to 30, Middlehand gives up, Rearhand outbids
+        assert_eq!(
+            Auction::run([30, 24, 36]),
+            AuctionOutcome::Declarer {
+                seat: Seat::Rearhand,
+                bid: 33
+            }
+        );
+        // Forehand wins ties as listener
+        assert_eq!(
+            Auction::run([24, 24, 0]),
+            AuctionOutcome::Declarer {
+                seat: Seat::Forehand,
+                bid: 24
+            }
+        );
+    }
+
+    #[test]
+    fn test_recommend_max_bid() {
+        let hand = JACKOFCLUBS
+            | JACKOFSPADES
+            | ACEOFCLUBS
+            | TENOFCLUBS
+            | KINGOFCLUBS
+            | ACEOFSPADES
+            | TENOFSPADES
+            | ACEOFHEARTS
+            | SEVENOFDIAMONDS
+            | EIGHTOFDIAMONDS;
+
+        let estimates = vec![
+            ContractEstimate::new(Contract::grand(), hand, 0.4),
+            ContractEstimate::new(Contract::suit(CLUBS), hand, 0.9),
+            ContractEstimate::new(Contract::new(Game::Null), hand, 0.0),
+        ];
+        let advice = recommend_max_bid(estimates.clone(), 0.6);
+        // Clubs with 2: 3 x 12
+        assert_eq!(advice.max_bid, 36);
+        assert_eq!(advice.contract.unwrap().contract, Contract::suit(CLUBS));
+
+        let advice = recommend_max_bid(estimates.clone(), 0.3);
+        assert_eq!(advice.max_bid, 72);
+
+        let advice = recommend_max_bid(estimates, 0.95);
+        assert_eq!(advice.max_bid, 0);
+        assert!(advice.contract.is_none());
+    }
+}
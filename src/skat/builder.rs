@@ -1,11 +1,28 @@
 use crate::skat::context::GameContext;
+use crate::skat::scoring::Doubling;
 use crate::{
     pimc::facts::Facts,
     skat::defs::*,
     skat::rules::{get_all_unplayed_cards, get_suit_for_card},
     traits::{BitConverter, Bitboard, Points},
 };
+use rand::rngs::StdRng;
 use rand::seq::index::sample;
+use rand::{Rng, SeedableRng};
+
+/// Hidden hands of the two players besides me, see
+/// `GameContextBuilder::hidden_hands`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HiddenHands {
+    /// Cards the next player can hold in hand.
+    pub next_pool: u32,
+    /// Cards the previous player can hold in hand.
+    pub previous_pool: u32,
+    /// Number of cards in the next player's hand (without a card on the table).
+    pub next_count: u32,
+    /// Number of cards in the previous player's hand.
+    pub previous_count: u32,
+}
 
 pub struct GameContextBuilder {
     declarer_cards: Option<u32>,
@@ -18,6 +35,9 @@ pub struct GameContextBuilder {
     trick_suit: Option<u32>,
     declarer_start_points: Option<u8>,
     played_cards: Option<u32>,
+    doubling: Option<Doubling>,
+    open_cards: Option<(Player, u32)>,
+    seed: Option<u64>,
 }
 
 impl GameContextBuilder {
@@ -74,6 +94,21 @@ impl GameContextBuilder {
         self
     }
 
+    /// Seed of the random cards drawn by `set_cards_for_other_players` (which
+    /// has to come later in the chain); without a seed they are drawn from
+    /// the thread's random generator.
+    pub fn seed(mut self, seed: u64) -> GameContextBuilder {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Cards laid open by `player` (Null Ouvert). When sampling the hidden
+    /// hands, that player keeps exactly the open cards that are still in play.
+    pub fn open_cards(mut self, player: Player, cards: u32) -> GameContextBuilder {
+        self.open_cards = Some((player, cards));
+        self
+    }
+
     pub fn declarer_start_points(mut self, points: u8) -> GameContextBuilder {
         self.declarer_start_points = Some(points);
         self
@@ -131,6 +166,12 @@ impl GameContextBuilder {
         self
     }
 
+    /// Kontra/Re said so far.
+    pub fn doubling(mut self, doubling: Doubling) -> GameContextBuilder {
+        self.doubling = Some(doubling);
+        self
+    }
+
     pub fn build(self) -> GameContext {
         self.validate();
 
@@ -183,6 +224,10 @@ impl GameContextBuilder {
         if let Some(played_cards) = self.played_cards {
             context.set_played_cards(played_cards);
         }
+
+        if let Some(doubling) = self.doubling {
+            context.set_doubling(doubling);
+        }
         // But ProblemBuilder used setters.
 
         context
@@ -242,6 +287,7 @@ impl GameContextBuilder {
         self
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn set_cards_for_other_players(
         mut self,
         all_cards: u32,
@@ -252,6 +298,47 @@ impl GameContextBuilder {
         next_player_facts: Facts,
         previous_player_facts: Facts,
     ) -> GameContextBuilder {
+        let hidden = self.hidden_hands(
+            all_cards,
+            card_on_table_previous_player,
+            card_on_table_next_player,
+            my_cards,
+            my_player,
+            next_player_facts,
+            previous_player_facts,
+        );
+
+        let proposed_draw = self.draw_cards(
+            hidden.next_pool,
+            hidden.previous_pool,
+            my_cards,
+            hidden.next_count,
+            hidden.previous_count,
+        );
+
+        self.set_hands_for_other_players(
+            proposed_draw.0,
+            proposed_draw.1,
+            card_on_table_previous_player,
+            card_on_table_next_player,
+            my_player,
+        )
+    }
+
+    /// Cards the two other players can hold in hand, consistent with the
+    /// facts, the open cards and the cards on the table, and how many they
+    /// hold. `set_cards_for_other_players` draws from these pools.
+    #[allow(clippy::too_many_arguments)]
+    pub fn hidden_hands(
+        &self,
+        all_cards: u32,
+        card_on_table_previous_player: u32,
+        card_on_table_next_player: u32,
+        my_cards: u32,
+        my_player: Player,
+        next_player_facts: Facts,
+        previous_player_facts: Facts,
+    ) -> HiddenHands {
         let cards_on_hands_of_both_other_players =
             (all_cards & !my_cards) & !card_on_table_previous_player & !card_on_table_next_player;
 
@@ -267,6 +354,21 @@ impl GameContextBuilder {
 
         cards_previous_player = cards_previous_player & !card_on_table_next_player;
 
+        // Cards known in one hand are not in the other one
+        cards_next_player &= !previous_player_facts.has_cards;
+        cards_previous_player &= !next_player_facts.has_cards;
+
+        if let Some((open_player, open_cards)) = self.open_cards {
+            let open_in_play = open_cards & cards_on_hands_of_both_other_players;
+            if open_player == my_player.inc() {
+                cards_next_player = open_in_play;
+                cards_previous_player &= !open_in_play;
+            } else if open_player == my_player.dec() {
+                cards_previous_player = open_in_play;
+                cards_next_player &= !open_in_play;
+            }
+        }
+
         let my_cards_count = my_cards.count_ones();
 
         let target_next = my_cards_count - if card_on_table_next_player != 0 { 1 } else { 0 };
@@ -284,20 +386,29 @@ impl GameContextBuilder {
             cards_previous_player = 0;
         }
 
-        let proposed_draw = self.draw_cards(
-            cards_next_player,
-            cards_previous_player,
-            my_cards,
-            target_next,
-            target_prev,
-        );
-
-        cards_next_player = proposed_draw.0 | card_on_table_next_player;
-        cards_previous_player = proposed_draw.1 | card_on_table_previous_player;
-
-        self.set_cards_for_problem_core(cards_next_player, my_player.inc());
-        self.set_cards_for_problem_core(cards_previous_player, my_player.dec());
+        HiddenHands {
+            next_pool: cards_next_player,
+            previous_pool: cards_previous_player,
+            next_count: target_next,
+            previous_count: target_prev,
+        }
+    }
 
+    /// Sets the hands of the two other players: the given cards in hand plus
+    /// their cards on the table.
+    pub fn set_hands_for_other_players(
+        mut self,
+        next_hand: u32,
+        previous_hand: u32,
+        card_on_table_previous_player: u32,
+        card_on_table_next_player: u32,
+        my_player: Player,
+    ) -> GameContextBuilder {
+        self.set_cards_for_problem_core(next_hand | card_on_table_next_player, my_player.inc());
+        self.set_cards_for_problem_core(
+            previous_hand | card_on_table_previous_player,
+            my_player.dec(),
+        );
         self
     }
 
@@ -348,10 +459,26 @@ impl GameContextBuilder {
         let remaining_after_p1 = nr_ambiguous_cards.saturating_sub(available_for_p1);
         let available_for_p2 = remaining_after_p1.min(needed_for_p2);
 
-        let draw_player_1 = random_cards(ambiguous_cards, available_for_p1);
+        let seed = self.seed.unwrap_or_else(|| rand::thread_rng().gen());
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let draw_player_1 = random_cards(&mut rng, ambiguous_cards, available_for_p1);
         let remaining_ambiguous = ambiguous_cards & !draw_player_1;
 
-        let draw_player_2 = random_cards(remaining_ambiguous, available_for_p2);
+        let draw_player_2 = random_cards(&mut rng, remaining_ambiguous, available_for_p2);
+
+        // A player may own more definite cards than the target, e.g. when the
+        // other hand is fully known and the Skat remains in the pool.
+        let definite_cards_player_1 = if nr_definite_cards_player_1 > target_p1 {
+            random_cards(&mut rng, definite_cards_player_1, target_p1)
+        } else {
+            definite_cards_player_1
+        };
+        let definite_cards_player_2 = if nr_definite_cards_player_2 > target_p2 {
+            random_cards(&mut rng, definite_cards_player_2, target_p2)
+        } else {
+            definite_cards_player_2
+        };
 
         // If we couldn't draw enough from the ambiguous pool, fill from the unconstrained
         // remaining cards (those not in either player's constrained pool but in all_cards).
@@ -376,17 +503,18 @@ impl Default for GameContextBuilder {
             trick_suit: Some(0),
             declarer_start_points: Some(0),
             played_cards: Some(0),
+            doubling: None,
+            open_cards: None,
+            seed: None,
         }
     }
 }
 
-fn random_cards(cards: u32, nr: u32) -> u32 {
-    let mut random_number_generator = rand::thread_rng();
-
+fn random_cards(rng: &mut StdRng, cards: u32, nr: u32) -> u32 {
     let cards_dec = cards.__decompose();
     assert!(cards_dec.1 >= nr as usize);
 
-    let indices = sample(&mut random_number_generator, cards_dec.1, nr as usize);
+    let indices = sample(rng, cards_dec.1, nr as usize);
 
     let mut ret = 0;
     for i in indices.iter() {
@@ -396,48 +524,50 @@ fn random_cards(cards: u32, nr: u32) -> u32 {
     ret
 }
 
-fn cancel_cards_with_facts(cards: u32, facts: Facts, game: Game) -> u32 {
+/// Removes the cards `facts` exclude from `cards`: the suit voids and the
+/// cards known not to be held.
+pub fn cancel_cards_with_facts(cards: u32, facts: Facts, game: Game) -> u32 {
     let mut ret_cards = cards;
 
     if facts.no_trump {
         ret_cards = match game {
             Game::Suit => ret_cards & !TRUMP_SUIT,
-            Game::Grand => ret_cards & !TRUMP_GRAND,
-            Game::Null => ret_cards & !TRUMP_NULL,
+            Game::Grand | Game::Ramsch => ret_cards & !TRUMP_GRAND,
+            _ => ret_cards & !TRUMP_NULL,
         }
     }
 
     if facts.no_clubs {
         ret_cards = match game {
             Game::Suit => ret_cards & !TRUMP_SUIT,
-            Game::Grand => ret_cards & !CLUBS,
-            Game::Null => ret_cards & !NULL_CLUBS,
+            Game::Grand | Game::Ramsch => ret_cards & !CLUBS,
+            _ => ret_cards & !NULL_CLUBS,
         }
     }
 
     if facts.no_spades {
         ret_cards = match game {
             Game::Suit => ret_cards & !SPADES,
-            Game::Grand => ret_cards & !SPADES,
-            Game::Null => ret_cards & !NULL_SPADES,
+            Game::Grand | Game::Ramsch => ret_cards & !SPADES,
+            _ => ret_cards & !NULL_SPADES,
         }
     }
 
     if facts.no_hearts {
         ret_cards = match game {
             Game::Suit => ret_cards & !HEARTS,
-            Game::Grand => ret_cards & !HEARTS,
-            Game::Null => ret_cards & !NULL_HEARTS,
+            Game::Grand | Game::Ramsch => ret_cards & !HEARTS,
+            _ => ret_cards & !NULL_HEARTS,
         }
     }
 
     if facts.no_diamonds {
         ret_cards = match game {
             Game::Suit => ret_cards & !DIAMONDS,
-            Game::Grand => ret_cards & !DIAMONDS,
-            Game::Null => ret_cards & !NULL_DIAMONDS,
+            Game::Grand | Game::Ramsch => ret_cards & !DIAMONDS,
+            _ => ret_cards & !NULL_DIAMONDS,
         }
     }
 
-    ret_cards
+    ret_cards & !facts.no_cards
 }
@@ -9,6 +9,7 @@ use crate::skat::defs::{
 };
 use crate::skat::position::Position;
 use crate::skat::rules::get_suit_for_card;
+use crate::skat::scoring::Doubling;
 use crate::traits::Bitboard; // Need to ensure traits are available or moved
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -30,6 +31,8 @@ pub struct GameContext {
     pub trick_suit: u32,
     pub declarer_start_points: u8,
     pub played_cards: u32,
+    /// Kontra/Re said so far.
+    pub doubling: Doubling,
 }
 
 impl GameContext {
@@ -40,7 +43,7 @@ impl GameContext {
         game_type: Game,
         start_player: Player,
     ) -> Self {
-        let threshold_upper = if game_type == Game::Null { 1 } else { 61 };
+        let threshold_upper = if game_type.is_null() { 1 } else { 61 };
         Self {
             declarer_cards,
             left_cards,
@@ -52,6 +55,7 @@ impl GameContext {
             trick_suit: 0,
             declarer_start_points: 0,
             played_cards: 0,
+            doubling: Doubling::None,
         }
     }
 
@@ -68,6 +72,7 @@ impl GameContext {
             trick_suit: 0,
             declarer_start_points: 0,
             played_cards: 0,
+            doubling: Doubling::None,
         }
     }
 
@@ -111,6 +116,10 @@ impl GameContext {
         self.played_cards = played_cards;
     }
 
+    pub fn set_doubling(&mut self, doubling: Doubling) {
+        self.doubling = doubling;
+    }
+
     // Getters
 
     pub fn declarer_cards(&self) -> u32 {
@@ -149,6 +158,10 @@ impl GameContext {
         self.played_cards
     }
 
+    pub fn doubling(&self) -> Doubling {
+        self.doubling
+    }
+
     // Derived logic
 
     pub fn total_points(&self) -> u8 {
@@ -188,6 +201,7 @@ impl GameContext {
             trick_suit: p.trick_suit,
             declarer_start_points: p.declarer_start_points,
             played_cards: switched_played_cards,
+            doubling: p.doubling,
         }
     }
 
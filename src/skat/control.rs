@@ -0,0 +1,173 @@
+//! # Search Control
+//!
+//! Limits of a search: a node budget, a deadline and a cancel token. The
+//! alpha-beta searches poll the control every `POLL_INTERVAL` nodes; once a
+//! limit is hit the control stays stopped and all searches using it return
+//! without writing further transposition table entries. Values returned by an
+//! interrupted search are meaningless, see `SkatEngine::search_anytime` for
+//! the best card known so far.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Nodes between two polls of the limits (per search thread). A node budget
+/// may be overrun by this many nodes per thread.
+pub const POLL_INTERVAL: u32 = 1024;
+
+/// Shared flag to cancel a search from another thread.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SearchControl {
+    max_nodes: Option<u64>,
+    /// Milliseconds of `now_millis`.
+    deadline: Option<f64>,
+    cancel: Option<CancelToken>,
+    nodes: AtomicU64,
+    stopped: AtomicBool,
+}
+
+impl SearchControl {
+    /// Control without limits, the search always completes.
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Stops after about `nodes` searched nodes.
+    pub fn with_node_budget(mut self, nodes: u64) -> Self {
+        self.max_nodes = Some(nodes);
+        self
+    }
+
+    /// Stops once `limit` has passed from now on.
+    pub fn with_time_limit(mut self, limit: Duration) -> Self {
+        self.deadline = Some(now_millis() + limit.as_secs_f64() * 1000.0);
+        self
+    }
+
+    /// Stops once `token` is cancelled.
+    pub fn with_cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    pub fn is_limited(&self) -> bool {
+        self.max_nodes.is_some() || self.deadline.is_some() || self.cancel.is_some()
+    }
+
+    /// Nodes counted by the polls so far (a multiple of `POLL_INTERVAL`).
+    pub fn nodes(&self) -> u64 {
+        self.nodes.load(Ordering::Relaxed)
+    }
+
+    /// True once a limit was hit.
+    #[inline(always)]
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    /// Called by the searches for every node, `iters` being the node count of
+    /// the calling search thread. Returns true if the search has to stop.
+    #[inline(always)]
+    pub fn should_stop(&self, iters: u32) -> bool {
+        if iters.is_multiple_of(POLL_INTERVAL) && self.is_limited() {
+            return self.poll(POLL_INTERVAL as u64);
+        }
+        self.is_stopped()
+    }
+
+    /// Checks the limits outside of a search, e.g. between iterations.
+    /// Returns true if the search has to stop.
+    pub fn check(&self) -> bool {
+        self.is_limited() && self.poll(0)
+    }
+
+    fn poll(&self, nodes: u64) -> bool {
+        if self.is_stopped() {
+            return true;
+        }
+
+        let searched = self.nodes.fetch_add(nodes, Ordering::Relaxed) + nodes;
+        let out_of_nodes = self.max_nodes.is_some_and(|max| searched >= max);
+        let out_of_time = self
+            .deadline
+            .is_some_and(|deadline| now_millis() >= deadline);
+        let cancelled = self.cancel.as_ref().is_some_and(CancelToken::is_cancelled);
+
+        let stop = out_of_nodes || out_of_time || cancelled;
+
+        if stop {
+            self.stopped.store(true, Ordering::Relaxed);
+        }
+        stop
+    }
+}
+
+/// Monotonic milliseconds. `std::time::Instant` is not available in the
+/// browser, there the clock of JavaScript is used.
+#[cfg(not(target_arch = "wasm32"))]
+fn now_millis() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
+
+#[cfg(target_arch = "wasm32")]
+fn now_millis() -> f64 {
+    js_date::now()
+}
+
+#[cfg(target_arch = "wasm32")]
+mod js_date {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = Date)]
+        pub fn now() -> f64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_budget_and_cancel() {
+        let control = SearchControl::unlimited().with_node_budget(2 * POLL_INTERVAL as u64);
+        assert!(!control.should_stop(POLL_INTERVAL));
+        assert!(!control.should_stop(POLL_INTERVAL + 1));
+        assert!(control.should_stop(2 * POLL_INTERVAL));
+        // Stays stopped
+        assert!(control.should_stop(2 * POLL_INTERVAL + 1));
+
+        let token = CancelToken::new();
+        let control = SearchControl::unlimited().with_cancel_token(token.clone());
+        assert!(!control.should_stop(POLL_INTERVAL));
+        token.cancel();
+        assert!(!control.should_stop(POLL_INTERVAL + 1));
+        assert!(control.should_stop(2 * POLL_INTERVAL));
+
+        let control = SearchControl::unlimited();
+        assert!(!control.should_stop(POLL_INTERVAL));
+        assert_eq!(control.nodes(), 0);
+    }
+}
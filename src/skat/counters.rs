@@ -8,8 +8,16 @@ pub struct Counters {
     pub reads: u32,
     pub breaks: u32,
     pub collisions: u32,
+    pub misses: u32,
     pub exactreads: u32,
     pub iters: u32,
+    /// Cutoffs caused by the first searched move (well ordered nodes).
+    pub first_move_breaks: u32,
+    /// Cutoffs caused by a killer move, see `MoveOrdering::Dynamic`.
+    pub killer_breaks: u32,
+    /// Endgame positions answered by, and added to, the endgame table.
+    pub endgame_reads: u32,
+    pub endgame_writes: u32,
 }
 
 impl Counters {
@@ -33,6 +41,10 @@ impl Counters {
         self.collisions += 1;
     }
 
+    pub fn inc_misses(&mut self) {
+        self.misses += 1;
+    }
+
     pub fn inc_exactreads(&mut self) {
         self.exactreads += 1;
     }
@@ -41,6 +53,22 @@ impl Counters {
         self.iters += 1;
     }
 
+    pub fn inc_first_move_breaks(&mut self) {
+        self.first_move_breaks += 1;
+    }
+
+    pub fn inc_killer_breaks(&mut self) {
+        self.killer_breaks += 1;
+    }
+
+    pub fn inc_endgame_reads(&mut self) {
+        self.endgame_reads += 1;
+    }
+
+    pub fn inc_endgame_writes(&mut self) {
+        self.endgame_writes += 1;
+    }
+
     pub fn accumulate(all_counters_result: Vec<Counters>) -> Counters {
         let mut ret = Counters::new();
         for counter in all_counters_result {
@@ -52,9 +80,47 @@ impl Counters {
     pub fn add(&mut self, counter: Counters) {
         self.breaks += counter.breaks;
         self.collisions += counter.collisions;
+        self.misses += counter.misses;
         self.exactreads += counter.exactreads;
         self.iters += counter.iters;
         self.writes += counter.writes;
         self.reads += counter.reads;
+        self.first_move_breaks += counter.first_move_breaks;
+        self.killer_breaks += counter.killer_breaks;
+        self.endgame_reads += counter.endgame_reads;
+        self.endgame_writes += counter.endgame_writes;
+    }
+
+    /// Transposition table lookups: hits (`reads`), misses and collisions
+    /// (the slot was occupied by other positions).
+    pub fn probes(&self) -> u32 {
+        self.reads + self.misses + self.collisions
+    }
+
+    /// Fraction of the lookups answered by the transposition table.
+    pub fn hit_rate(&self) -> f32 {
+        if self.probes() == 0 {
+            0.0
+        } else {
+            self.reads as f32 / self.probes() as f32
+        }
+    }
+
+    /// Fraction of the cutoffs caused by the first searched move.
+    pub fn first_move_rate(&self) -> f32 {
+        if self.breaks == 0 {
+            0.0
+        } else {
+            self.first_move_breaks as f32 / self.breaks as f32
+        }
+    }
+
+    /// Fraction of the lookups that found only other positions in the slot.
+    pub fn collision_rate(&self) -> f32 {
+        if self.probes() == 0 {
+            0.0
+        } else {
+            self.collisions as f32 / self.probes() as f32
+        }
     }
 }
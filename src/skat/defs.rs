@@ -80,6 +80,12 @@ pub enum Game {
     Suit,
     Grand,
     Null,
+    NullHand,
+    NullOuvert,
+    NullOuvertHand,
+    /// Everybody plays for himself after all players passed. Only the Jacks
+    /// are trump; the search sees it from the player in the declarer seat.
+    Ramsch,
 }
 
 impl Game {
@@ -88,30 +94,65 @@ impl Game {
             Game::Suit => "Suit".to_string(),
             Game::Grand => "Grand".to_string(),
             Game::Null => "Null".to_string(),
+            Game::NullHand => "Null Hand".to_string(),
+            Game::NullOuvert => "Null Ouvert".to_string(),
+            Game::NullOuvertHand => "Null Ouvert Hand".to_string(),
+            Game::Ramsch => "Ramsch".to_string(),
+        }
+    }
+
+    /// True for all Null variants. They share the card mechanics of a plain Null game
+    /// and only differ in value and in what the players know or may do with the Skat.
+    pub fn is_null(&self) -> bool {
+        matches!(
+            self,
+            Game::Null | Game::NullHand | Game::NullOuvert | Game::NullOuvertHand
+        )
+    }
+
+    /// True if the declarer plays without picking up the Skat.
+    pub fn is_hand(&self) -> bool {
+        matches!(self, Game::NullHand | Game::NullOuvertHand)
+    }
+
+    /// True if the declarer plays with open cards.
+    pub fn is_ouvert(&self) -> bool {
+        matches!(self, Game::NullOuvert | Game::NullOuvertHand)
+    }
+
+    /// Fixed game value of the Null variants. Suit and Grand games are valued by
+    /// matadors and multipliers, see `rules::calculate_game_value`.
+    pub fn null_value(&self) -> Option<u16> {
+        match self {
+            Game::Null => Some(23),
+            Game::NullHand => Some(35),
+            Game::NullOuvert => Some(46),
+            Game::NullOuvertHand => Some(59),
+            Game::Suit | Game::Grand | Game::Ramsch => None,
         }
     }
 
     pub fn get_trump(&self) -> u32 {
         match self {
             Game::Suit => TRUMP_SUIT,
-            Game::Grand => TRUMP_GRAND,
-            Game::Null => TRUMP_NULL,
+            Game::Grand | Game::Ramsch => TRUMP_GRAND,
+            _ => TRUMP_NULL,
         }
     }
 
     pub fn get_unequal_sequence(&self) -> &[(u32, u8)] {
         match self {
             Game::Suit => &SUIT_CONN,
-            Game::Grand => &GRAND_CONN,
-            Game::Null => panic!("Not allowed for Null because Null has only equal values."),
+            Game::Grand | Game::Ramsch => &GRAND_CONN,
+            _ => panic!("Not allowed for Null because Null has only equal values."),
         }
     }
 
     pub fn get_equal_sequence(&self) -> &[u32] {
         match self {
             Game::Suit => &SUIT_CONN_EQ,
-            Game::Grand => &GRAND_CONN_EQ,
-            Game::Null => &NULL_CONN_EQ,
+            Game::Grand | Game::Ramsch => &GRAND_CONN_EQ,
+            _ => &NULL_CONN_EQ,
         }
     }
 }
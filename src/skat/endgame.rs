@@ -0,0 +1,366 @@
+//! # Endgame Tables
+//!
+//! Lazily memoized values of the last tricks. Positions at a trick start with
+//! at most `ENDGAME_CARDS` cards left are keyed by a canonical encoding that
+//! keeps only what decides the play from there on: for every suit (and the
+//! trumps) the owners and card points of the remaining cards in rank order,
+//! and the player to move.
+//!
+//! Ranks are relative: played cards leave no gaps, so e.g. a lone ace and a
+//! lone ten of a suit whose ace is gone encode alike. The non-trump suits play
+//! alike and are sorted by their encoding. The rank orders are the connection
+//! sequences of the game (`SUIT_CONN`, `GRAND_CONN`, `NULL_CONN_EQ`); Null
+//! games ignore card points.
+//!
+//! Entries hold the points the declarer still makes (Null: 1 if he takes a
+//! trick) and the best card as suit and rank in the canonical order, so one
+//! entry serves all positions of its key. The table is shared by all searches
+//! of the process, see `EndgameTable::global`.
+
+use crate::consts::bitboard::{CONNECTION_BREAKER, GRAND_CONN, NULL_CONN_EQ, SUIT_CONN};
+use crate::skat::context::GameContext;
+use crate::skat::counters::Counters;
+use crate::skat::defs::{Game, Player};
+use crate::skat::position::Position;
+use crate::skat::rules::get_sorted_by_value;
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Positions with at most this many cards left (the last two tricks) are
+/// kept in the table. With the last three tricks the plain alpha-beta of the
+/// table searched more nodes than the table saved.
+pub const ENDGAME_CARDS: u32 = 6;
+
+/// Maximum number of entries; further positions are solved but not stored.
+pub const ENDGAME_CAPACITY: usize = 1 << 20;
+
+const SHARDS: usize = 64;
+
+/// Bits of a card code: owner (2 bits, never 0) and points class (3 bits).
+const CODE_BITS: u32 = 5;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct EndgameEntry {
+    /// Points the declarer makes from the remaining cards (Null: 0 or 1).
+    value: u8,
+    /// Best card as index of the canonical suit and rank within the suit.
+    suit: u8,
+    rank: u8,
+}
+
+/// Remaining cards of a suit (or the trumps) in rank order and their code.
+#[derive(Clone, Copy, Default)]
+struct SuitCode {
+    cards: [u32; ENDGAME_CARDS as usize],
+    len: usize,
+    code: u64,
+}
+
+pub struct EndgameTable {
+    shards: Vec<Mutex<HashMap<u128, EndgameEntry>>>,
+}
+
+impl Default for EndgameTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EndgameTable {
+    pub fn new() -> Self {
+        EndgameTable {
+            shards: (0..SHARDS).map(|_| Mutex::new(HashMap::new())).collect(),
+        }
+    }
+
+    /// Table shared by all searches of the process. Keys do not depend on
+    /// the deal, so e.g. the samples of a PIMC decision fill it together.
+    pub fn global() -> &'static EndgameTable {
+        static GLOBAL: OnceLock<EndgameTable> = OnceLock::new();
+        GLOBAL.get_or_init(EndgameTable::new)
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// True if `position` is an endgame position of the table.
+    pub fn covers(game_context: &GameContext, position: &Position) -> bool {
+        game_context.game_type != Game::Ramsch
+            && position.trick_cards_count == 0
+            && position.get_all_unplayed_cards().count_ones() <= ENDGAME_CARDS
+    }
+
+    /// Best card and value (declarer points at the end of the game, like
+    /// `search`) of an endgame position, see `covers`. Solved on a miss.
+    pub fn lookup(
+        &self,
+        game_context: &GameContext,
+        position: &Position,
+        cnt: &mut Counters,
+    ) -> (u32, u8) {
+        let (key, suits) = encode(game_context.game_type, position);
+        let shard = &self.shards[(key as u64 ^ (key >> 64) as u64) as usize % SHARDS];
+
+        let cached = shard.lock().unwrap().get(&key).copied();
+        let entry = match cached {
+            Some(entry) => {
+                cnt.inc_endgame_reads();
+                entry
+            }
+            None => {
+                let (card, value) = self.solve(game_context, position, cnt);
+                let (suit, rank) = locate(&suits, card);
+                let entry = EndgameEntry {
+                    value: value - position.declarer_points,
+                    suit,
+                    rank,
+                };
+                let mut positions = shard.lock().unwrap();
+                if positions.len() < ENDGAME_CAPACITY / SHARDS {
+                    positions.insert(key, entry);
+                    cnt.inc_endgame_writes();
+                }
+                entry
+            }
+        };
+
+        let card = suits[entry.suit as usize].cards[entry.rank as usize];
+        (card, position.declarer_points + entry.value)
+    }
+
+    /// Full-window alpha-beta search of an endgame position. Later trick starts
+    /// are looked up in the table again.
+    fn solve(
+        &self,
+        game_context: &GameContext,
+        position: &Position,
+        cnt: &mut Counters,
+    ) -> (u32, u8) {
+        self.search(game_context, position, cnt, 0, 120, true)
+    }
+
+    fn search(
+        &self,
+        game_context: &GameContext,
+        position: &Position,
+        cnt: &mut Counters,
+        mut alpha: u8,
+        mut beta: u8,
+        is_root: bool,
+    ) -> (u32, u8) {
+        cnt.inc_iters();
+
+        if position.player_cards == 0 {
+            return (0, position.declarer_points);
+        }
+        if game_context.game_type.is_null() && position.declarer_points > 0 {
+            return (0, 1);
+        }
+        if !is_root && position.trick_cards_count == 0 {
+            return self.lookup(game_context, position, cnt);
+        }
+
+        // Declarer maximizes his points, in Null he minimizes his tricks
+        let maximizes = (position.player == Player::Declarer) != game_context.game_type.is_null();
+
+        let (moves, n) = get_sorted_by_value(position.get_reduced_moves(game_context));
+        let mut best = (moves[0], if maximizes { 0 } else { 120 });
+
+        for mov in &moves[0..n] {
+            let child = position.make_move(*mov, game_context);
+            let (_, value) = self.search(game_context, &child, cnt, alpha, beta, false);
+
+            if maximizes {
+                if value > best.1 {
+                    best = (*mov, value);
+                }
+                alpha = alpha.max(value);
+            } else {
+                if value < best.1 {
+                    best = (*mov, value);
+                }
+                beta = beta.min(value);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        best
+    }
+}
+
+/// Canonical key of an endgame position and its suits in canonical order.
+fn encode(game: Game, position: &Position) -> (u128, [SuitCode; 5]) {
+    let remaining = position.get_all_unplayed_cards();
+    let (sequence, kind, has_trumps): (&[(u32, u8)], u128, bool) = match game {
+        Game::Suit => (&SUIT_CONN, 1, true),
+        Game::Grand => (&GRAND_CONN, 2, true),
+        _ => (&NULL_SEQUENCE, 3, false),
+    };
+
+    let mut suits = [SuitCode::default(); 5];
+    let mut i = 0;
+    for &(card, points) in sequence {
+        if card == CONNECTION_BREAKER {
+            i += 1;
+            continue;
+        }
+        if remaining & card == 0 {
+            continue;
+        }
+
+        let owner = if position.declarer_cards & card != 0 {
+            1
+        } else if position.left_cards & card != 0 {
+            2
+        } else {
+            3
+        };
+        let points = if game.is_null() {
+            0
+        } else {
+            points_class(points)
+        };
+
+        let suit = &mut suits[i];
+        suit.cards[suit.len] = card;
+        suit.len += 1;
+        suit.code = (suit.code << CODE_BITS) | (owner | points << 2);
+    }
+
+    // Non-trump suits play alike
+    let first = if has_trumps { 1 } else { 0 };
+    suits[first..i].sort_by_key(|suit| (suit.len, suit.code));
+
+    let mut key = kind << 2 | position.player as u128;
+    for suit in &suits[0..i] {
+        key = (key << (CODE_BITS * suit.len as u32)) | suit.code as u128;
+        key <<= CODE_BITS; // breaker
+    }
+
+    (key, suits)
+}
+
+/// Canonical suit and rank of a card.
+fn locate(suits: &[SuitCode; 5], card: u32) -> (u8, u8) {
+    for (i, suit) in suits.iter().enumerate() {
+        if let Some(rank) = suit.cards[0..suit.len].iter().position(|&c| c == card) {
+            return (i as u8, rank as u8);
+        }
+    }
+    panic!("Card {} not among the remaining cards.", card);
+}
+
+fn points_class(points: u8) -> u64 {
+    match points {
+        0 => 0,
+        2 => 1,
+        3 => 2,
+        4 => 3,
+        10 => 4,
+        _ => 5,
+    }
+}
+
+/// `NULL_CONN_EQ` in the form of the other connection sequences.
+const NULL_SEQUENCE: [(u32, u8); 36] = {
+    let mut sequence = [(0u32, 0u8); 36];
+    let mut i = 0;
+    while i < 36 {
+        sequence[i] = (NULL_CONN_EQ[i], 0);
+        i += 1;
+    }
+    sequence
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skat::builder::GameContextBuilder;
+    use crate::skat::control::SearchControl;
+    use crate::skat::search::{search, MoveOrder};
+    use crate::skat::tt::TranspositionTable;
+
+    #[test]
+    fn test_relative_ranks_share_an_entry() {
+        let key = |declarer: &str, left: &str, right: &str| {
+            let context = GameContextBuilder::new_grand()
+                .cards(Player::Declarer, declarer)
+                .cards(Player::Left, left)
+                .cards(Player::Right, right)
+                .turn(Player::Declarer)
+                .build();
+            encode(Game::Grand, &context.create_initial_position()).0
+        };
+
+        let spades = key("ST S9 HA", "SK HT H7", "SQ H9 H8");
+        // Suits swapped, S9 becomes H8: the same ranks relative to the others
+        assert_eq!(spades, key("HT H8 SA", "HK ST S7", "HQ S9 S8"));
+        // An ace instead of the ten differs in points
+        assert_ne!(spades, key("SA S9 HA", "SK HT H7", "SQ H9 H8"));
+        // Another player to move
+        assert_ne!(spades, key("SK HT H7", "ST S9 HA", "SQ H9 H8"));
+    }
+
+    #[test]
+    fn test_lookup_matches_search() {
+        let contexts = [
+            GameContextBuilder::new_farbspiel()
+                .cards(Player::Declarer, "CJ CA ST")
+                .cards(Player::Left, "HJ SA C7")
+                .cards(Player::Right, "CT SK HA")
+                .turn(Player::Left)
+                .build(),
+            GameContextBuilder::new_grand()
+                .cards(Player::Declarer, "CJ HA HK")
+                .cards(Player::Left, "SJ HT CA")
+                .cards(Player::Right, "C7 H7 DA")
+                .turn(Player::Right)
+                .build(),
+            GameContextBuilder::new(Game::Null)
+                .cards(Player::Declarer, "C7 H8 S9")
+                .cards(Player::Left, "CA HJ SK")
+                .cards(Player::Right, "C8 H7 DA")
+                .turn(Player::Declarer)
+                .build(),
+        ];
+
+        let table = EndgameTable::new();
+        for context in &contexts {
+            let position = context.create_initial_position();
+
+            let mut tt = TranspositionTable::new();
+            tt.set_endgame(None);
+            let mut cnt = Counters::new();
+            let (_, expected) = search(
+                context,
+                &position,
+                &mut tt,
+                &mut MoveOrder::new(Default::default(), context.game_type),
+                &SearchControl::unlimited(),
+                &mut cnt,
+                0,
+                120,
+            );
+
+            let (card, value) = table.lookup(context, &position, &mut cnt);
+            assert_eq!(value, expected);
+            assert!(position.get_legal_moves() & card != 0);
+
+            // Second lookup is read from the table
+            let reads = cnt.endgame_reads;
+            assert_eq!(table.lookup(context, &position, &mut cnt), (card, value));
+            assert_eq!(cnt.endgame_reads, reads + 1);
+        }
+    }
+}
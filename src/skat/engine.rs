@@ -4,21 +4,196 @@
 //! Holds the game context and the transposition table.
 
 use crate::skat::context::GameContext;
+use crate::skat::control::SearchControl;
 use crate::skat::counters::Counters;
+use crate::skat::endgame::EndgameTable;
 use crate::skat::position::Position;
-use crate::skat::search::search;
-use crate::skat::tt::TranspositionTable;
+use crate::skat::rules::get_sorted_by_value;
+use crate::skat::search::{
+    search, search_parallel, search_schwarz, search_variation, MoveOrder, MoveOrdering, Variation,
+    NO_HORIZON,
+};
+use crate::skat::solved::SolvedStore;
+use crate::skat::tt::{
+    read_entries_from_file, write_entries_to_file, SharedTranspositionTable, TableKey,
+    TranspositionEntry, TranspositionStore, TranspositionTable,
+};
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::collections::HashMap;
+use std::io;
+
+/// Plies below the searched position in which `search_parallel` splits.
+const SPLIT_PLIES: u8 = 3;
+
+/// Share of `tt` the table of the shallow searches of `deepen` gets.
+const SCRATCH_TT_FRACTION: usize = 16;
 
 pub struct SkatEngine {
     pub context: GameContext,
     pub tt: TranspositionTable,
+    parallel: Option<ParallelSearch>,
+    move_ordering: MoveOrdering,
+    /// Table of the shallow searches of `deepen`, created on first use.
+    scratch: Option<TranspositionTable>,
+}
+
+/// Thread pool and shared table of the parallel search mode.
+struct ParallelSearch {
+    pool: ThreadPool,
+    tt: SharedTranspositionTable,
 }
 
 impl SkatEngine {
     pub fn new(context: GameContext, tt: Option<TranspositionTable>) -> Self {
         Self {
             context,
-            tt: tt.unwrap_or_else(TranspositionTable::new),
+            tt: tt.unwrap_or_default(),
+            parallel: None,
+            move_ordering: MoveOrdering::default(),
+            scratch: None,
+        }
+    }
+
+    /// Reuses the engine and its tables for another game.
+    pub fn set_context(&mut self, context: GameContext) {
+        self.context = context;
+        self.tt.clear();
+        if let Some(parallel) = &self.parallel {
+            parallel.tt.clear();
+        }
+    }
+
+    pub fn move_ordering(&self) -> MoveOrdering {
+        self.move_ordering
+    }
+
+    /// Sets the move ordering of the searches. Killers and history are learned
+    /// anew in every search.
+    pub fn set_move_ordering(&mut self, move_ordering: MoveOrdering) {
+        self.move_ordering = move_ordering;
+    }
+
+    /// Number of search threads. With more than one thread `search` runs in
+    /// parallel on a shared transposition table (instead of `tt`).
+    pub fn threads(&self) -> usize {
+        self.parallel
+            .as_ref()
+            .map_or(1, |p| p.pool.current_num_threads())
+    }
+
+    /// Sets the number of search threads; 0 uses one thread per core.
+    pub fn set_threads(&mut self, threads: usize) -> Result<(), &'static str> {
+        if threads == 1 {
+            self.parallel = None;
+            return Ok(());
+        }
+
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .map_err(|_| "Unable to create the search thread pool")?;
+
+        let mut tt = SharedTranspositionTable::with_capacity(self.tt.capacity(), self.tt.policy());
+        tt.set_endgame(self.tt.endgame());
+
+        self.parallel = Some(ParallelSearch { pool, tt });
+        Ok(())
+    }
+
+    /// Uses the global endgame table (the default) or searches the last
+    /// tricks like all other positions.
+    pub fn set_endgame_table(&mut self, enabled: bool) {
+        let endgame = enabled.then(EndgameTable::global);
+        self.tt.set_endgame(endgame);
+        if let Some(parallel) = &mut self.parallel {
+            parallel.tt.set_endgame(endgame);
+        }
+    }
+
+    /// Occupied entries and capacity of the transposition table in use.
+    pub fn tt_fill(&self) -> (usize, usize) {
+        match &self.parallel {
+            Some(parallel) => (parallel.tt.get_occupied_slots(), parallel.tt.capacity()),
+            None => (self.tt.get_occupied_slots(), self.tt.capacity()),
+        }
+    }
+
+    /// Starts a new search generation of the transposition table, see
+    /// `ReplacementPolicy::AgePreferred`.
+    pub fn new_search(&mut self) {
+        match &self.parallel {
+            Some(parallel) => parallel.tt.new_search(),
+            None => self.tt.new_search(),
+        }
+    }
+
+    /// Key of the games the engine's table values are valid for.
+    pub fn table_key(&self) -> TableKey {
+        TableKey::of(&self.context)
+    }
+
+    /// Saves the transposition table in use, returns the number of entries.
+    pub fn save_tt(&self, path: &str) -> io::Result<usize> {
+        let entries = self.tt_entries();
+        write_entries_to_file(path, self.table_key(), &entries)?;
+        Ok(entries.len())
+    }
+
+    /// Adds the entries of a table file saved for the same game, returns the
+    /// number of entries read.
+    pub fn load_tt(&mut self, path: &str) -> io::Result<usize> {
+        let entries = read_entries_from_file(path, self.table_key())?;
+        for entry in &entries {
+            self.insert_entry(*entry);
+        }
+        Ok(entries.len())
+    }
+
+    /// Puts all positions of a solved store into the transposition table.
+    pub fn seed_solved(&mut self, store: &SolvedStore) -> Result<usize, &'static str> {
+        if store.key() != self.table_key() {
+            return Err("Solved positions belong to another game");
+        }
+        for entry in store.entries() {
+            self.insert_entry(*entry);
+        }
+        Ok(store.len())
+    }
+
+    /// Adds the exactly solved positions of the transposition table to a
+    /// store, returns the number of new positions.
+    pub fn harvest_solved(&self, store: &mut SolvedStore) -> Result<usize, &'static str> {
+        if store.key() != self.table_key() {
+            return Err("Solved positions belong to another game");
+        }
+        Ok(store.harvest(&self.tt_entries()))
+    }
+
+    fn tt_entries(&self) -> Vec<TranspositionEntry> {
+        match &self.parallel {
+            Some(parallel) => parallel.tt.entries(),
+            None => self.tt.entries().copied().collect(),
+        }
+    }
+
+    fn insert_entry(&mut self, entry: TranspositionEntry) {
+        match &self.parallel {
+            Some(parallel) => parallel.tt.insert(entry),
+            None => self.tt.insert(entry),
+        }
+    }
+
+    /// Runs `op` on the engine's search threads (or on the calling thread in
+    /// the sequential mode), e.g. to evaluate several root moves at once.
+    pub fn install<OP, R>(&self, op: OP) -> R
+    where
+        OP: FnOnce() -> R + Send,
+        R: Send,
+    {
+        match &self.parallel {
+            Some(parallel) => parallel.pool.install(op),
+            None => op(),
         }
     }
 
@@ -29,7 +204,208 @@ impl SkatEngine {
         alpha: u8,
         beta: u8,
     ) -> (u32, u8) {
-        search(&self.context, position, &mut self.tt, cnt, alpha, beta)
+        self.search_window(position, cnt, alpha, beta, &SearchControl::unlimited())
+    }
+
+    /// `search` within the limits of `control`, `None` if it was interrupted.
+    pub fn search_controlled(
+        &mut self,
+        position: &Position,
+        cnt: &mut Counters,
+        alpha: u8,
+        beta: u8,
+        control: &SearchControl,
+    ) -> Option<(u32, u8)> {
+        let result = self.search_window(position, cnt, alpha, beta, control);
+        (!control.is_stopped()).then_some(result)
+    }
+
+    fn search_window(
+        &mut self,
+        position: &Position,
+        cnt: &mut Counters,
+        alpha: u8,
+        beta: u8,
+        control: &SearchControl,
+    ) -> (u32, u8) {
+        let mut order = MoveOrder::new(self.move_ordering, self.context.game_type);
+        match &self.parallel {
+            Some(parallel) => search_parallel(
+                &self.context,
+                position,
+                &parallel.tt,
+                &parallel.pool,
+                &mut order,
+                control,
+                cnt,
+                alpha,
+                beta,
+                SPLIT_PLIES,
+            ),
+            None => search(
+                &self.context,
+                position,
+                &mut self.tt,
+                &mut order,
+                control,
+                cnt,
+                alpha,
+                beta,
+            ),
+        }
+    }
+
+    /// Returns the value and the optimal card sequence to the end of the hand.
+    pub fn search_variation(&mut self, position: &Position, cnt: &mut Counters) -> Variation {
+        self.search_complete_variation(position, cnt, &[], &SearchControl::unlimited())
+    }
+
+    fn search_complete_variation(
+        &mut self,
+        position: &Position,
+        cnt: &mut Counters,
+        pv_hint: &[u32],
+        control: &SearchControl,
+    ) -> Variation {
+        let mut order = MoveOrder::new(self.move_ordering, self.context.game_type);
+        match &self.parallel {
+            Some(parallel) => {
+                let mut shared = &parallel.tt;
+                search_variation(
+                    &self.context,
+                    position,
+                    &mut shared,
+                    &mut order,
+                    control,
+                    cnt,
+                    NO_HORIZON,
+                    pv_hint,
+                )
+            }
+            None => search_variation(
+                &self.context,
+                position,
+                &mut self.tt,
+                &mut order,
+                control,
+                cnt,
+                NO_HORIZON,
+                pv_hint,
+            ),
+        }
+    }
+
+    /// Iterative deepening trick by trick. Every depth is searched with the
+    /// principal variation of the previous one as move ordering hint and is
+    /// reported to `on_depth` before the next depth starts; the last report is
+    /// the complete search, which is also returned.
+    ///
+    /// Limited depths use a scratch table, only the complete search writes to
+    /// the engine's transposition table.
+    pub fn search_iterative<F>(
+        &mut self,
+        position: &Position,
+        cnt: &mut Counters,
+        on_depth: F,
+    ) -> Variation
+    where
+        F: FnMut(&Variation),
+    {
+        self.deepen(position, cnt, &SearchControl::unlimited(), on_depth)
+    }
+
+    /// Anytime search: `search_iterative` within the limits of `control`.
+    ///
+    /// Returns the variation of the deepest search completed before an
+    /// interruption; its value is exact if it `is_complete`. Interrupted
+    /// within the first trick, the first card in static order is returned
+    /// with the points made so far.
+    pub fn search_anytime(
+        &mut self,
+        position: &Position,
+        cnt: &mut Counters,
+        control: &SearchControl,
+    ) -> Variation {
+        self.deepen(position, cnt, control, |_| {})
+    }
+
+    fn deepen<F>(
+        &mut self,
+        position: &Position,
+        cnt: &mut Counters,
+        control: &SearchControl,
+        mut on_depth: F,
+    ) -> Variation
+    where
+        F: FnMut(&Variation),
+    {
+        let root_tricks = position.declarer_tricks + position.team_tricks;
+        let cards_left =
+            position.get_all_unplayed_cards().count_ones() as u8 + position.trick_cards_count;
+        let tricks_left = cards_left.div_ceil(3);
+
+        let mut variation = Variation::default();
+
+        if tricks_left > 1 {
+            let (capacity, policy) = (self.tt.capacity() / SCRATCH_TT_FRACTION, self.tt.policy());
+            let scratch = self
+                .scratch
+                .get_or_insert_with(|| TranspositionTable::with_capacity(capacity, policy));
+            scratch.set_endgame(self.tt.endgame());
+            let mut order = MoveOrder::new(self.move_ordering, self.context.game_type);
+            for depth in 1..tricks_left {
+                if control.check() {
+                    return self.best_known(position, variation);
+                }
+                scratch.clear();
+                let deeper = search_variation(
+                    &self.context,
+                    position,
+                    scratch,
+                    &mut order,
+                    control,
+                    cnt,
+                    root_tricks + depth,
+                    &variation.cards,
+                );
+                if control.is_stopped() {
+                    return self.best_known(position, variation);
+                }
+                variation = deeper;
+                on_depth(&variation);
+            }
+        }
+
+        if control.check() {
+            return self.best_known(position, variation);
+        }
+        let complete = self.search_complete_variation(position, cnt, &variation.cards, control);
+        if control.is_stopped() {
+            return self.best_known(position, variation);
+        }
+        on_depth(&complete);
+
+        complete
+    }
+
+    /// Result of an interrupted `deepen`: the last completed variation, if any.
+    fn best_known(&self, position: &Position, variation: Variation) -> Variation {
+        if !variation.cards.is_empty() {
+            return variation;
+        }
+
+        let (moves, n) = get_sorted_by_value(position.get_reduced_moves(&self.context));
+        Variation {
+            horizon: position.declarer_tricks + position.team_tricks,
+            value: position.declarer_points,
+            cards: moves[0..n.min(1)].to_vec(),
+        }
+    }
+
+    /// Returns a declarer card and whether the declarer can make Schwarz.
+    pub fn search_schwarz(&self, position: &Position, cnt: &mut Counters) -> (u32, bool) {
+        let mut memo = HashMap::new();
+        search_schwarz(&self.context, position, &mut memo, cnt)
     }
 
     pub fn create_initial_position(&self) -> Position {
@@ -3,8 +3,8 @@ use crate::traits::StringConverter; // For __str backup
 
 pub fn format_hand_for_game(hand: u32, game_name: &str) -> String {
     match game_name {
-        "Grand" => hand.__str(),
-        "Null" => format_null(hand),
+        "Grand" | "Ramsch" => hand.__str(),
+        "Null" | "Null Hand" | "Null Ouvert" | "Null Ouvert Hand" => format_null(hand),
         "Clubs" => format_suit_game(hand, 3),    // 3=Clubs
         "Spades" => format_suit_game(hand, 2),   // 2=Spades
         "Hearts" => format_suit_game(hand, 1),   // 1=Hearts
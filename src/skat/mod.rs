@@ -1,11 +1,16 @@
+pub mod bidding;
 pub mod builder;
 pub mod context;
+pub mod control;
 pub mod counters;
 pub mod defs;
+pub mod endgame;
 pub mod engine;
 pub mod formatter;
 pub mod position;
 pub mod rules;
+pub mod scoring;
 pub mod search;
 pub mod signature;
+pub mod solved;
 pub mod tt;
@@ -26,10 +26,14 @@ pub struct Position {
     pub remaining_points: u8,
     pub team_points: u8,
 
+    // Tricks taken since the context start (Schwarz targets)
+    pub declarer_tricks: u8,
+    pub team_tricks: u8,
+
     // Additional values
     pub is_root_position: bool,
 
-    hash: usize,
+    hash: u64,
 }
 
 impl Position {
@@ -46,6 +50,8 @@ impl Position {
         trick_cards_count: u8,
         remaining_points: u8,
         team_points: u8,
+        declarer_tricks: u8,
+        team_tricks: u8,
         is_root_position: bool,
     ) -> Self {
         let pos = Self {
@@ -61,6 +67,8 @@ impl Position {
             trick_cards_count,
             remaining_points,
             team_points,
+            declarer_tricks,
+            team_tricks,
             is_root_position,
             hash: 0,
         };
@@ -84,11 +92,18 @@ impl Position {
         let trick_points = trick_cards.points();
         // Total points (120) = Remaining (Hands) + Trick (Table) + Declarer (Won) + Team (Won)
         // Solved for Team:
+        // In Ramsch the Skat is not owned by anybody until the last trick.
+        let unowned_points = if game_context.game_type() == Game::Ramsch {
+            game_context.get_skat().points()
+        } else {
+            0
+        };
         let team_points = game_context
             .total_points()
             .wrapping_sub(remaining_points)
             .wrapping_sub(declarer_points)
-            .wrapping_sub(trick_points); // wrapping_sub to be safe, but logic should hold
+            .wrapping_sub(trick_points) // wrapping_sub to be safe, but logic should hold
+            .wrapping_sub(unowned_points);
 
         let player_cards = match player {
             Player::Declarer => declarer_cards,
@@ -110,13 +125,16 @@ impl Position {
             trick_cards_count,
             remaining_points,
             team_points,
+            0,
+            0,
             is_root_position,
         )
     }
 
     pub fn create_initial_position(game_context: &GameContext) -> Position {
         let mut initial_points = game_context.declarer_start_points;
-        if game_context.game_type != Game::Null {
+        // In Ramsch the Skat goes to the winner of the last trick, see make_move.
+        if !game_context.game_type.is_null() && game_context.game_type != Game::Ramsch {
             initial_points += game_context.get_skat().points();
         }
 
@@ -152,6 +170,8 @@ impl Position {
         // new points
         let mut new_declarer_points = self.declarer_points;
         let mut new_team_points = self.team_points;
+        let mut new_declarer_tricks = self.declarer_tricks;
+        let mut new_team_tricks = self.team_tricks;
 
         // Update remaining points: Card leaves hand, so subtract its points immediately.
         let new_remaining_points = self.remaining_points.saturating_sub(card.points());
@@ -161,6 +181,9 @@ impl Position {
         let mut new_left_cards = self.left_cards;
         let mut new_right_cards = self.right_cards;
 
+        // Card moves from the hand into the trick
+        let mut new_hash = self.hash ^ get_move_hash(card, self.player);
+
         // Update hand cards according to last player
         match self.player {
             Player::Declarer => new_declarer_cards ^= card,
@@ -170,22 +193,34 @@ impl Position {
 
         // evaluate upon trick completion
         if new_trick_cards_count == 3 {
-            let points = if game_context.game_type() == Game::Null {
+            let mut points = if game_context.game_type().is_null() {
                 1
             } else {
                 new_trick_cards.trick_points()
             };
 
+            let is_last_trick = (new_declarer_cards | new_left_cards | new_right_cards) == 0;
+            if is_last_trick && game_context.game_type() == Game::Ramsch {
+                points += game_context.get_skat().points();
+            }
+
             let winner = self.calculate_trick_winner(new_trick_cards, new_trick_suit, game_context);
 
+            new_hash ^= get_cards_hash(new_trick_cards, ZOBRIST_TRICK);
             new_trick_cards = 0;
             new_trick_cards_count = 0;
             new_trick_suit = 0;
             new_player = winner;
 
             match winner {
-                Player::Declarer => new_declarer_points += points,
-                _ => new_team_points += points,
+                Player::Declarer => {
+                    new_declarer_points += points;
+                    new_declarer_tricks += 1;
+                }
+                _ => {
+                    new_team_points += points;
+                    new_team_tricks += 1;
+                }
             }
 
             // remaining_points already reduced when cards were played.
@@ -198,21 +233,26 @@ impl Position {
             Player::Right => new_right_cards,
         };
 
-        Position::new(
-            new_player,
-            new_played_cards,
-            new_trick_cards,
-            new_trick_suit,
-            new_declarer_points,
-            new_declarer_cards,
-            new_left_cards,
-            new_right_cards,
-            new_player_cards,
-            new_trick_cards_count,
-            new_remaining_points,
-            new_team_points,
-            false,
-        )
+        new_hash ^= get_player_hash(self.player) ^ get_player_hash(new_player);
+
+        Position {
+            player: new_player,
+            played_cards: new_played_cards,
+            trick_cards: new_trick_cards,
+            trick_suit: new_trick_suit,
+            declarer_points: new_declarer_points,
+            declarer_cards: new_declarer_cards,
+            left_cards: new_left_cards,
+            right_cards: new_right_cards,
+            player_cards: new_player_cards,
+            trick_cards_count: new_trick_cards_count,
+            remaining_points: new_remaining_points,
+            team_points: new_team_points,
+            declarer_tricks: new_declarer_tricks,
+            team_tricks: new_team_tricks,
+            is_root_position: false,
+            hash: new_hash,
+        }
     }
 
     pub fn get_legal_moves(&self) -> u32 {
@@ -239,18 +279,19 @@ impl Position {
         )
     }
 
-    pub fn get_hash(&self) -> usize {
+    /// Zobrist hash of the position, kept up to date by `make_move`.
+    pub fn get_hash(&self) -> u64 {
         self.hash
     }
 
     fn add_hash(mut self) -> Position {
-        self.hash = get_mapped_hash(get_hash(
+        self.hash = get_hash(
             self.player,
-            self.declarer_cards, // Optimization: Use pointers or references? No, u32 is cheap.
+            self.declarer_cards,
             self.left_cards,
             self.right_cards,
             self.trick_cards,
-        ));
+        );
         self
     }
 
@@ -258,11 +299,11 @@ impl Position {
     pub fn get_reduced_moves(&self, game_context: &GameContext) -> u32 {
         let mut moves = self.get_legal_moves();
         match game_context.game_type() {
-            Game::Suit | Game::Grand => {
+            Game::Suit | Game::Grand | Game::Ramsch => {
                 moves = self.reduce_unequal(moves, game_context);
                 moves = self.reduce_equal(moves, game_context.game_type());
             }
-            Game::Null => {
+            _ => {
                 moves = self.reduce_equal(moves, game_context.game_type());
             }
         }
@@ -286,7 +327,8 @@ impl Position {
             let winner = self.predict_winner(repr, game_context);
 
             if let Some(x) = winner {
-                if x.is_same_team_as(player) {
+                // Ramsch inverts the goal: points given to the own side are bad.
+                if x.is_same_team_as(player) != (game_context.game_type() == Game::Ramsch) {
                     ret |= connections[i].1;
                 } else {
                     ret |= connections[i].2;
@@ -384,3 +426,41 @@ impl Position {
 // Assuming they are on the Player impl which we need to make sure is available or replicated.
 // In defs.rs we just defined the Enum, but not the methods.
 // We must add those methods to defs.rs or a separate impl file.
+
+#[cfg(test)]
+mod tests {
+    use crate::skat::builder::GameContextBuilder;
+    use crate::skat::defs::Player;
+    use crate::skat::rules::get_hash;
+
+    #[test]
+    fn test_incremental_hash() {
+        let context = GameContextBuilder::new_grand()
+            .cards(Player::Declarer, "CJ SA ST HA")
+            .cards(Player::Left, "SJ SK HT CA")
+            .cards(Player::Right, "HJ SQ HK CT")
+            .turn(Player::Declarer)
+            .build();
+
+        let mut position = context.create_initial_position();
+        while position.player_cards != 0 {
+            let moves = position.get_legal_moves();
+            // Highest and lowest legal card alternately
+            let card = if position.trick_cards_count.is_multiple_of(2) {
+                1 << (31 - moves.leading_zeros())
+            } else {
+                moves & moves.wrapping_neg()
+            };
+            position = position.make_move(card, &context);
+
+            let hash = get_hash(
+                position.player,
+                position.declarer_cards,
+                position.left_cards,
+                position.right_cards,
+                position.trick_cards,
+            );
+            assert_eq!(position.get_hash(), hash);
+        }
+    }
+}
@@ -4,8 +4,9 @@
 //! Formerly `core_functions`.
 
 use crate::consts::bitboard::*;
-use crate::consts::general::{HSH_INIT, HSH_MUL, TT_SIZE_U64};
+use crate::consts::general::ZOBRIST_SEED;
 use crate::skat::defs::{Game, Player};
+use crate::skat::scoring::{base_game_value, Contract};
 use crate::traits::Bitboard;
 
 // -----------------------------------------------------------------------------
@@ -134,76 +135,73 @@ fn invalidate_connection(single_moves: u32, conn_length: u8, conn_all: u32) -> (
 // HASHING
 // -----------------------------------------------------------------------------
 
+/// Zobrist key index of the trick; hands use the index of their player.
+pub const ZOBRIST_TRICK: usize = 3;
+
+/// Zobrist keys: one per card (bit index) and location (the three hands and
+/// the trick), followed by one per player to move.
+const ZOBRIST_KEYS: [u64; 4 * 32 + 3] = zobrist_keys();
+
+const fn zobrist_keys() -> [u64; 4 * 32 + 3] {
+    // splitmix64, fixed seed: keys (and hence table files) are stable across runs.
+    let mut keys = [0u64; 4 * 32 + 3];
+    let mut state = ZOBRIST_SEED;
+    let mut i = 0;
+    while i < keys.len() {
+        state = state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+}
+
+/// Zobrist key of a set of cards at a location (`Player as usize` or
+/// `ZOBRIST_TRICK`).
+#[inline(always)]
+pub fn get_cards_hash(cards: u32, location: usize) -> u64 {
+    let mut hash = 0u64;
+    let mut rest = cards;
+    while rest != 0 {
+        hash ^= ZOBRIST_KEYS[location * 32 + rest.trailing_zeros() as usize];
+        rest &= rest - 1;
+    }
+    hash
+}
+
+/// Zobrist key of the player to move.
+#[inline(always)]
+pub fn get_player_hash(player: Player) -> u64 {
+    ZOBRIST_KEYS[4 * 32 + player as usize]
+}
+
+/// Difference of the Zobrist hash when `player` puts `card` into the trick.
+#[inline(always)]
+pub fn get_move_hash(card: u32, player: Player) -> u64 {
+    get_cards_hash(card, player as usize) ^ get_cards_hash(card, ZOBRIST_TRICK)
+}
+
+/// Zobrist hash of a position, computed from scratch. `Position::make_move`
+/// updates it incrementally.
 pub fn get_hash(
     player: Player,
+    declarer_cards: u32,
     left_cards: u32,
     right_cards: u32,
-    declarer_cards: u32,
     trick_cards: u32,
 ) -> u64 {
-    let mut hash = HSH_INIT;
-    let mut x1 = left_cards as u64;
-    let mut x2 = right_cards as u64;
-    let mut x3 = declarer_cards as u64;
-    let mut x4 = trick_cards as u64;
-
-    hash ^= x1 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-    x1 >>= 8;
-    hash ^= x1 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-    x1 >>= 8;
-    hash ^= x1 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-    x1 >>= 8;
-    hash ^= x1 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-
-    hash ^= x2 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-    x2 >>= 8;
-    hash ^= x2 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-    x2 >>= 8;
-    hash ^= x2 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-    x2 >>= 8;
-    hash ^= x2 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-
-    hash ^= x3 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-    x3 >>= 8;
-    hash ^= x3 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-    x3 >>= 8;
-    hash ^= x3 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-    x3 >>= 8;
-    hash ^= x3 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-
-    hash ^= x4 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-    x4 >>= 8;
-    hash ^= x4 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-    x4 >>= 8;
-    hash ^= x4 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-    x4 >>= 8;
-    hash ^= x4 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-
-    hash ^= player as u64 & 0xFF;
-    hash = hash.wrapping_mul(HSH_MUL);
-
-    hash
+    get_cards_hash(declarer_cards, Player::Declarer as usize)
+        ^ get_cards_hash(left_cards, Player::Left as usize)
+        ^ get_cards_hash(right_cards, Player::Right as usize)
+        ^ get_cards_hash(trick_cards, ZOBRIST_TRICK)
+        ^ get_player_hash(player)
 }
 
-pub fn get_mapped_hash(hash: u64) -> usize {
-    let mapped_hash = hash % TT_SIZE_U64;
-    mapped_hash as usize
+/// Maps a hash to a slot of a table with `mask + 1` slots (a power of two).
+pub fn get_mapped_hash(hash: u64, mask: u64) -> usize {
+    (hash & mask) as usize
 }
 
 // -----------------------------------------------------------------------------
@@ -290,7 +288,7 @@ pub fn get_suit_for_card(card: u32, game_type: Game) -> u32 {
             x if DIAMONDS.__contain(x) => DIAMONDS,
             _ => 0u32,
         },
-        Game::Grand => match card {
+        Game::Grand | Game::Ramsch => match card {
             x if TRUMP_GRAND.__contain(x) => TRUMP_GRAND,
             x if CLUBS.__contain(x) => CLUBS,
             x if SPADES.__contain(x) => SPADES,
@@ -298,7 +296,7 @@ pub fn get_suit_for_card(card: u32, game_type: Game) -> u32 {
             x if DIAMONDS.__contain(x) => DIAMONDS,
             _ => 0u32,
         },
-        Game::Null => match card {
+        _ => match card {
             x if NULL_CLUBS.__contain(x) => NULL_CLUBS,
             x if NULL_SPADES.__contain(x) => NULL_SPADES,
             x if NULL_HEARTS.__contain(x) => NULL_HEARTS,
@@ -330,7 +328,7 @@ pub fn get_trick_winner(
     let mut lead_left = lead_cards & left_cards_all;
     let mut lead_right = lead_cards & right_cards_all;
 
-    if let Game::Null = game_type {
+    if game_type.is_null() {
         lead_declarer = nullmap(lead_declarer).unwrap_or(0);
         lead_left = nullmap(lead_left).unwrap_or(0);
         lead_right = nullmap(lead_right).unwrap_or(0);
@@ -374,65 +372,21 @@ pub fn get_trick_limit(_game: Game) -> u8 {
 // GAME VALUE CALCULATION
 // -----------------------------------------------------------------------------
 
+/// Value of a won game without extra levels (see `scoring::base_game_value`).
 pub fn calculate_game_value(hand_skat: u32, game_variant: u8) -> u16 {
-    // game_variant: 0=Grand, 1=Clubs, 2=Spades, 3=Hearts, 4=Diamonds, 5=Null
-    match game_variant {
-        5 => 23, // Null
-        _ => {
-            let base = match game_variant {
-                0 => 24, // Grand
-                1 => 12, // Clubs
-                2 => 11, // Spades
-                3 => 10, // Hearts
-                4 => 9,  // Diamonds
-                _ => 0,
-            };
-            if base == 0 {
-                return 0;
-            }
-
-            let matadors = count_matadors(hand_skat);
-            (matadors + 1) * base
-        }
-    }
-}
-
-fn count_matadors(cards: u32) -> u16 {
-    let has_cj = (cards & JACKOFCLUBS) != 0;
-    let mut m = 1;
-    if has_cj {
-        if (cards & JACKOFSPADES) != 0 {
-            m += 1;
-        } else {
-            return m;
-        }
-        if (cards & JACKOFHEARTS) != 0 {
-            m += 1;
-        } else {
-            return m;
-        }
-        if (cards & JACKOFDIAMONDS) != 0 {
-            m += 1;
-        } else {
-            return m;
-        }
-        m
-    } else {
-        if (cards & JACKOFSPADES) == 0 {
-            m += 1;
-        } else {
-            return m;
-        }
-        if (cards & JACKOFHEARTS) == 0 {
-            m += 1;
-        } else {
-            return m;
-        }
-        if (cards & JACKOFDIAMONDS) == 0 {
-            m += 1;
-        } else {
-            return m;
-        }
-        m
-    }
+    // game_variant: 0=Grand, 1=Clubs, 2=Spades, 3=Hearts, 4=Diamonds, 5=Null,
+    // 6=Null Hand, 7=Null Ouvert, 8=Null Ouvert Hand
+    let contract = match game_variant {
+        0 => Contract::grand(),
+        1 => Contract::suit(CLUBS),
+        2 => Contract::suit(SPADES),
+        3 => Contract::suit(HEARTS),
+        4 => Contract::suit(DIAMONDS),
+        5 => Contract::new(Game::Null),
+        6 => Contract::new(Game::NullHand),
+        7 => Contract::new(Game::NullOuvert),
+        8 => Contract::new(Game::NullOuvertHand),
+        _ => return 0,
+    };
+    base_game_value(&contract, hand_skat)
 }
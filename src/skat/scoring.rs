@@ -0,0 +1,691 @@
+//! # Scoring
+//!
+//! Settles finished games following the ISkO rules: matadors, game levels
+//! (Hand, Schneider, Schwarz, announcements, Ouvert), loss doubling, Kontra
+//! and Re, and the overbid case ("überreizt"). Ramsch is settled per player.
+
+use crate::consts::bitboard::*;
+use crate::skat::context::ProblemTransformation;
+use crate::skat::defs::{Game, Player};
+use crate::skat::rules::{get_suit_for_card, get_trick_winner};
+use crate::traits::Points;
+
+// -----------------------------------------------------------------------------
+// TYPES
+// -----------------------------------------------------------------------------
+
+/// The game the declarer announced after the auction.
+///
+/// Suit games carry their real trump suit, the solver itself always plays
+/// them as Clubs (see `ProblemTransformation`). The Null variants encode Hand
+/// and Ouvert in `game`; for Suit and Grand the flags below are used.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contract {
+    pub game: Game,
+    pub trump_suit: u32,
+    pub hand: bool,
+    pub schneider_announced: bool,
+    pub schwarz_announced: bool,
+    pub ouvert: bool,
+    pub doubling: Doubling,
+}
+
+/// Kontra by a defender doubles the game value, Re by the declarer doubles it
+/// once more. The bid is compared with the undoubled value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Doubling {
+    None,
+    Kontra,
+    Re,
+}
+
+/// A completed trick and the player who took it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Trick {
+    pub cards: u32,
+    pub winner: Player,
+}
+
+/// What the declarer achieved in the card play. `declarer_tricks` is `None`
+/// if only card points are known (e.g. from a point search); Schwarz is then
+/// never assumed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameOutcome {
+    pub declarer_points: u8,
+    pub declarer_tricks: Option<u8>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Settlement {
+    pub won: bool,
+    pub overbid: bool,
+    /// Number of matadors, "with" or "without" (Suit and Grand only).
+    pub matadors: u8,
+    pub multiplier: u16,
+    pub doubling: Doubling,
+    /// Game value including Kontra and Re.
+    pub game_value: u16,
+    /// Points entered on the score sheet: `game_value`, or minus twice the
+    /// game value if the game is lost.
+    pub score: i32,
+}
+
+/// Card points and tricks of each player after a Ramsch, indexed by
+/// `Player as usize`. The Skat belongs to the winner of the last trick.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RamschOutcome {
+    pub points: [u8; 3],
+    pub tricks: [u8; 3],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RamschSettlement {
+    /// Players with the most points; all of them lose on a tie.
+    pub losers: [bool; 3],
+    /// Number of players without a trick ("Jungfrau"), each doubles the loss.
+    pub jungfrau: u8,
+    /// Player who took all tricks ("Durchmarsch") and wins instead.
+    pub durchmarsch: Option<Player>,
+    pub scores: [i32; 3],
+}
+
+const NUMBER_OF_TRICKS: u8 = 10;
+const DURCHMARSCH_VALUE: i32 = 120;
+
+/// Seeger-Fabian points for every won or lost game of the declarer.
+const SEEGER_FABIAN_GAME_POINTS: i32 = 50;
+/// Seeger-Fabian points of each defender for a lost game, at a table of three.
+const SEEGER_FABIAN_DEFENDER_BONUS: i32 = 40;
+
+// -----------------------------------------------------------------------------
+// CONTRACT
+// -----------------------------------------------------------------------------
+
+impl Contract {
+    /// Plain game of the given type. Suit games default to Clubs.
+    pub fn new(game: Game) -> Contract {
+        Contract {
+            game,
+            trump_suit: if game == Game::Suit { CLUBS } else { 0 },
+            hand: false,
+            schneider_announced: false,
+            schwarz_announced: false,
+            ouvert: false,
+            doubling: Doubling::None,
+        }
+    }
+
+    pub fn suit(trump_suit: u32) -> Contract {
+        let mut contract = Contract::new(Game::Suit);
+        contract.trump_suit = trump_suit;
+        contract
+    }
+
+    pub fn grand() -> Contract {
+        Contract::new(Game::Grand)
+    }
+
+    /// Contract for a game as the solver sees it: a game type plus the
+    /// transformation that maps the real trump suit onto Clubs.
+    pub fn from_transformation(
+        game: Game,
+        transformation: Option<ProblemTransformation>,
+    ) -> Contract {
+        match (game, transformation) {
+            (Game::Suit, Some(ProblemTransformation::SpadesSwitch)) => Contract::suit(SPADES),
+            (Game::Suit, Some(ProblemTransformation::HeartsSwitch)) => Contract::suit(HEARTS),
+            (Game::Suit, Some(ProblemTransformation::DiamondsSwitch)) => Contract::suit(DIAMONDS),
+            _ => Contract::new(game),
+        }
+    }
+
+    pub fn hand(mut self) -> Contract {
+        self.hand = true;
+        self
+    }
+
+    /// Schneider can only be announced in Hand games.
+    pub fn announce_schneider(mut self) -> Contract {
+        self.hand = true;
+        self.schneider_announced = true;
+        self
+    }
+
+    pub fn announce_schwarz(mut self) -> Contract {
+        self = self.announce_schneider();
+        self.schwarz_announced = true;
+        self
+    }
+
+    /// Open Suit and Grand games are always Hand games with Schwarz announced.
+    pub fn ouvert(mut self) -> Contract {
+        self = self.announce_schwarz();
+        self.ouvert = true;
+        self
+    }
+
+    pub fn kontra(mut self) -> Contract {
+        self.doubling = Doubling::Kontra;
+        self
+    }
+
+    /// Re can only follow a Kontra.
+    pub fn re(mut self) -> Result<Contract, &'static str> {
+        if self.doubling != Doubling::Kontra {
+            return Err("Re can only follow a Kontra");
+        }
+        self.doubling = Doubling::Re;
+        Ok(self)
+    }
+
+    /// Kontra/Re state of a running game, see `GameContext::doubling`.
+    pub fn with_doubling(mut self, doubling: Doubling) -> Contract {
+        self.doubling = doubling;
+        self
+    }
+
+    pub fn is_hand(&self) -> bool {
+        self.hand || self.game.is_hand()
+    }
+
+    /// Base value: Clubs 12, Spades 11, Hearts 10, Diamonds 9, Grand 24.
+    /// Null games have a fixed value instead.
+    pub fn base_value(&self) -> u16 {
+        match self.game {
+            Game::Grand => 24,
+            Game::Suit => match self.trump_suit {
+                CLUBS => 12,
+                SPADES => 11,
+                HEARTS => 10,
+                DIAMONDS => 9,
+                _ => 0,
+            },
+            g => g.null_value().unwrap_or(0),
+        }
+    }
+}
+
+impl Doubling {
+    pub fn factor(&self) -> u16 {
+        match self {
+            Doubling::None => 1,
+            Doubling::Kontra => 2,
+            Doubling::Re => 4,
+        }
+    }
+
+    /// The next announcement, `None` after Re.
+    pub fn next(&self) -> Option<Doubling> {
+        match self {
+            Doubling::None => Some(Doubling::Kontra),
+            Doubling::Kontra => Some(Doubling::Re),
+            Doubling::Re => None,
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// OUTCOME
+// -----------------------------------------------------------------------------
+
+impl GameOutcome {
+    /// Outcome of a finished game. The Skat is counted for the declarer.
+    pub fn from_tricks(tricks: &[Trick], skat: u32) -> GameOutcome {
+        let mut declarer_points = skat.points();
+        let mut declarer_tricks = 0;
+
+        for trick in tricks.iter().filter(|t| t.winner == Player::Declarer) {
+            declarer_points += trick.cards.points();
+            declarer_tricks += 1;
+        }
+
+        GameOutcome {
+            declarer_points,
+            declarer_tricks: Some(declarer_tricks),
+        }
+    }
+
+    /// Outcome from a double dummy value: card points incl. Skat for Suit and
+    /// Grand, 0 (no trick taken) or 1 for the Null variants.
+    pub fn from_solver_value(game: Game, value: u8) -> GameOutcome {
+        if game.is_null() {
+            GameOutcome {
+                declarer_points: 0,
+                declarer_tricks: Some(if value == 0 { 0 } else { 1 }),
+            }
+        } else {
+            GameOutcome {
+                declarer_points: value,
+                declarer_tricks: None,
+            }
+        }
+    }
+
+    pub fn is_schneider(&self) -> bool {
+        self.declarer_points >= 90 || self.declarer_points <= 30
+    }
+
+    pub fn is_schwarz(&self) -> bool {
+        matches!(self.declarer_tricks, Some(0) | Some(NUMBER_OF_TRICKS))
+    }
+}
+
+impl RamschOutcome {
+    pub fn from_tricks(tricks: &[Trick], skat: u32) -> RamschOutcome {
+        let mut points = [0u8; 3];
+        let mut counts = [0u8; 3];
+
+        for trick in tricks {
+            points[trick.winner as usize] += trick.cards.points();
+            counts[trick.winner as usize] += 1;
+        }
+        if let Some(last) = tricks.last() {
+            points[last.winner as usize] += skat.points();
+        }
+
+        RamschOutcome {
+            points,
+            tricks: counts,
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// FUNCTIONS
+// -----------------------------------------------------------------------------
+
+/// Settles a Ramsch: the player with the most points scores them negatively,
+/// doubled for every Jungfrau. A Durchmarsch wins 120 for its player instead.
+pub fn settle_ramsch(outcome: &RamschOutcome) -> RamschSettlement {
+    let players = [Player::Declarer, Player::Left, Player::Right];
+
+    if let Some(&player) = players
+        .iter()
+        .find(|p| outcome.tricks[**p as usize] == NUMBER_OF_TRICKS)
+    {
+        let mut scores = [0; 3];
+        scores[player as usize] = DURCHMARSCH_VALUE;
+        return RamschSettlement {
+            losers: [false; 3],
+            jungfrau: 0,
+            durchmarsch: Some(player),
+            scores,
+        };
+    }
+
+    let max_points = *outcome.points.iter().max().unwrap();
+    let jungfrau = outcome.tricks.iter().filter(|&&t| t == 0).count() as u8;
+    let factor = 1 << jungfrau;
+
+    let mut losers = [false; 3];
+    let mut scores = [0; 3];
+    for p in players.iter() {
+        let i = *p as usize;
+        if outcome.points[i] == max_points {
+            losers[i] = true;
+            scores[i] = -(outcome.points[i] as i32) * factor;
+        }
+    }
+
+    RamschSettlement {
+        losers,
+        jungfrau,
+        durchmarsch: None,
+        scores,
+    }
+}
+
+/// Settles a finished game from its trick history.
+///
+/// `declarer_cards_with_skat` are the twelve cards the declarer held after the
+/// auction (hand plus Skat), `skat` are the two cards laid away at the end.
+pub fn settle_game(
+    contract: &Contract,
+    declarer_cards_with_skat: u32,
+    skat: u32,
+    tricks: &[Trick],
+    bid: u16,
+) -> Settlement {
+    let outcome = GameOutcome::from_tricks(tricks, skat);
+    settle(contract, declarer_cards_with_skat, &outcome, bid)
+}
+
+/// Settles a game from its outcome. A bid of 0 skips the overbid check.
+pub fn settle(
+    contract: &Contract,
+    declarer_cards_with_skat: u32,
+    outcome: &GameOutcome,
+    bid: u16,
+) -> Settlement {
+    if contract.game.is_null() {
+        return settle_null(contract, outcome, bid);
+    }
+
+    let won_card_play = outcome.declarer_points >= 61
+        && (!contract.schneider_announced || outcome.declarer_points >= 90)
+        && (!contract.schwarz_announced || outcome.declarer_tricks == Some(NUMBER_OF_TRICKS));
+
+    let matadors = count_matadors(declarer_cards_with_skat, contract);
+    let multiplier = matadors as u16 + 1 + game_levels(contract, outcome);
+    let base_value = contract.base_value();
+
+    finish(
+        won_card_play,
+        contract,
+        base_value,
+        matadors,
+        multiplier,
+        bid,
+    )
+}
+
+/// Scores of a settled game in the Seeger-Fabian system, indexed by
+/// `Player as usize`: the declarer scores the game plus 50 points if won,
+/// the doubled loss minus 50 if lost; then each defender gets 40 points.
+pub fn seeger_fabian(settlement: &Settlement) -> [i32; 3] {
+    if settlement.won {
+        [settlement.score + SEEGER_FABIAN_GAME_POINTS, 0, 0]
+    } else {
+        [
+            settlement.score - SEEGER_FABIAN_GAME_POINTS,
+            SEEGER_FABIAN_DEFENDER_BONUS,
+            SEEGER_FABIAN_DEFENDER_BONUS,
+        ]
+    }
+}
+
+/// Value of a won game without any extra levels, used to compare games before
+/// they are played.
+pub fn base_game_value(contract: &Contract, declarer_cards_with_skat: u32) -> u16 {
+    if contract.game.is_null() {
+        return contract.base_value();
+    }
+    let matadors = count_matadors(declarer_cards_with_skat, contract) as u16;
+    (matadors + 1) * contract.base_value()
+}
+
+/// Counts matadors "with" or "without": the unbroken run of top trumps the
+/// declarer holds (or misses), starting with the Jack of Clubs.
+pub fn count_matadors(cards: u32, contract: &Contract) -> u8 {
+    let trumps = match contract.game {
+        Game::Grand => JACKS,
+        Game::Suit => JACKS | contract.trump_suit,
+        _ => return 0,
+    };
+
+    let with = cards & JACKOFCLUBS != 0;
+    let mut matadors = 0;
+    let mut remaining = trumps;
+
+    while remaining != 0 {
+        let card = 1 << (31 - remaining.leading_zeros());
+        if (cards & card != 0) != with {
+            break;
+        }
+        matadors += 1;
+        remaining ^= card;
+    }
+    matadors
+}
+
+/// Rebuilds the tricks of a game played from the start, in playing order.
+pub fn collect_tricks(
+    played_cards: &[u32],
+    game: Game,
+    declarer_cards: u32,
+    left_cards: u32,
+    right_cards: u32,
+) -> Vec<Trick> {
+    played_cards
+        .chunks_exact(3)
+        .map(|trick| {
+            let cards = trick[0] | trick[1] | trick[2];
+            let trick_suit = get_suit_for_card(trick[0], game);
+            Trick {
+                cards,
+                winner: get_trick_winner(
+                    cards,
+                    trick_suit,
+                    game,
+                    declarer_cards,
+                    left_cards,
+                    right_cards,
+                ),
+            }
+        })
+        .collect()
+}
+
+fn settle_null(contract: &Contract, outcome: &GameOutcome, bid: u16) -> Settlement {
+    let won_card_play = outcome.declarer_tricks == Some(0);
+    finish(won_card_play, contract, contract.base_value(), 0, 1, bid)
+}
+
+fn game_levels(contract: &Contract, outcome: &GameOutcome) -> u16 {
+    let schneider = contract.schneider_announced || outcome.is_schneider();
+    let schwarz = contract.schwarz_announced || outcome.is_schwarz();
+
+    [
+        contract.hand,
+        schneider,
+        contract.schneider_announced,
+        schwarz,
+        contract.schwarz_announced,
+        contract.ouvert,
+    ]
+    .iter()
+    .filter(|&&level| level)
+    .count() as u16
+}
+
+fn finish(
+    won_card_play: bool,
+    contract: &Contract,
+    base_value: u16,
+    matadors: u8,
+    multiplier: u16,
+    bid: u16,
+) -> Settlement {
+    let mut game_value = base_value * multiplier;
+    let overbid = game_value < bid;
+
+    if overbid {
+        // The game counts as the lowest multiple of its base value reaching the bid.
+        game_value = bid.div_ceil(base_value) * base_value;
+    }
+
+    let multiplier = game_value / base_value;
+    game_value *= contract.doubling.factor();
+
+    let won = won_card_play && !overbid;
+    let score = if won {
+        game_value as i32
+    } else {
+        -2 * game_value as i32
+    };
+
+    Settlement {
+        won,
+        overbid,
+        matadors,
+        multiplier,
+        doubling: contract.doubling,
+        game_value,
+        score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::BitConverter;
+
+    fn outcome(points: u8, tricks: u8) -> GameOutcome {
+        GameOutcome {
+            declarer_points: points,
+            declarer_tricks: Some(tricks),
+        }
+    }
+
+    #[test]
+    fn test_matadors_with_and_without() {
+        let clubs = Contract::suit(CLUBS);
+        assert_eq!(count_matadors("CJ SJ HJ DJ CA CT".__bit(), &clubs), 6);
+        assert_eq!(count_matadors("CJ HJ CA".__bit(), &clubs), 1);
+        assert_eq!(count_matadors("HJ CA".__bit(), &clubs), 2);
+        assert_eq!(count_matadors("SJ".__bit(), &Contract::grand()), 1);
+        assert_eq!(count_matadors("C7".__bit(), &Contract::grand()), 4);
+    }
+
+    #[test]
+    fn test_won_suit_game_with_levels() {
+        // Spades, with 2, Hand, Schneider: 11 * (2 + 1 + 1 + 1) = 55
+        let contract = Contract::suit(SPADES).hand();
+        let s = settle(&contract, "CJ SJ SA".__bit(), &outcome(95, 8), 18);
+        assert!(s.won);
+        assert_eq!(s.game_value, 55);
+        assert_eq!(s.score, 55);
+    }
+
+    #[test]
+    fn test_ouvert_grand() {
+        // Grand Ouvert with 4: 24 * (4 + 1 + Hand, Schneider, angesagt,
+        // Schwarz, angesagt, Ouvert) = 24 * 11
+        let contract = Contract::grand().ouvert();
+        let s = settle(&contract, "CJ SJ HJ DJ".__bit(), &outcome(120, 10), 0);
+        assert!(s.won);
+        assert_eq!(s.game_value, 264);
+    }
+
+    #[test]
+    fn test_lost_game_counts_double() {
+        let contract = Contract::grand();
+        let s = settle(&contract, "CJ".__bit(), &outcome(60, 5), 18);
+        assert!(!s.won);
+        assert_eq!(s.score, -96);
+    }
+
+    #[test]
+    fn test_failed_schneider_announcement() {
+        // Clubs with 1, Hand, Schneider, Schneider angesagt: 12 * 5 = 60
+        let contract = Contract::suit(CLUBS).announce_schneider();
+        let s = settle(&contract, "CJ".__bit(), &outcome(85, 7), 18);
+        assert!(!s.won);
+        assert_eq!(s.game_value, 60);
+        assert_eq!(s.score, -120);
+    }
+
+    #[test]
+    fn test_overbid() {
+        // Bid 36, Clubs with 1 is only worth 24: lost as 36 (3 * 12), doubled.
+        let contract = Contract::suit(CLUBS);
+        let s = settle(&contract, "CJ".__bit(), &outcome(70, 6), 36);
+        assert!(s.overbid);
+        assert!(!s.won);
+        assert_eq!(s.game_value, 36);
+        assert_eq!(s.score, -72);
+    }
+
+    #[test]
+    fn test_seeger_fabian() {
+        let contract = Contract::grand();
+        let won = settle(&contract, "CJ".__bit(), &outcome(70, 6), 18);
+        assert_eq!(seeger_fabian(&won), [98, 0, 0]);
+        let lost = settle(&contract, "CJ".__bit(), &outcome(60, 5), 18);
+        assert_eq!(seeger_fabian(&lost), [-146, 40, 40]);
+    }
+
+    #[test]
+    fn test_null_variants() {
+        let won = settle(&Contract::new(Game::NullOuvert), 0, &outcome(0, 0), 23);
+        assert_eq!(won.score, 46);
+
+        let lost = settle(&Contract::new(Game::NullHand), 0, &outcome(0, 1), 18);
+        assert_eq!(lost.score, -70);
+    }
+
+    #[test]
+    fn test_kontra_and_re() {
+        // Grand with 1, won: 2 * 24 = 48, Kontra 96
+        let contract = Contract::grand().kontra();
+        let s = settle(&contract, "CJ HJ".__bit(), &outcome(70, 6), 48);
+        assert!(s.won);
+        assert_eq!(s.multiplier, 2);
+        assert_eq!(s.game_value, 96);
+
+        // Re quadruples the loss; the bid is checked against the plain value
+        let re = contract.re().unwrap();
+        let lost = settle(&re, "CJ HJ".__bit(), &outcome(50, 4), 48);
+        assert!(!lost.overbid);
+        assert_eq!(lost.score, -384);
+
+        let null = settle(&Contract::new(Game::Null).kontra(), 0, &outcome(0, 0), 23);
+        assert_eq!(null.score, 46);
+
+        assert!(Contract::grand().re().is_err());
+    }
+
+    #[test]
+    fn test_ramsch() {
+        let plain = settle_ramsch(&RamschOutcome {
+            points: [30, 50, 40],
+            tricks: [3, 4, 3],
+        });
+        assert_eq!(plain.losers, [false, true, false]);
+        assert_eq!(plain.scores, [0, -50, 0]);
+
+        let jungfrau = settle_ramsch(&RamschOutcome {
+            points: [0, 70, 50],
+            tricks: [0, 6, 4],
+        });
+        assert_eq!(jungfrau.jungfrau, 1);
+        assert_eq!(jungfrau.scores, [0, -140, 0]);
+
+        let tie = settle_ramsch(&RamschOutcome {
+            points: [20, 50, 50],
+            tricks: [2, 4, 4],
+        });
+        assert_eq!(tie.scores, [0, -50, -50]);
+
+        let durchmarsch = settle_ramsch(&RamschOutcome {
+            points: [0, 0, 120],
+            tricks: [0, 0, 10],
+        });
+        assert_eq!(durchmarsch.durchmarsch, Some(Player::Right));
+        assert_eq!(durchmarsch.scores, [0, 0, 120]);
+    }
+
+    #[test]
+    fn test_ramsch_skat_to_last_trick() {
+        let tricks = [
+            Trick {
+                cards: "CA CT SA".__bit(),
+                winner: Player::Left,
+            },
+            Trick {
+                cards: "HA HT DA".__bit(),
+                winner: Player::Right,
+            },
+        ];
+        let o = RamschOutcome::from_tricks(&tricks, "DT D7".__bit());
+        assert_eq!(o.points, [0, 32, 42]);
+        assert_eq!(o.tricks, [0, 1, 1]);
+    }
+
+    #[test]
+    fn test_settle_from_tricks() {
+        let tricks = [
+            Trick {
+                cards: "CA CT SA".__bit(),
+                winner: Player::Declarer,
+            },
+            Trick {
+                cards: "HA HT DA".__bit(),
+                winner: Player::Left,
+            },
+        ];
+        let o = GameOutcome::from_tricks(&tricks, "DT D7".__bit());
+        assert_eq!(o.declarer_points, 42);
+        assert_eq!(o.declarer_tricks, Some(1));
+    }
+}
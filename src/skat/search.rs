@@ -1,28 +1,47 @@
-﻿//! # Search
+//! # Search
 //!
 //! Implementation of the Alpha-Beta pruning algorithm.
 
 use crate::skat::context::GameContext;
+use crate::skat::control::SearchControl;
 use crate::skat::counters::Counters; // Moving this later
 use crate::skat::defs::{Game, Player};
+use crate::skat::endgame::EndgameTable;
 use crate::skat::position::Position;
 use crate::skat::rules::get_sorted_by_value;
-use crate::skat::tt::{TranspositionFlag, TranspositionTable};
+use crate::skat::tt::{
+    SharedTranspositionTable, TranspositionEntry, TranspositionFlag, TranspositionStore,
+};
+
+use rayon::prelude::*;
+use rayon::ThreadPool;
 
 use std::cmp;
+use std::collections::HashMap;
 
 enum GameStrategy {
     Standard,
     Null,
+    /// Paranoid Ramsch: the player in the declarer seat minimizes his points,
+    /// the other two maximize them.
+    Ramsch,
 }
 
 impl GameStrategy {
+    fn for_game(game: Game) -> GameStrategy {
+        match game {
+            g if g.is_null() => GameStrategy::Null,
+            Game::Ramsch => GameStrategy::Ramsch,
+            _ => GameStrategy::Standard,
+        }
+    }
+
     fn evaluate(&self, a: u8, b: u8, player: Player) -> bool {
         match self {
             GameStrategy::Standard => {
                 (player == Player::Declarer && a > b) || (player != Player::Declarer && a < b)
             }
-            GameStrategy::Null => {
+            GameStrategy::Null | GameStrategy::Ramsch => {
                 (player == Player::Declarer && a < b) || (player != Player::Declarer && a > b)
             }
         }
@@ -44,30 +63,240 @@ impl GameStrategy {
                     0
                 }
             }
+            GameStrategy::Ramsch => {
+                if player == Player::Declarer {
+                    120
+                } else {
+                    0
+                }
+            }
+        }
+    }
+}
+
+/// Horizon of a search that is not limited: the hand is searched to the end.
+pub const NO_HORIZON: u8 = u8::MAX;
+
+/// A principal variation: the value of a position and the optimal cards from
+/// there on, up to the end of the hand or the search horizon.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Variation {
+    /// Completed tricks (counted like `Position::declarer_tricks`) at which the
+    /// search stopped, `NO_HORIZON` for a complete search.
+    pub horizon: u8,
+    pub value: u8,
+    pub cards: Vec<u32>,
+}
+
+impl Variation {
+    pub fn best_card(&self) -> u32 {
+        self.cards.first().copied().unwrap_or(0)
+    }
+
+    /// True if the variation reaches the end of the hand, i.e. the value is exact.
+    pub fn is_complete(&self) -> bool {
+        self.horizon == NO_HORIZON
+    }
+}
+
+/// How the moves of a node are ordered before they are searched.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum MoveOrdering {
+    /// Card value order (`get_sorted_by_value`), the best card of the
+    /// transposition table first.
+    Static,
+    /// Transposition table card first, then the static order re-sorted by
+    /// the history of cutoffs, killer moves of the ply breaking ties.
+    ///
+    /// Null games keep the static order, which already cuts at the first move
+    /// in nearly all nodes.
+    #[default]
+    Dynamic,
+}
+
+/// Plies of a hand, indexed by the number of played cards.
+const MAX_PLIES: usize = 33;
+
+/// Killer moves and history table learned during a search.
+///
+/// Killers are the last two cards that caused a cutoff at a ply (i.e. at the
+/// same trick and seat in the trick), the history counts cutoffs per player
+/// and card, weighted by the square of the cards left in the hand.
+///
+/// On the problem suite of `tests/tests_move_ordering.rs` killers ranked
+/// above the history searched more nodes than the history alone, so they
+/// only break ties.
+#[derive(Clone)]
+pub struct MoveOrder {
+    ordering: MoveOrdering,
+    killers: [[u32; 2]; MAX_PLIES],
+    history: [[u32; 32]; 3],
+}
+
+impl MoveOrder {
+    pub fn new(ordering: MoveOrdering, game: Game) -> Self {
+        MoveOrder {
+            ordering: if game.is_null() {
+                MoveOrdering::Static
+            } else {
+                ordering
+            },
+            killers: [[0; 2]; MAX_PLIES],
+            history: [[0; 32]; 3],
+        }
+    }
+
+    pub fn ordering(&self) -> MoveOrdering {
+        self.ordering
+    }
+
+    /// Orders the moves of `position`, `front_card` (the transposition table
+    /// or principal variation card) first.
+    fn sort(&self, position: &Position, moves: &mut [u32], front_card: u32) {
+        match self.ordering {
+            MoveOrdering::Static => {
+                if front_card > 0 {
+                    if let Some(i) = moves.iter().position(|&mov| mov == front_card) {
+                        moves.swap(0, i);
+                    }
+                }
+            }
+            MoveOrdering::Dynamic => {
+                let killers = &self.killers[ply(position)];
+                let history = &self.history[position.player as usize];
+                // Stable: equal scores keep the static order.
+                moves.sort_by_key(|&mov| {
+                    cmp::Reverse(if mov == front_card {
+                        u32::MAX
+                    } else {
+                        let killer = if mov == killers[0] {
+                            2
+                        } else if mov == killers[1] {
+                            1
+                        } else {
+                            0
+                        };
+                        (history[mov.trailing_zeros() as usize] << 2) | killer
+                    })
+                });
+            }
+        }
+    }
+
+    fn is_killer(&self, position: &Position, card: u32) -> bool {
+        self.ordering == MoveOrdering::Dynamic && self.killers[ply(position)].contains(&card)
+    }
+
+    /// Learns from `card` causing a cutoff at `position`.
+    fn record_cutoff(&mut self, position: &Position, card: u32) {
+        if self.ordering == MoveOrdering::Static {
+            return;
+        }
+
+        let killers = &mut self.killers[ply(position)];
+        if killers[0] != card {
+            killers[1] = killers[0];
+            killers[0] = card;
+        }
+
+        let cards_left = position.player_cards.count_ones();
+        let entry = &mut self.history[position.player as usize][card.trailing_zeros() as usize];
+        // Kept below 2^30, the sort key holds the killer rank in the low bits.
+        *entry = (*entry + cards_left * cards_left).min(1 << 30);
+    }
+
+    /// Counts the cutoff of the `i`-th searched move at `position`.
+    fn count_cutoff(&self, position: &Position, card: u32, i: usize, cnt: &mut Counters) {
+        cnt.inc_breaks();
+        if i == 0 {
+            cnt.inc_first_move_breaks();
+        }
+        if self.is_killer(position, card) {
+            cnt.inc_killer_breaks();
         }
     }
 }
 
-pub fn search(
+#[inline(always)]
+fn ply(position: &Position) -> usize {
+    position.played_cards.count_ones() as usize
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn search<T: TranspositionStore>(
     game_context: &GameContext,
     position: &Position,
-    tt: &mut TranspositionTable,
+    tt: &mut T,
+    order: &mut MoveOrder,
+    control: &SearchControl,
+    cnt: &mut Counters,
+    alpha: u8,
+    beta: u8,
+) -> (u32, u8) {
+    search_to_horizon(
+        game_context,
+        position,
+        tt,
+        order,
+        control,
+        cnt,
+        alpha,
+        beta,
+        NO_HORIZON,
+        &[],
+    )
+}
+
+/// Alpha-beta search that stops at trick starts once `horizon` tricks are
+/// completed. Positions at the horizon are valued by the points won so far.
+///
+/// `pv_hint` is the principal variation of a previous (shallower) search; its
+/// cards are tried first as long as the searched line follows it.
+///
+/// Values of a limited search depend on the horizon, so it must not share a
+/// transposition table with searches to a different horizon.
+///
+/// Once `control` is stopped the search unwinds without writing to `tt`; the
+/// returned value is meaningless then.
+#[allow(clippy::too_many_arguments)]
+pub fn search_to_horizon<T: TranspositionStore>(
+    game_context: &GameContext,
+    position: &Position,
+    tt: &mut T,
+    order: &mut MoveOrder,
+    control: &SearchControl,
     cnt: &mut Counters,
     mut alpha: u8,
     mut beta: u8,
+    horizon: u8,
+    pv_hint: &[u32],
 ) -> (u32, u8) {
     cnt.inc_iters();
 
-    let strategy = match game_context.game_type {
-        Game::Null => GameStrategy::Null,
-        _ => GameStrategy::Standard,
-    };
+    // INTERRUPTION
+    if control.should_stop(cnt.iters) {
+        return (0, position.declarer_points);
+    }
+
+    let strategy = GameStrategy::for_game(game_context.game_type);
 
     // TERMINATION
     if let Some(x) = apply_termination_criteria(game_context, position, alpha, beta) {
         return (0, x);
     }
 
+    // HORIZON
+    if is_at_horizon(position, horizon) {
+        return (0, position.declarer_points);
+    }
+
+    // ENDGAME TABLE (exact values, only if the horizon is beyond the end)
+    if let Some(endgame) = tt.endgame() {
+        if EndgameTable::covers(game_context, position) && ends_before_horizon(position, horizon) {
+            return endgame.lookup(game_context, position, cnt);
+        }
+    }
+
     let mut optimized_value: (u32, u8) = (0, strategy.initial_value(position.player));
     let mut tt_best_card = 0;
 
@@ -87,24 +316,41 @@ pub fn search(
     let moves_word = position.get_reduced_moves(game_context);
     let (mut moves, n) = get_sorted_by_value(moves_word);
 
-    // Put best card from TT to front (PV-Move)
-    if tt_best_card > 0 {
-        for i in 0..n {
-            if moves[i] == tt_best_card {
-                moves.swap(0, i);
-                break;
-            }
-        }
-    }
+    // Put best card from TT (or else from the previous principal variation) to front (PV-Move)
+    let front_card = if tt_best_card > 0 {
+        tt_best_card
+    } else {
+        pv_hint.first().copied().unwrap_or(0)
+    };
+    order.sort(position, &mut moves[0..n], front_card);
 
     // Set dummy card if no optimization of start value possible
     optimized_value.0 = moves[0];
 
     // BRANCHING LOOP
-    for mov in &moves[0..n] {
+    for (i, mov) in moves[0..n].iter().enumerate() {
         let child_position = position.make_move(*mov, game_context);
 
-        let child_value = search(game_context, &child_position, tt, cnt, alpha, beta);
+        let child_hint = match pv_hint.split_first() {
+            Some((card, rest)) if card == mov => rest,
+            _ => &[],
+        };
+        let child_value = search_to_horizon(
+            game_context,
+            &child_position,
+            tt,
+            order,
+            control,
+            cnt,
+            alpha,
+            beta,
+            horizon,
+            child_hint,
+        );
+
+        if control.is_stopped() {
+            return optimized_value;
+        }
 
         // Optimize value
         if strategy.evaluate(child_value.1, optimized_value.1, position.player) {
@@ -120,7 +366,8 @@ pub fn search(
             child_value.1,
             game_context.game_type,
         ) {
-            cnt.inc_breaks();
+            order.count_cutoff(position, *mov, i, cnt);
+            order.record_cutoff(position, *mov);
             break;
         }
     }
@@ -130,6 +377,289 @@ pub fn search(
     optimized_value
 }
 
+/// Parallel alpha-beta search in the manner of Young Brothers Wait: the first
+/// (best ordered) move is searched alone to narrow the window, then its
+/// brothers are searched in parallel on `pool`. All threads share `tt`.
+///
+/// The eldest brother is split again down to `split_plies` plies; below that
+/// and for the younger brothers the sequential `search` is used.
+#[allow(clippy::too_many_arguments)]
+pub fn search_parallel(
+    game_context: &GameContext,
+    position: &Position,
+    tt: &SharedTranspositionTable,
+    pool: &ThreadPool,
+    order: &mut MoveOrder,
+    control: &SearchControl,
+    cnt: &mut Counters,
+    mut alpha: u8,
+    mut beta: u8,
+    split_plies: u8,
+) -> (u32, u8) {
+    let mut shared = tt;
+
+    if split_plies == 0 {
+        return search(
+            game_context,
+            position,
+            &mut shared,
+            order,
+            control,
+            cnt,
+            alpha,
+            beta,
+        );
+    }
+
+    cnt.inc_iters();
+
+    // INTERRUPTION
+    if control.should_stop(cnt.iters) {
+        return (0, position.declarer_points);
+    }
+
+    let strategy = GameStrategy::for_game(game_context.game_type);
+
+    // TERMINATION
+    if let Some(x) = apply_termination_criteria(game_context, position, alpha, beta) {
+        return (0, x);
+    }
+
+    // TRANSPOSITION TABLE LOOKUP
+    let mut tt_best_card = 0;
+    if let Some(x) = transposition_table_lookup(
+        &shared,
+        position,
+        &mut alpha,
+        &mut beta,
+        cnt,
+        &mut tt_best_card,
+    ) {
+        return x;
+    }
+
+    let alphaorig = alpha;
+    let betaorig = beta;
+
+    // MOVE GENERATION & SORTING
+    let moves_word = position.get_reduced_moves(game_context);
+    let (mut moves, n) = get_sorted_by_value(moves_word);
+    order.sort(position, &mut moves[0..n], tt_best_card);
+
+    // ELDEST BROTHER
+    let eldest_position = position.make_move(moves[0], game_context);
+    let eldest_value = search_parallel(
+        game_context,
+        &eldest_position,
+        tt,
+        pool,
+        order,
+        control,
+        cnt,
+        alpha,
+        beta,
+        split_plies - 1,
+    );
+
+    if control.is_stopped() {
+        return eldest_value;
+    }
+
+    let mut optimized_value: (u32, u8) = (moves[0], strategy.initial_value(position.player));
+    if strategy.evaluate(eldest_value.1, optimized_value.1, position.player) {
+        optimized_value.1 = eldest_value.1;
+    }
+
+    if shrink_alpha_beta_window(
+        position.player,
+        &mut alpha,
+        &mut beta,
+        eldest_value.1,
+        game_context.game_type,
+    ) {
+        order.count_cutoff(position, moves[0], 0, cnt);
+        order.record_cutoff(position, moves[0]);
+    } else {
+        // YOUNGER BROTHERS, each thread learning its own killers and history
+        let ordering = order.ordering();
+        let brothers: Vec<(u32, u8, Counters)> = pool.install(|| {
+            moves[1..n]
+                .par_iter()
+                .map(|mov| {
+                    let mut thread_tt = tt;
+                    let mut thread_order = MoveOrder::new(ordering, game_context.game_type);
+                    let mut thread_cnt = Counters::new();
+                    let child_position = position.make_move(*mov, game_context);
+                    let (_, value) = search(
+                        game_context,
+                        &child_position,
+                        &mut thread_tt,
+                        &mut thread_order,
+                        control,
+                        &mut thread_cnt,
+                        alpha,
+                        beta,
+                    );
+                    (*mov, value, thread_cnt)
+                })
+                .collect()
+        });
+
+        // Evaluated in move order, so ties resolve like in the sequential search
+        for (mov, value, thread_cnt) in brothers {
+            cnt.add(thread_cnt);
+            if strategy.evaluate(value, optimized_value.1, position.player) {
+                optimized_value = (mov, value);
+            }
+        }
+
+        if control.is_stopped() {
+            return optimized_value;
+        }
+    }
+
+    transposition_table_write(
+        &mut shared,
+        position,
+        alphaorig,
+        betaorig,
+        optimized_value,
+        cnt,
+    );
+
+    optimized_value
+}
+
+/// Collects the principal variation of `position` by following the best card
+/// of full-window searches ply by ply. After the first search the
+/// transposition table answers most of the follow-up searches.
+///
+/// An interrupted search (see `control`) returns the cards found so far.
+#[allow(clippy::too_many_arguments)]
+pub fn search_variation<T: TranspositionStore>(
+    game_context: &GameContext,
+    position: &Position,
+    tt: &mut T,
+    order: &mut MoveOrder,
+    control: &SearchControl,
+    cnt: &mut Counters,
+    horizon: u8,
+    pv_hint: &[u32],
+) -> Variation {
+    let mut variation = Variation {
+        horizon,
+        value: position.declarer_points,
+        cards: Vec::new(),
+    };
+
+    let mut current = *position;
+    let mut hint = pv_hint;
+
+    while current.player_cards != 0 && !is_at_horizon(&current, horizon) {
+        let (mut card, value) = search_to_horizon(
+            game_context,
+            &current,
+            tt,
+            order,
+            control,
+            cnt,
+            0,
+            120,
+            horizon,
+            hint,
+        );
+
+        if control.is_stopped() {
+            break;
+        }
+
+        if variation.cards.is_empty() {
+            variation.value = value;
+        }
+
+        // Decided positions return no card, all remaining cards are equally good.
+        if card == 0 {
+            card = get_sorted_by_value(current.get_reduced_moves(game_context)).0[0];
+        }
+
+        hint = match hint.split_first() {
+            Some((hint_card, rest)) if *hint_card == card => rest,
+            _ => &[],
+        };
+
+        variation.cards.push(card);
+        current = current.make_move(card, game_context);
+    }
+
+    variation
+}
+
+#[inline(always)]
+fn ends_before_horizon(position: &Position, horizon: u8) -> bool {
+    let tricks_left = position.get_all_unplayed_cards().count_ones().div_ceil(3) as u8;
+    position.declarer_tricks + position.team_tricks + tricks_left <= horizon
+}
+
+#[inline(always)]
+fn is_at_horizon(position: &Position, horizon: u8) -> bool {
+    position.trick_cards_count == 0 && position.declarer_tricks + position.team_tricks >= horizon
+}
+
+/// Decides whether the declarer can take all remaining tricks (Schwarz).
+///
+/// Schwarz depends on trick ownership rather than points, so it cannot be
+/// expressed as a point window of `search`. As long as the team has not won a
+/// trick, the declarer leads every trick and the position is fully determined
+/// by the played cards, which is used as the memo key at trick boundaries.
+pub fn search_schwarz(
+    game_context: &GameContext,
+    position: &Position,
+    memo: &mut HashMap<u32, bool>,
+    cnt: &mut Counters,
+) -> (u32, bool) {
+    cnt.inc_iters();
+
+    if game_context.game_type.is_null() || position.team_tricks > 0 {
+        return (0, false);
+    }
+
+    if position.get_all_unplayed_cards() == 0 {
+        return (0, true);
+    }
+
+    let at_trick_start = position.trick_cards_count == 0;
+    if at_trick_start {
+        if let Some(x) = memo.get(&position.played_cards) {
+            cnt.inc_reads();
+            return (0, *x);
+        }
+    }
+
+    let moves_word = position.get_reduced_moves(game_context);
+    let (moves, n) = get_sorted_by_value(moves_word);
+
+    let is_declarer = position.player == Player::Declarer;
+    let mut optimized_value: (u32, bool) = (moves[0], !is_declarer);
+
+    for mov in &moves[0..n] {
+        let child_position = position.make_move(*mov, game_context);
+        let child_value = search_schwarz(game_context, &child_position, memo, cnt);
+
+        if child_value.1 == is_declarer {
+            optimized_value = (*mov, child_value.1);
+            cnt.inc_breaks();
+            break;
+        }
+    }
+
+    if at_trick_start {
+        memo.insert(position.played_cards, optimized_value.1);
+        cnt.inc_writes();
+    }
+
+    optimized_value
+}
+
 #[inline(always)]
 fn apply_termination_criteria(
     context: &GameContext,
@@ -142,7 +672,7 @@ fn apply_termination_criteria(
     }
 
     match context.game_type {
-        Game::Null => {
+        g if g.is_null() => {
             if position.declarer_points > 0 {
                 return Some(1);
             }
@@ -162,8 +692,8 @@ fn apply_termination_criteria(
 }
 
 #[inline(always)]
-fn transposition_table_lookup(
-    tt: &TranspositionTable,
+fn transposition_table_lookup<T: TranspositionStore>(
+    tt: &T,
     position: &Position,
     alpha: &mut u8,
     beta: &mut u8,
@@ -176,7 +706,7 @@ fn transposition_table_lookup(
         return None;
     }
 
-    if let Some(tt_entry) = tt.read(position, cnt) {
+    if let Some(tt_entry) = tt.probe(position, cnt) {
         *tt_best_card = tt_entry.bestcard;
         // Stored value is REMAINING value (i16).
         // Convert i16 back to u8.
@@ -205,8 +735,8 @@ fn transposition_table_lookup(
 }
 
 #[inline(always)]
-fn transposition_table_write(
-    tt: &mut TranspositionTable,
+fn transposition_table_write<T: TranspositionStore>(
+    tt: &mut T,
     position: &Position,
     alphaorig: u8,
     betaorig: u8,
@@ -215,7 +745,10 @@ fn transposition_table_write(
 ) {
     if is_tt_compatible(position) {
         cnt.inc_writes();
-        tt.write(position, position.get_hash(), alphaorig, betaorig, value);
+        tt.store(
+            position.get_hash(),
+            TranspositionEntry::from_search(position, alphaorig, betaorig, value),
+        );
     }
 }
 
@@ -233,7 +766,7 @@ fn shrink_alpha_beta_window(
     game: Game,
 ) -> bool {
     match game {
-        Game::Null => match player {
+        g if g.is_null() || g == Game::Ramsch => match player {
             Player::Declarer => {
                 *beta = cmp::min(*beta, child_state_value);
                 if *beta <= *alpha {
@@ -270,10 +803,12 @@ fn shrink_alpha_beta_window(
 // OPTIMUM SEARCH (Fast Win / Slow Loss)
 // -----------------------------------------------------------------------------
 
-pub fn search_optimum(
+#[allow(clippy::too_many_arguments)]
+pub fn search_optimum<T: TranspositionStore>(
     game_context: &GameContext,
     position: &Position,
-    tt: &mut TranspositionTable,
+    tt: &mut T,
+    order: &mut MoveOrder,
     cnt: &mut Counters,
     mut alpha: i16,
     mut beta: i16,
@@ -288,7 +823,7 @@ pub fn search_optimum(
     }
 
     // Null Game Early Termination: If Declarer takes a trick (points > 0), they lose immediately.
-    if game_context.game_type == Game::Null && position.declarer_points > 0 {
+    if game_context.game_type.is_null() && position.declarer_points > 0 {
         return (0, evaluate_terminal_node(game_context, position, depth));
     }
 
@@ -315,14 +850,7 @@ pub fn search_optimum(
     let (mut moves, n) = get_sorted_by_value(moves_word);
 
     // TT Move Ordering
-    if tt_best_card > 0 {
-        for i in 0..n {
-            if moves[i] == tt_best_card {
-                moves.swap(0, i);
-                break;
-            }
-        }
-    }
+    order.sort(position, &mut moves[0..n], tt_best_card);
 
     let mut best_move = moves[0];
     let mut best_score;
@@ -333,12 +861,13 @@ pub fn search_optimum(
         i16::MAX - 1
     };
 
-    for mov in &moves[0..n] {
+    for (i, mov) in moves[0..n].iter().enumerate() {
         let child_position = position.make_move(*mov, game_context);
         let (_, child_score) = search_optimum(
             game_context,
             &child_position,
             tt,
+            order,
             cnt,
             alpha,
             beta,
@@ -352,6 +881,8 @@ pub fn search_optimum(
             }
             alpha = cmp::max(alpha, best_score);
             if alpha >= beta {
+                order.count_cutoff(position, *mov, i, cnt);
+                order.record_cutoff(position, *mov);
                 break; // Beta Cutoff
             }
         } else {
@@ -361,6 +892,8 @@ pub fn search_optimum(
             }
             beta = cmp::min(beta, best_score);
             if beta <= alpha {
+                order.count_cutoff(position, *mov, i, cnt);
+                order.record_cutoff(position, *mov);
                 break; // Alpha Cutoff
             }
         }
@@ -379,8 +912,8 @@ pub fn search_optimum(
 }
 
 #[inline(always)]
-fn transposition_table_lookup_optimum(
-    tt: &TranspositionTable,
+fn transposition_table_lookup_optimum<T: TranspositionStore>(
+    tt: &T,
     position: &Position,
     alpha: &mut i16,
     beta: &mut i16,
@@ -391,7 +924,7 @@ fn transposition_table_lookup_optimum(
         return None;
     }
 
-    if let Some(tt_entry) = tt.read_optimum(position, cnt) {
+    if let Some(tt_entry) = tt.probe(position, cnt) {
         *tt_best_card = tt_entry.bestcard;
         let value = tt_entry.value; // Store absolute i16 score
         let bestcard = tt_entry.bestcard;
@@ -417,8 +950,8 @@ fn transposition_table_lookup_optimum(
 }
 
 #[inline(always)]
-fn transposition_table_write_optimum(
-    tt: &mut TranspositionTable,
+fn transposition_table_write_optimum<T: TranspositionStore>(
+    tt: &mut T,
     position: &Position,
     alphaorig: i16,
     betaorig: i16,
@@ -427,7 +960,10 @@ fn transposition_table_write_optimum(
 ) {
     if is_tt_compatible(position) {
         cnt.inc_writes();
-        tt.write_optimum(position, position.get_hash(), alphaorig, betaorig, value);
+        tt.store(
+            position.get_hash(),
+            TranspositionEntry::from_optimum(position, alphaorig, betaorig, value),
+        );
     }
 }
 
@@ -436,7 +972,9 @@ fn evaluate_terminal_node(context: &GameContext, position: &Position, depth: i16
     let max_depth = 40; // Max plies (3*10=30, plus safety)
 
     match context.game_type {
-        Game::Null => {
+        // Fewer points are better; depth doesn't matter, the game is always played out.
+        Game::Ramsch => -declarer_points,
+        g if g.is_null() => {
             // Declarer Win condition: No tricks taken? Or points?
             // In Null, `declarer_points` > 0 means LOST.
             let lost = declarer_points > 0;
@@ -0,0 +1,167 @@
+//! # Solved Positions
+//!
+//! Persistent store of exactly solved positions, keyed by the full state key of
+//! a `TranspositionEntry`. Unlike the transposition table it never evicts an
+//! entry: it is harvested from the table after a search and seeded into the
+//! table before the next one, also across processes via its binary file.
+
+use crate::skat::position::Position;
+use crate::skat::tt::{
+    read_entries, write_entries, TableKey, TranspositionEntry, TranspositionFlag,
+};
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+
+const SOLVED_FILE_MAGIC: &[u8; 4] = b"SKSV";
+
+/// Full state key of a position, see `TranspositionEntry::matches`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+struct StateKey {
+    player: u8,
+    declarer_cards: u32,
+    left_cards: u32,
+    right_cards: u32,
+    trick_cards: u32,
+}
+
+impl StateKey {
+    fn of_entry(entry: &TranspositionEntry) -> StateKey {
+        StateKey {
+            player: entry.player as u8,
+            declarer_cards: entry.declarer_cards,
+            left_cards: entry.left_cards,
+            right_cards: entry.right_cards,
+            trick_cards: entry.trick_cards,
+        }
+    }
+
+    fn of_position(position: &Position) -> StateKey {
+        StateKey {
+            player: position.player as u8,
+            declarer_cards: position.declarer_cards,
+            left_cards: position.left_cards,
+            right_cards: position.right_cards,
+            trick_cards: position.trick_cards,
+        }
+    }
+}
+
+pub struct SolvedStore {
+    key: TableKey,
+    positions: HashMap<StateKey, TranspositionEntry>,
+}
+
+impl SolvedStore {
+    pub fn new(key: TableKey) -> Self {
+        Self {
+            key,
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn key(&self) -> TableKey {
+        self.key
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Best card and value of a solved position (declarer points at the end
+    /// of the game, like `search`).
+    pub fn get(&self, position: &Position) -> Option<(u32, u8)> {
+        self.positions
+            .get(&StateKey::of_position(position))
+            .map(|entry| (entry.bestcard, entry.value as u8 + position.declarer_points))
+    }
+
+    /// Adds the exact entries of a table, returns the number of new positions.
+    pub fn harvest<'a, I>(&mut self, entries: I) -> usize
+    where
+        I: IntoIterator<Item = &'a TranspositionEntry>,
+    {
+        let before = self.positions.len();
+        for entry in entries {
+            if entry.occupied && entry.flag == TranspositionFlag::Exact {
+                self.positions.insert(StateKey::of_entry(entry), *entry);
+            }
+        }
+        self.positions.len() - before
+    }
+
+    /// Exact entries to seed a transposition table with.
+    pub fn entries(&self) -> impl Iterator<Item = &TranspositionEntry> {
+        self.positions.values()
+    }
+
+    pub fn save_to_file(&self, path: &str) -> io::Result<()> {
+        self.save(std::fs::File::create(path)?)
+    }
+
+    pub fn load_from_file(key: TableKey, path: &str) -> io::Result<SolvedStore> {
+        SolvedStore::load(key, std::fs::File::open(path)?)
+    }
+
+    pub fn save<W: Write>(&self, writer: W) -> io::Result<()> {
+        let entries: Vec<TranspositionEntry> = self.positions.values().copied().collect();
+        write_entries(SOLVED_FILE_MAGIC, self.key, &entries, writer)
+    }
+
+    /// Reads a store written by `save`. Stale versions and stores of other
+    /// games are rejected.
+    pub fn load<R: Read>(key: TableKey, reader: R) -> io::Result<SolvedStore> {
+        let mut store = SolvedStore::new(key);
+        store.harvest(&read_entries(SOLVED_FILE_MAGIC, key, reader)?);
+        Ok(store)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skat::builder::GameContextBuilder;
+    use crate::skat::defs::{Game, Player};
+
+    #[test]
+    fn test_harvest_save_and_load() {
+        let context = GameContextBuilder::new_grand()
+            .cards(Player::Declarer, "CJ SA")
+            .cards(Player::Left, "SJ ST")
+            .cards(Player::Right, "HJ SK")
+            .turn(Player::Declarer)
+            .build();
+        let position = context.create_initial_position();
+        let key = TableKey::of(&context);
+
+        let value = position.declarer_points + 5;
+        let exact = TranspositionEntry::from_search(&position, 0, 120, (1, value));
+        let bound = TranspositionEntry::from_search(&position, value, 120, (1, value));
+
+        let mut store = SolvedStore::new(key);
+        assert_eq!(store.harvest(&[bound]), 0);
+        assert_eq!(store.harvest(&[exact, exact]), 1);
+        assert_eq!(store.get(&position), Some((1, value)));
+
+        let mut file = Vec::new();
+        store.save(&mut file).unwrap();
+        assert_eq!(file.len(), 21 + 25);
+
+        let loaded = SolvedStore::load(key, file.as_slice()).unwrap();
+        assert_eq!(loaded.get(&position), Some((1, value)));
+
+        // Other game or version
+        let suit = TableKey {
+            game: Game::Suit,
+            skat: 0,
+        };
+        assert!(SolvedStore::load(suit, file.as_slice()).is_err());
+
+        file[4] += 1;
+        assert!(SolvedStore::load(key, file.as_slice()).is_err());
+    }
+}
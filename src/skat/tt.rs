@@ -2,11 +2,31 @@
 //!
 //! A hash map to store visited game positions, preventing redundant calculations.
 //! Stores exact scores or alpha/beta bounds.
+//!
+//! The table size is chosen at runtime and rounded down to a power of two, so
+//! that a slot is found by masking the position hash. Slots are grouped into
+//! buckets of `BUCKET_SIZE` entries; a full bucket evicts an entry according to
+//! the `ReplacementPolicy`.
+//!
+//! `TranspositionTable` is owned by a single search, `SharedTranspositionTable`
+//! is sharded behind locks and used by several search threads at once.
+//!
+//! Table contents can be saved to and loaded from a compact binary format with
+//! a version header, see `TableKey` for which games can share a file.
 
 use crate::consts::general::TT_SIZE;
+use crate::skat::context::GameContext;
 use crate::skat::counters::Counters;
-use crate::skat::defs::Player;
+use crate::skat::defs::{Game, Player};
+use crate::skat::endgame::EndgameTable;
 use crate::skat::position::Position;
+use crate::skat::rules::{get_hash, get_mapped_hash};
+
+use std::io::{self, Read, Write};
+use std::sync::Mutex;
+
+/// Number of entries sharing one hash slot.
+pub const BUCKET_SIZE: usize = 4;
 
 // -----------------------------------------------------------------------------
 // TT FLAG
@@ -19,6 +39,48 @@ pub enum TranspositionFlag {
     Lower,
 }
 
+// -----------------------------------------------------------------------------
+// REPLACEMENT POLICY
+// -----------------------------------------------------------------------------
+
+/// Decides which entry of a full bucket is overwritten by a new position.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ReplacementPolicy {
+    /// Keeps the entries with the most cards left, as they save the largest
+    /// searches. Bounds are evicted before exact values of the same depth.
+    DepthPreferred,
+    /// Evicts entries of earlier searches (see `TranspositionTable::new_search`)
+    /// first, then like `DepthPreferred`.
+    AgePreferred,
+}
+
+impl std::str::FromStr for ReplacementPolicy {
+    type Err = &'static str;
+
+    fn from_str(policy: &str) -> Result<ReplacementPolicy, &'static str> {
+        match policy.to_lowercase().as_str() {
+            "depth" => Ok(ReplacementPolicy::DepthPreferred),
+            "age" => Ok(ReplacementPolicy::AgePreferred),
+            _ => Err("Unknown replacement policy, use 'depth' or 'age'"),
+        }
+    }
+}
+
+impl ReplacementPolicy {
+    /// Keep-priority of an occupied entry; the lowest one of a bucket is evicted.
+    fn priority(&self, entry: &TranspositionEntry, age: u8) -> (bool, u8, bool) {
+        let is_current = match self {
+            ReplacementPolicy::DepthPreferred => true,
+            ReplacementPolicy::AgePreferred => entry.age == age,
+        };
+        (
+            is_current,
+            entry.depth,
+            entry.flag == TranspositionFlag::Exact,
+        )
+    }
+}
+
 // -----------------------------------------------------------------------------
 // TT ENTRY
 // -----------------------------------------------------------------------------
@@ -41,54 +103,32 @@ pub struct TranspositionEntry {
     // for alpha-beta functions
     pub flag: TranspositionFlag,
     pub bestcard: u32,
+
+    // for replacement: cards left in the hands and search generation
+    pub depth: u8,
+    pub age: u8,
 }
 
 impl TranspositionEntry {
-    pub fn matches(&self, position: &Position) -> bool {
-        self.player == position.player
-            && self.left_cards == position.left_cards
-            && self.right_cards == position.right_cards
-            && self.declarer_cards == position.declarer_cards
-            && self.trick_cards == position.trick_cards
+    pub fn empty() -> Self {
+        TranspositionEntry {
+            occupied: false,
+            player: Player::Declarer, // Default
+            left_cards: 0,
+            right_cards: 0,
+            declarer_cards: 0,
+            trick_cards: 0,
+            value: 0,
+            flag: TranspositionFlag::Exact,
+            bestcard: 0,
+            depth: 0,
+            age: 0,
+        }
     }
-}
-
-// -----------------------------------------------------------------------------
-// TT TABLE
-// -----------------------------------------------------------------------------
-
-#[derive(Clone)]
-pub struct TranspositionTable {
-    pub data: Vec<TranspositionEntry>,
-}
 
-impl TranspositionTable {
-    pub fn new() -> Self {
-        let mut data = Vec::with_capacity(TT_SIZE);
-        for _ in 0..TT_SIZE {
-            data.push(TranspositionEntry {
-                occupied: false,
-                player: Player::Declarer, // Default
-                left_cards: 0,
-                right_cards: 0,
-                declarer_cards: 0,
-                trick_cards: 0,
-                value: 0,
-                flag: TranspositionFlag::Exact,
-                bestcard: 0,
-            });
-        }
-        Self { data }
-    }
-
-    pub fn write(
-        &mut self,
-        position: &Position,
-        mapped_hash: usize,
-        alpha: u8,
-        beta: u8,
-        value: (u32, u8),
-    ) {
+    /// Entry for a `search` result. The value is stored as the points the
+    /// declarer makes from `position` on.
+    pub fn from_search(position: &Position, alpha: u8, beta: u8, value: (u32, u8)) -> Self {
         let flag = match value.1 {
             x if x <= alpha => TranspositionFlag::Upper,
             x if x >= beta => TranspositionFlag::Lower,
@@ -103,85 +143,185 @@ impl TranspositionTable {
             0
         };
 
-        let entry = TranspositionEntry {
+        TranspositionEntry::occupied(position, stored_val, value.0, flag)
+    }
+
+    /// Entry for a `search_optimum` result. The score is stored as is.
+    pub fn from_optimum(position: &Position, alpha: i16, beta: i16, value: (u32, i16)) -> Self {
+        // optimum mode uses i16 scores directly
+        let flag = match value.1 {
+            x if x <= alpha => TranspositionFlag::Upper,
+            x if x >= beta => TranspositionFlag::Lower,
+            _ => TranspositionFlag::Exact,
+        };
+
+        // For Optimum Search, score is position-dependent (depth included in score),
+        // but 'depth' is fixed for a given card configuration in Skat.
+        // So we can store absolute score.
+        TranspositionEntry::occupied(position, value.1, value.0, flag)
+    }
+
+    fn occupied(position: &Position, value: i16, bestcard: u32, flag: TranspositionFlag) -> Self {
+        TranspositionEntry {
             occupied: true,
             player: position.player,
             left_cards: position.left_cards,
             right_cards: position.right_cards,
             declarer_cards: position.declarer_cards,
             trick_cards: position.trick_cards,
-            value: stored_val,
-            bestcard: value.0,
+            value,
+            bestcard,
             flag,
-        };
+            depth: position.get_all_unplayed_cards().count_ones() as u8,
+            age: 0,
+        }
+    }
 
-        let old = &self.data[mapped_hash];
-        let replace = if !old.occupied {
-            true
-        } else {
-            entry.flag == TranspositionFlag::Exact || old.flag != TranspositionFlag::Exact
-        };
+    pub fn matches(&self, position: &Position) -> bool {
+        self.player == position.player
+            && self.left_cards == position.left_cards
+            && self.right_cards == position.right_cards
+            && self.declarer_cards == position.declarer_cards
+            && self.trick_cards == position.trick_cards
+    }
+
+    /// Hash of the state key, equal to `Position::get_hash` of the position.
+    pub fn key_hash(&self) -> u64 {
+        get_hash(
+            self.player,
+            self.declarer_cards,
+            self.left_cards,
+            self.right_cards,
+            self.trick_cards,
+        )
+    }
+
+    fn is_same_position(&self, other: &TranspositionEntry) -> bool {
+        self.player == other.player
+            && self.left_cards == other.left_cards
+            && self.right_cards == other.right_cards
+            && self.declarer_cards == other.declarer_cards
+            && self.trick_cards == other.trick_cards
+    }
+}
 
-        if replace {
-            self.data[mapped_hash] = entry;
+// -----------------------------------------------------------------------------
+// TT STORE
+// -----------------------------------------------------------------------------
+
+/// Table access of the search functions. Implemented by `TranspositionTable`
+/// and by references to a `SharedTranspositionTable`, so that every search
+/// thread can hold its own `&mut` handle to the same shared table.
+pub trait TranspositionStore {
+    fn probe(&self, position: &Position, cnt: &mut Counters) -> Option<TranspositionEntry>;
+    fn store(&mut self, hash: u64, entry: TranspositionEntry);
+
+    /// Endgame table consulted by the search next to the transposition table.
+    fn endgame(&self) -> Option<&'static EndgameTable> {
+        None
+    }
+}
+
+// -----------------------------------------------------------------------------
+// TT TABLE
+// -----------------------------------------------------------------------------
+
+#[derive(Clone)]
+pub struct TranspositionTable {
+    /// Buckets of `BUCKET_SIZE` consecutive entries.
+    pub data: Vec<TranspositionEntry>,
+    mask: u64,
+    policy: ReplacementPolicy,
+    age: u8,
+    endgame: Option<&'static EndgameTable>,
+}
+
+impl TranspositionTable {
+    /// Table with the default size of `TT_SIZE` entries.
+    pub fn new() -> Self {
+        TranspositionTable::with_capacity(TT_SIZE, ReplacementPolicy::DepthPreferred)
+    }
+
+    /// Table with at most `entries` entries (rounded down to a power of two,
+    /// at least one bucket).
+    pub fn with_capacity(entries: usize, policy: ReplacementPolicy) -> Self {
+        let buckets = prev_power_of_two(entries / BUCKET_SIZE);
+        Self {
+            data: vec![TranspositionEntry::empty(); buckets * BUCKET_SIZE],
+            mask: (buckets - 1) as u64,
+            policy,
+            age: 0,
+            endgame: Some(EndgameTable::global()),
         }
     }
 
+    /// Sets the endgame table used next to this table (by default the global
+    /// one), `None` searches endgames like all other positions.
+    pub fn set_endgame(&mut self, endgame: Option<&'static EndgameTable>) {
+        self.endgame = endgame;
+    }
+
+    /// Table using at most `megabytes` MiB.
+    pub fn with_megabytes(megabytes: usize, policy: ReplacementPolicy) -> Self {
+        TranspositionTable::with_capacity(entries_for_megabytes(megabytes), policy)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn policy(&self) -> ReplacementPolicy {
+        self.policy
+    }
+
+    /// Starts a new search generation. With `ReplacementPolicy::AgePreferred`
+    /// the entries of earlier generations are evicted first.
+    pub fn new_search(&mut self) {
+        self.age = self.age.wrapping_add(1);
+    }
+
+    pub fn write(&mut self, position: &Position, hash: u64, alpha: u8, beta: u8, value: (u32, u8)) {
+        self.store(
+            hash,
+            TranspositionEntry::from_search(position, alpha, beta, value),
+        );
+    }
+
     pub fn read(&self, position: &Position, cnt: &mut Counters) -> Option<&TranspositionEntry> {
-        let candidate = &self.data[position.get_hash()];
+        let bucket = self.bucket(position.get_hash());
+        let mut occupied = false;
 
-        if !candidate.occupied {
-            None
-        } else if candidate.matches(position) {
-            cnt.inc_reads();
-            Some(candidate)
-        } else {
+        for candidate in bucket {
+            if candidate.occupied {
+                if candidate.matches(position) {
+                    cnt.inc_reads();
+                    return Some(candidate);
+                }
+                occupied = true;
+            }
+        }
+
+        if occupied {
             cnt.inc_collisions();
-            None
+        } else {
+            cnt.inc_misses();
         }
+        None
     }
 
     // New methods for Optimum Search (Score based i16)
     pub fn write_optimum(
         &mut self,
         position: &Position,
-        mapped_hash: usize,
+        hash: u64,
         alpha: i16,
         beta: i16,
         value: (u32, i16),
     ) {
-        // optimum mode uses i16 scores directly
-        let flag = match value.1 {
-            x if x <= alpha => TranspositionFlag::Upper,
-            x if x >= beta => TranspositionFlag::Lower,
-            _ => TranspositionFlag::Exact,
-        };
-
-        // For Optimum Search, score is position-dependent (depth included in score),
-        // but 'depth' is fixed for a given card configuration in Skat.
-        // So we can store absolute score.
-        let entry = TranspositionEntry {
-            occupied: true,
-            player: position.player,
-            left_cards: position.left_cards,
-            right_cards: position.right_cards,
-            declarer_cards: position.declarer_cards,
-            trick_cards: position.trick_cards,
-            value: value.1,
-            bestcard: value.0,
-            flag,
-        };
-
-        let old = &self.data[mapped_hash];
-        let replace = if !old.occupied {
-            true
-        } else {
-            entry.flag == TranspositionFlag::Exact || old.flag != TranspositionFlag::Exact
-        };
-
-        if replace {
-            self.data[mapped_hash] = entry;
-        }
+        self.store(
+            hash,
+            TranspositionEntry::from_optimum(position, alpha, beta, value),
+        );
     }
 
     pub fn read_optimum(
@@ -193,15 +333,510 @@ impl TranspositionTable {
         self.read(position, cnt)
     }
 
+    /// Empties all slots, keeping the allocation.
+    pub fn clear(&mut self) {
+        for entry in self.data.iter_mut() {
+            entry.occupied = false;
+        }
+    }
+
     pub fn get_occupied_slots(&self) -> usize {
         self.data.iter().filter(|e| e.occupied).count()
     }
 
+    /// Occupied entries.
+    pub fn entries(&self) -> impl Iterator<Item = &TranspositionEntry> {
+        self.data.iter().filter(|e| e.occupied)
+    }
+
+    /// Stores an entry (e.g. loaded from a file) at the slot of its state key.
+    pub fn insert(&mut self, entry: TranspositionEntry) {
+        self.store(entry.key_hash(), entry);
+    }
+
+    /// Writes all occupied entries in the binary table format.
+    pub fn save<W: Write>(&self, key: TableKey, writer: W) -> io::Result<usize> {
+        let entries: Vec<TranspositionEntry> = self.entries().copied().collect();
+        write_entries(TT_FILE_MAGIC, key, &entries, writer)?;
+        Ok(entries.len())
+    }
+
+    /// Inserts the entries of a table file written by `save` for the same key.
+    pub fn load<R: Read>(&mut self, key: TableKey, reader: R) -> io::Result<usize> {
+        let entries = read_entries(TT_FILE_MAGIC, key, reader)?;
+        for entry in &entries {
+            self.insert(*entry);
+        }
+        Ok(entries.len())
+    }
+
     pub fn add_entries(&mut self, other: TranspositionTable) {
-        for i in 0..TT_SIZE {
+        if other.data.len() != self.data.len() {
+            return;
+        }
+        for i in 0..self.data.len() {
             if !self.data[i].occupied && other.data[i].occupied {
                 self.data[i] = other.data[i];
             }
         }
     }
+
+    fn bucket(&self, hash: u64) -> &[TranspositionEntry] {
+        let start = get_mapped_hash(hash, self.mask) * BUCKET_SIZE;
+        &self.data[start..start + BUCKET_SIZE]
+    }
+
+    fn bucket_mut(&mut self, hash: u64) -> &mut [TranspositionEntry] {
+        let start = get_mapped_hash(hash, self.mask) * BUCKET_SIZE;
+        &mut self.data[start..start + BUCKET_SIZE]
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TranspositionStore for TranspositionTable {
+    fn probe(&self, position: &Position, cnt: &mut Counters) -> Option<TranspositionEntry> {
+        self.read(position, cnt).copied()
+    }
+
+    fn store(&mut self, hash: u64, mut entry: TranspositionEntry) {
+        let policy = self.policy;
+        let age = self.age;
+        entry.age = age;
+
+        let bucket = self.bucket_mut(hash);
+
+        // The same position is updated in place; exact values are only
+        // replaced by exact values.
+        if let Some(slot) = bucket
+            .iter_mut()
+            .find(|e| e.occupied && e.is_same_position(&entry))
+        {
+            if entry.flag == TranspositionFlag::Exact || slot.flag != TranspositionFlag::Exact {
+                *slot = entry;
+            }
+            return;
+        }
+
+        let victim = match bucket.iter().position(|e| !e.occupied) {
+            Some(i) => i,
+            None => (0..BUCKET_SIZE)
+                .min_by_key(|i| policy.priority(&bucket[*i], age))
+                .unwrap_or(0),
+        };
+
+        // Keep a more valuable entry if the new one is a shallow bound
+        if bucket[victim].occupied
+            && policy.priority(&entry, age) < policy.priority(&bucket[victim], age)
+        {
+            return;
+        }
+
+        bucket[victim] = entry;
+    }
+
+    fn endgame(&self) -> Option<&'static EndgameTable> {
+        self.endgame
+    }
+}
+
+// -----------------------------------------------------------------------------
+// SHARED TT TABLE
+// -----------------------------------------------------------------------------
+
+const SHARD_BITS: u32 = 8;
+const SHARDS: usize = 1 << SHARD_BITS;
+
+/// Transposition table for parallel searches. The table is split into shards
+/// with a lock each; the low bits of the hash select the shard, the other
+/// bits the bucket within the shard.
+pub struct SharedTranspositionTable {
+    shards: Vec<Mutex<TranspositionTable>>,
+    endgame: Option<&'static EndgameTable>,
+}
+
+impl SharedTranspositionTable {
+    pub fn new() -> Self {
+        SharedTranspositionTable::with_capacity(TT_SIZE, ReplacementPolicy::DepthPreferred)
+    }
+
+    pub fn with_capacity(entries: usize, policy: ReplacementPolicy) -> Self {
+        let shards = (0..SHARDS)
+            .map(|_| Mutex::new(TranspositionTable::with_capacity(entries / SHARDS, policy)))
+            .collect();
+        Self {
+            shards,
+            endgame: Some(EndgameTable::global()),
+        }
+    }
+
+    /// See `TranspositionTable::set_endgame`.
+    pub fn set_endgame(&mut self, endgame: Option<&'static EndgameTable>) {
+        self.endgame = endgame;
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().capacity())
+            .sum()
+    }
+
+    fn shard(&self, hash: u64) -> std::sync::MutexGuard<'_, TranspositionTable> {
+        self.shards[hash as usize % SHARDS].lock().unwrap()
+    }
+
+    /// Starts a new search generation in all shards.
+    pub fn new_search(&self) {
+        for shard in &self.shards {
+            shard.lock().unwrap().new_search();
+        }
+    }
+
+    /// Empties all slots, keeping the allocation.
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            shard.lock().unwrap().clear();
+        }
+    }
+
+    pub fn get_occupied_slots(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().get_occupied_slots())
+            .sum()
+    }
+}
+
+impl Default for SharedTranspositionTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SharedTranspositionTable {
+    /// Copies of the occupied entries of all shards.
+    pub fn entries(&self) -> Vec<TranspositionEntry> {
+        self.shards
+            .iter()
+            .flat_map(|shard| shard.lock().unwrap().entries().copied().collect::<Vec<_>>())
+            .collect()
+    }
+
+    pub fn insert(&self, entry: TranspositionEntry) {
+        let mut shared = self;
+        shared.store(entry.key_hash(), entry);
+    }
+}
+
+impl TranspositionStore for &SharedTranspositionTable {
+    fn probe(&self, position: &Position, cnt: &mut Counters) -> Option<TranspositionEntry> {
+        let hash = position.get_hash();
+        let shard = self.shard(hash);
+        let bucket = shard.bucket(hash >> SHARD_BITS);
+
+        let mut occupied = false;
+        for candidate in bucket {
+            if candidate.occupied {
+                if candidate.matches(position) {
+                    cnt.inc_reads();
+                    return Some(*candidate);
+                }
+                occupied = true;
+            }
+        }
+
+        if occupied {
+            cnt.inc_collisions();
+        } else {
+            cnt.inc_misses();
+        }
+        None
+    }
+
+    fn store(&mut self, hash: u64, entry: TranspositionEntry) {
+        self.shard(hash).store(hash >> SHARD_BITS, entry);
+    }
+
+    fn endgame(&self) -> Option<&'static EndgameTable> {
+        self.endgame
+    }
+}
+
+// -----------------------------------------------------------------------------
+// PERSISTENCE
+// -----------------------------------------------------------------------------
+
+/// Version of the binary table format. Files of other versions are stale and
+/// rejected on load.
+pub const TT_FILE_VERSION: u32 = 1;
+
+const TT_FILE_MAGIC: &[u8; 4] = b"SKTT";
+
+/// Games whose positions have the same values and can share a table file.
+///
+/// Table values count the points the declarer makes from a position on, so
+/// they depend on the trump and trick rules of the game only. All Null
+/// variants play alike; in Ramsch the Skat goes to the winner of the last
+/// trick and is part of the key.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TableKey {
+    pub game: Game,
+    pub skat: u32,
+}
+
+impl TableKey {
+    pub fn of(context: &GameContext) -> TableKey {
+        match context.game_type() {
+            g if g.is_null() => TableKey {
+                game: Game::Null,
+                skat: 0,
+            },
+            Game::Ramsch => TableKey {
+                game: Game::Ramsch,
+                skat: context.get_skat(),
+            },
+            game => TableKey { game, skat: 0 },
+        }
+    }
+}
+
+// Record layout (little endian, 25 bytes): player, declarer/left/right/trick
+// cards, value, flag, best card, depth. The age is not persisted.
+const RECORD_SIZE: usize = 25;
+
+/// Writes a version header and the entries.
+pub(crate) fn write_entries<W: Write>(
+    magic: &[u8; 4],
+    key: TableKey,
+    entries: &[TranspositionEntry],
+    writer: W,
+) -> io::Result<()> {
+    let mut writer = io::BufWriter::new(writer);
+
+    writer.write_all(magic)?;
+    writer.write_all(&TT_FILE_VERSION.to_le_bytes())?;
+    writer.write_all(&[key.game as u8])?;
+    writer.write_all(&key.skat.to_le_bytes())?;
+    writer.write_all(&(entries.len() as u64).to_le_bytes())?;
+
+    let mut record = [0u8; RECORD_SIZE];
+    for entry in entries {
+        record[0] = entry.player as u8;
+        record[1..5].copy_from_slice(&entry.declarer_cards.to_le_bytes());
+        record[5..9].copy_from_slice(&entry.left_cards.to_le_bytes());
+        record[9..13].copy_from_slice(&entry.right_cards.to_le_bytes());
+        record[13..17].copy_from_slice(&entry.trick_cards.to_le_bytes());
+        record[17..19].copy_from_slice(&entry.value.to_le_bytes());
+        record[19] = match entry.flag {
+            TranspositionFlag::Exact => 0,
+            TranspositionFlag::Upper => 1,
+            TranspositionFlag::Lower => 2,
+        };
+        record[20..24].copy_from_slice(&entry.bestcard.to_le_bytes());
+        record[24] = entry.depth;
+        writer.write_all(&record)?;
+    }
+
+    writer.flush()
+}
+
+/// Reads entries written by `write_entries`. Fails on a different magic,
+/// version or key.
+pub(crate) fn read_entries<R: Read>(
+    magic: &[u8; 4],
+    key: TableKey,
+    reader: R,
+) -> io::Result<Vec<TranspositionEntry>> {
+    let mut reader = io::BufReader::new(reader);
+
+    let mut header = [0u8; 21];
+    reader.read_exact(&mut header)?;
+
+    if &header[0..4] != magic {
+        return Err(invalid_data("Not a table file of this kind"));
+    }
+    if u32::from_le_bytes(read_array(&header[4..8])) != TT_FILE_VERSION {
+        return Err(invalid_data("Stale table file version"));
+    }
+    if header[8] != key.game as u8 || u32::from_le_bytes(read_array(&header[9..13])) != key.skat {
+        return Err(invalid_data("Table file belongs to another game"));
+    }
+    let count = u64::from_le_bytes(read_array(&header[13..21])) as usize;
+
+    let mut entries = Vec::with_capacity(count);
+    let mut record = [0u8; RECORD_SIZE];
+    for _ in 0..count {
+        reader.read_exact(&mut record)?;
+        entries.push(TranspositionEntry {
+            occupied: true,
+            player: match record[0] {
+                0 => Player::Declarer,
+                1 => Player::Left,
+                2 => Player::Right,
+                _ => return Err(invalid_data("Invalid player in table file")),
+            },
+            declarer_cards: u32::from_le_bytes(read_array(&record[1..5])),
+            left_cards: u32::from_le_bytes(read_array(&record[5..9])),
+            right_cards: u32::from_le_bytes(read_array(&record[9..13])),
+            trick_cards: u32::from_le_bytes(read_array(&record[13..17])),
+            value: i16::from_le_bytes(read_array(&record[17..19])),
+            flag: match record[19] {
+                0 => TranspositionFlag::Exact,
+                1 => TranspositionFlag::Upper,
+                2 => TranspositionFlag::Lower,
+                _ => return Err(invalid_data("Invalid flag in table file")),
+            },
+            bestcard: u32::from_le_bytes(read_array(&record[20..24])),
+            depth: record[24],
+            age: 0,
+        });
+    }
+
+    Ok(entries)
+}
+
+pub(crate) fn write_entries_to_file(
+    path: &str,
+    key: TableKey,
+    entries: &[TranspositionEntry],
+) -> io::Result<()> {
+    write_entries(TT_FILE_MAGIC, key, entries, std::fs::File::create(path)?)
+}
+
+pub(crate) fn read_entries_from_file(
+    path: &str,
+    key: TableKey,
+) -> io::Result<Vec<TranspositionEntry>> {
+    read_entries(TT_FILE_MAGIC, key, std::fs::File::open(path)?)
+}
+
+fn read_array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut array = [0u8; N];
+    array.copy_from_slice(bytes);
+    array
+}
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// -----------------------------------------------------------------------------
+// SIZING
+// -----------------------------------------------------------------------------
+
+/// Number of entries fitting into `megabytes` MiB.
+pub fn entries_for_megabytes(megabytes: usize) -> usize {
+    megabytes * 1024 * 1024 / std::mem::size_of::<TranspositionEntry>()
+}
+
+fn prev_power_of_two(n: usize) -> usize {
+    if n <= 1 {
+        1
+    } else {
+        1 << (usize::BITS - 1 - n.leading_zeros())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: u32, depth: u8) -> TranspositionEntry {
+        let mut entry = TranspositionEntry::empty();
+        entry.occupied = true;
+        entry.left_cards = key;
+        entry.depth = depth;
+        entry
+    }
+
+    fn contains(tt: &TranspositionTable, key: u32) -> bool {
+        tt.data.iter().any(|e| e.occupied && e.left_cards == key)
+    }
+
+    #[test]
+    fn test_capacity_is_power_of_two() {
+        let tt = TranspositionTable::with_capacity(1000, ReplacementPolicy::DepthPreferred);
+        assert_eq!(tt.capacity(), 512);
+        assert_eq!(tt.mask, 127);
+
+        let tiny = TranspositionTable::with_capacity(0, ReplacementPolicy::AgePreferred);
+        assert_eq!(tiny.capacity(), BUCKET_SIZE);
+
+        let mb = TranspositionTable::with_megabytes(1, ReplacementPolicy::DepthPreferred);
+        assert!(mb.capacity() * std::mem::size_of::<TranspositionEntry>() <= 1024 * 1024);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let key = TableKey {
+            game: Game::Grand,
+            skat: 0,
+        };
+        let mut tt = TranspositionTable::with_capacity(64, ReplacementPolicy::DepthPreferred);
+        for left_cards in 1..=10 {
+            tt.insert(entry(left_cards, 12));
+        }
+
+        let mut file = Vec::new();
+        assert_eq!(tt.save(key, &mut file).unwrap(), 10);
+
+        let mut loaded = TranspositionTable::with_capacity(1024, ReplacementPolicy::DepthPreferred);
+        assert_eq!(loaded.load(key, file.as_slice()).unwrap(), 10);
+        for left_cards in 1..=10 {
+            assert!(contains(&loaded, left_cards));
+        }
+
+        let null = TableKey {
+            game: Game::Null,
+            skat: 0,
+        };
+        assert!(loaded.load(null, file.as_slice()).is_err());
+
+        // Stale version
+        file[4..8].copy_from_slice(&(TT_FILE_VERSION + 1).to_le_bytes());
+        assert!(loaded.load(key, file.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_depth_preferred_replacement() {
+        // A single bucket: all positions collide.
+        let mut tt =
+            TranspositionTable::with_capacity(BUCKET_SIZE, ReplacementPolicy::DepthPreferred);
+        for key in 1..=4 {
+            tt.store(0, entry(key, 20 + key as u8));
+        }
+
+        // A shallower position does not evict anything
+        tt.store(0, entry(5, 3));
+        assert!(!contains(&tt, 5));
+
+        // A deeper one evicts the shallowest
+        tt.store(0, entry(6, 29));
+        assert!(contains(&tt, 6));
+        assert!(!contains(&tt, 1));
+        assert_eq!(tt.get_occupied_slots(), BUCKET_SIZE);
+    }
+
+    #[test]
+    fn test_age_preferred_replacement() {
+        let mut tt =
+            TranspositionTable::with_capacity(BUCKET_SIZE, ReplacementPolicy::AgePreferred);
+        for key in 1..=4 {
+            tt.store(0, entry(key, 20 + key as u8));
+        }
+
+        tt.store(0, entry(5, 3));
+        assert!(!contains(&tt, 5));
+
+        // Entries of the previous search go first, the shallowest of them
+        tt.new_search();
+        tt.store(0, entry(5, 3));
+        assert!(contains(&tt, 5));
+        assert!(!contains(&tt, 1));
+    }
 }
@@ -1,8 +1,50 @@
 #![allow(dead_code)]
 
+use rand::seq::SliceRandom;
+use rand::Rng;
 use skat_aug23::skat::builder::GameContextBuilder;
 use skat_aug23::skat::context::GameContext;
 use skat_aug23::skat::defs::Player;
+use skat_aug23::skat::position::Position;
+use std::time::{Duration, Instant};
+
+/// Shuffles the deck and deals ten cards each to declarer, left and right;
+/// the Skat stays undealt.
+pub fn random_hands<R: Rng>(rng: &mut R) -> [u32; 3] {
+    let mut deck: Vec<u8> = (0..32).collect();
+    deck.shuffle(rng);
+
+    let mut cards = [0u32; 3];
+    for (i, card) in deck[0..30].iter().enumerate() {
+        cards[i / 10] |= 1 << card;
+    }
+    cards
+}
+
+/// Plays the game of `ctx` from the start with the card `next` chooses in
+/// every position. A lost Null game ends with the declarer's first trick.
+pub fn play_line<F>(ctx: &GameContext, mut next: F)
+where
+    F: FnMut(&Position) -> u32,
+{
+    let mut position = ctx.create_initial_position();
+    while position.get_legal_moves() != 0 {
+        let card = next(&position);
+        position = position.make_move(card, ctx);
+
+        if ctx.game_type().is_null() && position.declarer_points > 0 {
+            break;
+        }
+    }
+}
+
+/// Runs `f` and adds its time to `total`.
+pub fn timed<T, F: FnOnce() -> T>(total: &mut Duration, f: F) -> T {
+    let now = Instant::now();
+    let result = f();
+    *total += now.elapsed();
+    result
+}
 
 pub fn one_trick_rank_in_one_suit() -> (GameContext, u8) {
     let p = GameContextBuilder::new_farbspiel()
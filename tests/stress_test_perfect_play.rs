@@ -1,10 +1,17 @@
+mod problems;
+
 use rand::prelude::*;
-use skat_aug23::extensions::solver::{solve_optimum_from_position, OptimumMode};
+use rand::rngs::StdRng;
+use skat_aug23::extensions::solver::{
+    solve_all_cards_from_position, solve_all_cards_mtdf_from_position, solve_optimum_from_position,
+    OptimumMode,
+};
 use skat_aug23::skat::context::GameContext;
 use skat_aug23::skat::defs::{Game, Player};
 use skat_aug23::skat::engine::SkatEngine;
 use skat_aug23::skat::rules::get_legal_moves;
 use skat_aug23::traits::StringConverter;
+use std::time::Duration;
 
 #[test]
 fn stress_test_perfect_play() {
@@ -49,9 +56,70 @@ fn stress_test_perfect_play() {
 
             position = position.make_move(perfect_card, &ctx);
 
-            if game_type == Game::Null && position.declarer_points > 0 {
+            if game_type.is_null() && position.declarer_points > 0 {
                 break;
             }
         }
     }
 }
+
+/// Compares the full-window and the MTD(f) value search of the optimum solver
+/// on the perfect play of seeded random deals. Both must find the same values
+/// and cards; node counts and times of the value search are printed.
+#[test]
+fn bench_mtdf_perfect_play() {
+    let mut rng = StdRng::seed_from_u64(2023);
+
+    let mut full_nodes = 0u64;
+    let mut mtdf_nodes = 0u64;
+    let mut full_time = Duration::ZERO;
+    let mut mtdf_time = Duration::ZERO;
+
+    for _ in 0..5 {
+        let cards = problems::random_hands(&mut rng);
+
+        let game_types = [Game::Suit, Game::Grand, Game::Null];
+        let game_type = game_types[rng.gen_range(0..3)];
+
+        let ctx = GameContext::create(cards[0], cards[1], cards[2], game_type, Player::Declarer);
+        let mut full = SkatEngine::new(ctx, None);
+        let mut mtdf = SkatEngine::new(ctx, None);
+
+        problems::play_line(&ctx, |position| {
+            let full_values = problems::timed(&mut full_time, || {
+                solve_all_cards_from_position(&mut full, position, 0, 120)
+            });
+            let mtdf_values = problems::timed(&mut mtdf_time, || {
+                solve_all_cards_mtdf_from_position(&mut mtdf, position)
+            });
+
+            full_nodes += full_values.counters.iters as u64;
+            mtdf_nodes += mtdf_values.counters.iters as u64;
+
+            let values = |r: &Vec<(u32, u32, u8)>| -> Vec<(u32, u8)> {
+                r.iter().map(|(card, _, value)| (*card, *value)).collect()
+            };
+            assert_eq!(values(&full_values.results), values(&mtdf_values.results));
+
+            let full_optimum =
+                solve_optimum_from_position(&mut full, position, OptimumMode::BestValue).unwrap();
+            let mtdf_optimum =
+                solve_optimum_from_position(&mut mtdf, position, OptimumMode::BestValueMtdf)
+                    .unwrap();
+            assert_eq!(full_optimum, mtdf_optimum);
+
+            full_optimum.0
+        });
+    }
+
+    println!(
+        "Full window: {} nodes, {} ms",
+        full_nodes,
+        full_time.as_millis()
+    );
+    println!(
+        "MTD(f):      {} nodes, {} ms",
+        mtdf_nodes,
+        mtdf_time.as_millis()
+    );
+}
@@ -0,0 +1,33 @@
+extern crate skat_aug23;
+
+use std::process::Command;
+
+use skat_aug23::extensions::solver::solve;
+use skat_aug23::skat::engine::SkatEngine;
+
+mod problems;
+
+/// value-calc credits the Skat of a full deal to the declarer only once, as
+/// the solver does.
+#[test]
+fn value_calc_counts_the_skat_once() {
+    let (context, _) = problems::ten_tricks();
+    let expected = solve(&mut SkatEngine::new(context, None)).best_value;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_skat_aug23"))
+        .args([
+            "value-calc",
+            "--context",
+            "tests/contexts/value_full_deal.json",
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("Auto-Skat: Determined 7 points in Skat."));
+    assert!(
+        stdout.contains(&format!("Value: {},", expected)),
+        "{}",
+        stdout
+    );
+}
@@ -0,0 +1,66 @@
+extern crate skat_aug23;
+
+mod problems;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use skat_aug23::extensions::solver::solve_all_cards_from_position;
+use skat_aug23::skat::context::GameContext;
+use skat_aug23::skat::counters::Counters;
+use skat_aug23::skat::defs::{Game, Player};
+use skat_aug23::skat::engine::SkatEngine;
+use std::time::Duration;
+
+/// Solves seeded random deals with and without the endgame table. The values
+/// of all cards must agree along the principal variation; node counts of
+/// both are printed.
+#[test]
+fn endgame_table_keeps_values() {
+    let mut rng = StdRng::seed_from_u64(14);
+
+    let mut with_table = Counters::new();
+    let mut without_table = Counters::new();
+    let mut with_time = Duration::ZERO;
+    let mut without_time = Duration::ZERO;
+
+    for game_type in [Game::Suit, Game::Grand, Game::Null, Game::Suit, Game::Grand] {
+        let cards = problems::random_hands(&mut rng);
+        let ctx = GameContext::create(cards[0], cards[1], cards[2], game_type, Player::Declarer);
+        let mut engine = SkatEngine::new(ctx, None);
+        let mut plain = SkatEngine::new(ctx, None);
+        plain.set_endgame_table(false);
+
+        problems::play_line(&ctx, |position| {
+            let expected = problems::timed(&mut without_time, || {
+                solve_all_cards_from_position(&mut plain, position, 0, 120)
+            });
+            let actual = problems::timed(&mut with_time, || {
+                solve_all_cards_from_position(&mut engine, position, 0, 120)
+            });
+
+            without_table.add(expected.counters);
+            with_table.add(actual.counters);
+
+            let values = |r: &Vec<(u32, u32, u8)>| -> Vec<(u32, u8)> {
+                r.iter().map(|(card, _, value)| (*card, *value)).collect()
+            };
+            assert_eq!(values(&expected.results), values(&actual.results));
+
+            engine.search(position, &mut Counters::new(), 0, 120).0
+        });
+    }
+
+    println!(
+        "Without endgame table: {} nodes, {} ms",
+        without_table.iters,
+        without_time.as_millis()
+    );
+    println!(
+        "With endgame table:    {} nodes, {} ms ({} reads, {} writes)",
+        with_table.iters,
+        with_time.as_millis(),
+        with_table.endgame_reads,
+        with_table.endgame_writes
+    );
+    assert!(with_table.iters < without_table.iters);
+}
@@ -0,0 +1,60 @@
+extern crate skat_aug23;
+
+use skat_aug23::skat::context::GameContext;
+use skat_aug23::skat::counters::Counters;
+use skat_aug23::skat::engine::SkatEngine;
+use skat_aug23::skat::search::MoveOrdering;
+
+mod problems;
+
+/// Searches the problem suite with static and dynamic move ordering. Values
+/// must agree, the dynamic ordering must not search more nodes in total.
+#[test]
+fn dynamic_ordering_reduces_nodes() {
+    let suite = vec![
+        problems::five_tricks(),
+        problems::six_tricks(),
+        problems::seven_tricks(),
+        problems::eight_tricks(),
+        problems::ten_tricks(),
+        problems::null_1(),
+        problems::null_2(),
+        problems::null_3(),
+    ];
+
+    let mut total_static = Counters::new();
+    let mut total_dynamic = Counters::new();
+
+    for (context, _) in suite {
+        let (static_value, static_cnt) = solve(context, MoveOrdering::Static);
+        let (dynamic_value, dynamic_cnt) = solve(context, MoveOrdering::Dynamic);
+
+        assert_eq!(static_value, dynamic_value);
+        println!(
+            "Nodes: {:>9} static, {:>9} dynamic, first move cutoffs {:.1}% / {:.1}%",
+            static_cnt.iters,
+            dynamic_cnt.iters,
+            static_cnt.first_move_rate() * 100.0,
+            dynamic_cnt.first_move_rate() * 100.0
+        );
+
+        total_static.add(static_cnt);
+        total_dynamic.add(dynamic_cnt);
+    }
+
+    println!(
+        "Total nodes: {} static, {} dynamic ({} killer cutoffs)",
+        total_static.iters, total_dynamic.iters, total_dynamic.killer_breaks
+    );
+    assert!(total_dynamic.iters <= total_static.iters);
+}
+
+fn solve(context: GameContext, ordering: MoveOrdering) -> (u8, Counters) {
+    let mut engine = SkatEngine::new(context, None);
+    engine.set_move_ordering(ordering);
+
+    let mut cnt = Counters::new();
+    let position = engine.create_initial_position();
+    let (_, value) = engine.search(&position, &mut cnt, 0, 120);
+    (value, cnt)
+}
@@ -0,0 +1,76 @@
+extern crate skat_aug23;
+
+use skat_aug23::skat::context::GameContext;
+use skat_aug23::skat::control::{CancelToken, SearchControl, POLL_INTERVAL};
+use skat_aug23::skat::counters::Counters;
+use skat_aug23::skat::engine::SkatEngine;
+
+mod problems;
+
+/// Without limits the anytime search is the complete search.
+#[test]
+fn anytime_search_without_limits_is_exact() {
+    let (context, _) = problems::ten_tricks();
+    let value = exact_value(context);
+
+    let mut engine = SkatEngine::new(context, None);
+    let position = engine.create_initial_position();
+
+    let variation =
+        engine.search_anytime(&position, &mut Counters::new(), &SearchControl::unlimited());
+
+    assert!(variation.is_complete());
+    assert_eq!(variation.value, value);
+}
+
+/// An exhausted node budget returns a legal card of a shallower search, and
+/// the interrupted search leaves no wrong values in the transposition table.
+#[test]
+fn node_budget_interrupts_search() {
+    let (context, _) = problems::ten_tricks();
+    let value = exact_value(context);
+
+    for threads in [1, 4] {
+        let mut engine = SkatEngine::new(context, None);
+        engine.set_threads(threads).unwrap();
+        let position = engine.create_initial_position();
+
+        let control = SearchControl::unlimited().with_node_budget(4 * POLL_INTERVAL as u64);
+        let variation = engine.search_anytime(&position, &mut Counters::new(), &control);
+
+        assert!(control.is_stopped());
+        assert!(!variation.is_complete());
+        assert!(position.get_legal_moves() & variation.best_card() != 0);
+
+        let control = SearchControl::unlimited().with_node_budget(4 * POLL_INTERVAL as u64);
+        let result = engine.search_controlled(&position, &mut Counters::new(), 0, 120, &control);
+        assert_eq!(result, None);
+
+        let (_, exact) = engine.search(&position, &mut Counters::new(), 0, 120);
+        assert_eq!(exact, value);
+    }
+}
+
+/// A search cancelled before it starts still returns a card to play.
+#[test]
+fn cancelled_search_returns_a_card() {
+    let (context, _) = problems::ten_tricks();
+    let mut engine = SkatEngine::new(context, None);
+    let position = engine.create_initial_position();
+
+    let token = CancelToken::new();
+    token.cancel();
+    let control = SearchControl::unlimited().with_cancel_token(token);
+    let variation = engine.search_anytime(&position, &mut Counters::new(), &control);
+
+    assert!(!variation.is_complete());
+    assert_eq!(variation.cards.len(), 1);
+    assert!(position.get_legal_moves() & variation.best_card() != 0);
+    assert_eq!(variation.value, position.declarer_points);
+}
+
+fn exact_value(context: GameContext) -> u8 {
+    let mut engine = SkatEngine::new(context, None);
+    let position = engine.create_initial_position();
+    engine.search(&position, &mut Counters::new(), 0, 120).1
+}
@@ -0,0 +1,56 @@
+extern crate skat_aug23;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use skat_aug23::extensions::agent::{create_seats, AgentSettings};
+use skat_aug23::extensions::cli_playout::generate_random_deal;
+use skat_aug23::pimc::pimc_problem::SamplingMode;
+use skat_aug23::pimc::playout::{playout_with_history, GameTrace};
+use skat_aug23::skat::context::GameContext;
+
+fn deal(seed: u64) -> GameContext {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (context, _, _) = generate_random_deal(
+        "null".to_string(),
+        "declarer".to_string(),
+        SamplingMode::Random,
+        &mut rng,
+    );
+    context
+}
+
+fn cards(context: &GameContext) -> (u32, u32, u32) {
+    (
+        context.declarer_cards(),
+        context.left_cards(),
+        context.right_cards(),
+    )
+}
+
+/// PIMC playout of the deal of `seed` with agents seeded from `seed`.
+fn playout(seed: u64) -> GameTrace {
+    let settings = AgentSettings {
+        null_rules: true,
+        ..AgentSettings::new(4)
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut agents = create_seats("pimc", &settings, &mut rng).unwrap();
+    playout_with_history(deal(seed), &mut agents)
+}
+
+#[test]
+fn random_deal_depends_only_on_seed() {
+    assert_eq!(cards(&deal(17)), cards(&deal(17)));
+    assert_ne!(cards(&deal(17)), cards(&deal(18)));
+}
+
+/// Deal and playout of one seed are repeated exactly.
+#[test]
+fn seeded_playout_is_reproducible() {
+    let first = playout(3);
+    let second = playout(3);
+
+    assert_eq!(first.moves, second.moves);
+    assert_eq!(first.win_probs, second.win_probs);
+    assert_eq!(first.declarer_points, second.declarer_points);
+}
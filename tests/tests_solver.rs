@@ -1,9 +1,13 @@
 extern crate skat_aug23;
 
 use skat_aug23::extensions::skat_solving::{solve_with_skat, AccelerationMode};
-use skat_aug23::extensions::solver::{solve_all_cards, solve_win};
+use skat_aug23::extensions::solver::{solve, solve_all_cards, solve_pv, solve_targets, solve_win};
+use skat_aug23::skat::builder::GameContextBuilder;
+use skat_aug23::skat::counters::Counters;
+use skat_aug23::skat::defs::{Game, Player};
 use skat_aug23::skat::engine::SkatEngine;
-use skat_aug23::traits::{Points, StringConverter};
+use skat_aug23::skat::search::NO_HORIZON;
+use skat_aug23::traits::{BitConverter, Points, StringConverter};
 use std::time::Instant;
 
 mod problems;
@@ -100,3 +104,149 @@ pub fn solve_with_skat_test() {
         );
     }
 }
+
+#[test]
+pub fn solve_with_skat_null_hand_uses_dealt_skat() {
+    let (p, _) = problems::null_1();
+    let dealt_skat = !(p.declarer_cards | p.left_cards | p.right_cards);
+
+    let result = solve_with_skat(
+        p.left_cards,
+        p.right_cards,
+        p.declarer_cards,
+        Game::NullHand,
+        p.start_player,
+        AccelerationMode::NotAccelerating,
+    );
+
+    assert_eq!(result.all_skats.len(), 1);
+    let best = result.best_skat.unwrap();
+    assert_eq!(best.skat_card_1 | best.skat_card_2, dealt_skat);
+}
+
+#[test]
+pub fn solve_targets_grand_schwarz() {
+    let context = GameContextBuilder::new_grand()
+        .cards(Player::Declarer, "CJ SJ HJ DJ CA CT CK CQ C9 C8")
+        .cards(Player::Left, "SA ST SK SQ S9 S8 S7 HA HT HK")
+        .cards(Player::Right, "HQ H9 H8 H7 DA DT DK DQ D9 D8")
+        .turn(Player::Declarer)
+        .build();
+    let mut engine = SkatEngine::new(context, None);
+
+    let result = solve_targets(&mut engine);
+
+    assert!(result.declarer_wins);
+    assert!(result.avoids_schneider);
+    assert!(result.schneider);
+    assert!(result.schwarz);
+}
+
+#[test]
+pub fn solve_targets_grand_schneider_without_schwarz() {
+    let context = GameContextBuilder::new_grand()
+        .cards(Player::Declarer, "SJ HJ DJ CA CT CK CQ C9 C8 C7")
+        .cards(Player::Left, "CJ ST SK SQ S9 S8 S7 HT HK HQ")
+        .cards(Player::Right, "H9 H8 H7 DA DT DK DQ D9 D8 D7")
+        .turn(Player::Declarer)
+        .build();
+    let mut engine = SkatEngine::new(context, None);
+
+    let result = solve_targets(&mut engine);
+
+    assert!(result.declarer_wins);
+    assert!(result.schneider);
+    assert!(!result.schwarz);
+}
+
+#[test]
+pub fn solve_ramsch_minimizes_own_points() {
+    // Leading S7 loses the last trick and the Skat (95 points) to the declarer
+    // seat; leading SA first only costs 25 points.
+    let context = GameContextBuilder::new(Game::Ramsch)
+        .cards(Player::Declarer, "SA S7")
+        .cards(Player::Left, "ST S8")
+        .cards(Player::Right, "SK S9")
+        .turn(Player::Declarer)
+        .build();
+    let mut engine = SkatEngine::new(context, None);
+
+    assert_eq!(solve(&mut engine).best_value, 25);
+
+    let all = solve_all_cards(&mut engine, 0, 120);
+    for (card, _, value) in all.results {
+        let expected = if card == "SA".__bit() { 25 } else { 120 };
+        assert_eq!(value, expected, "{}", card.__str());
+    }
+}
+
+#[test]
+pub fn solve_pv_replays_to_solved_value() {
+    let (context, _) = problems::five_tricks();
+    let expected = solve(&mut SkatEngine::new(context, None)).best_value;
+    let mut engine = SkatEngine::new(context, None);
+
+    let result = solve_pv(&mut engine);
+    let variation = result.variation;
+
+    assert!(variation.is_complete());
+    assert_eq!(variation.value, expected);
+    assert_eq!(variation.cards.len(), 15);
+
+    let mut position = engine.create_initial_position();
+    for card in &variation.cards {
+        assert_ne!(position.get_legal_moves() & card, 0, "{}", card.__str());
+        position = position.make_move(*card, &engine.context);
+    }
+    assert_eq!(position.declarer_points, expected);
+}
+
+#[test]
+pub fn search_iterative_reports_every_trick_depth() {
+    let (context, _) = problems::five_tricks();
+    let expected = solve(&mut SkatEngine::new(context, None)).best_value;
+    let mut engine = SkatEngine::new(context, None);
+    let position = engine.create_initial_position();
+    let mut cnt = Counters::new();
+
+    // The second search reuses the table of the shallow searches
+    for _ in 0..2 {
+        let mut depths = Vec::new();
+        let last = engine.search_iterative(&position, &mut cnt, |v| {
+            depths.push((v.horizon, v.cards.len()));
+        });
+
+        assert_eq!(
+            depths,
+            vec![(1, 3), (2, 6), (3, 9), (4, 12), (NO_HORIZON, 15)]
+        );
+        assert_eq!(last.value, expected);
+        assert_eq!(last.best_card(), last.cards[0]);
+    }
+}
+
+#[test]
+pub fn parallel_search_matches_sequential() {
+    let problem_set = vec![
+        problems::five_tricks().0,
+        problems::seven_tricks().0,
+        problems::null_1().0,
+    ];
+
+    for context in problem_set {
+        let mut sequential = SkatEngine::new(context, None);
+        let mut parallel = SkatEngine::new(context, None);
+        parallel.set_threads(4).unwrap();
+        assert_eq!(parallel.threads(), 4);
+
+        assert_eq!(
+            solve(&mut parallel).best_value,
+            solve(&mut sequential).best_value
+        );
+
+        let expected = solve_all_cards(&mut sequential, 0, 120).results;
+        let actual = solve_all_cards(&mut parallel, 0, 120).results;
+        let values = |r: &Vec<(u32, u32, u8)>| r.iter().map(|x| (x.0, x.2)).collect::<Vec<_>>();
+        assert_eq!(values(&actual), values(&expected));
+    }
+}